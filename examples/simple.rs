@@ -14,8 +14,8 @@ fn main() {
     match CpuTemperature::get() {
         Ok(temp) => {
             println!("✓ Successfully retrieved CPU temperature:");
-            println!("  Celsius: {:.2}°C", temp.celsius);
-            println!("  Fahrenheit: {:.2}°F", temp.fahrenheit);
+            println!("  Celsius: {:.2}°C", temp.celsius());
+            println!("  Fahrenheit: {:.2}°F", temp.fahrenheit());
         }
         Err(error) => {
             eprintln!("✗ Error reading CPU temperature: {}", error);
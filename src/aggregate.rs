@@ -0,0 +1,99 @@
+//! Combining the zones of one read into a single temperature.
+
+use crate::calibration::zone_matches;
+use crate::smoothing::median;
+use crate::{CpuTemperature, TemperatureError, ThermalZoneReading};
+
+/// How [`TemperatureReader::get`](crate::TemperatureReader::get) turns the
+/// zones of a read into one temperature.
+///
+/// The order of zones is up to the firmware, so the first zone can be a
+/// chipset or ambient sensor rather than the CPU. On such machines, the
+/// hottest zone or a named one is the better choice.
+///
+/// # Example
+///
+/// ```
+/// use tunjukin_suhu_cpu_windows::{Aggregate, CpuTemperature, ThermalZoneReading};
+///
+/// let zones = [28.0, 64.0, 46.0].map(|celsius| ThermalZoneReading::new(None, CpuTemperature::from_celsius(celsius)));
+///
+/// assert_eq!(Aggregate::Max.apply(&zones)?.celsius(), 64.0);
+/// assert_eq!(Aggregate::Median.apply(&zones)?.celsius(), 46.0);
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+#[non_exhaustive]
+pub enum Aggregate {
+    /// The first zone, as [`CpuTemperature::get`] returns it.
+    #[default]
+    First,
+    /// The hottest zone.
+    Max,
+    /// The mean of every zone.
+    Mean,
+    /// The median of every zone; the mean of the two middle zones for an
+    /// even number.
+    Median,
+    /// The zone named like a [`Calibration`](crate::Calibration) key, such
+    /// as `TZ01` or `ACPI\ThermalZone\TZ01_0`.
+    Zone(String),
+}
+
+impl Aggregate {
+    /// Combines `zones` into one temperature.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`TemperatureError::NoSensors`] if `zones` is empty, and
+    /// with [`TemperatureError::InvalidArgument`] if no zone has the name of
+    /// [`Aggregate::Zone`].
+    pub fn apply(&self, zones: &[ThermalZoneReading]) -> Result<CpuTemperature, TemperatureError> {
+        if zones.is_empty() {
+            return Err(TemperatureError::NoSensors);
+        }
+
+        let celsius = zones.iter().map(|zone| zone.temperature.celsius());
+        Ok(match self {
+            Aggregate::First => zones[0].temperature.clone(),
+            Aggregate::Max => CpuTemperature::from_celsius(celsius.fold(f64::NEG_INFINITY, f64::max)),
+            Aggregate::Mean => CpuTemperature::from_celsius(celsius.sum::<f64>() / zones.len() as f64),
+            Aggregate::Median => CpuTemperature::from_celsius(median(celsius)),
+            Aggregate::Zone(name) => {
+                zones
+                    .iter()
+                    .find(|zone| zone.instance_name.as_deref().is_some_and(|instance| zone_matches(name, instance)))
+                    .ok_or_else(|| TemperatureError::InvalidArgument(format!("no thermal zone named '{}'", name)))?
+                    .temperature
+                    .clone()
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(name: &str, celsius: f64) -> ThermalZoneReading {
+        ThermalZoneReading::new(Some(name.to_string()), CpuTemperature::from_celsius(celsius))
+    }
+
+    #[test]
+    fn test_strategies() {
+        let zones = [zone(r"ACPI\ThermalZone\TZ00_0", 28.0), zone(r"ACPI\ThermalZone\TZ01_0", 64.0), zone(r"ACPI\ThermalZone\TZ02_0", 40.0), zone("GFXZ", 44.0)];
+
+        assert_eq!(Aggregate::First.apply(&zones), Ok(CpuTemperature::from_celsius(28.0)));
+        assert_eq!(Aggregate::Max.apply(&zones), Ok(CpuTemperature::from_celsius(64.0)));
+        assert_eq!(Aggregate::Mean.apply(&zones), Ok(CpuTemperature::from_celsius(44.0)));
+        assert_eq!(Aggregate::Median.apply(&zones), Ok(CpuTemperature::from_celsius(42.0)));
+        assert_eq!(Aggregate::Zone("TZ01".to_string()).apply(&zones), Ok(CpuTemperature::from_celsius(64.0)));
+    }
+
+    #[test]
+    fn test_missing_zones() {
+        assert_eq!(Aggregate::Max.apply(&[]), Err(TemperatureError::NoSensors));
+        assert!(matches!(Aggregate::Zone("TZ09".to_string()).apply(&[zone("TZ00", 30.0)]), Err(TemperatureError::InvalidArgument(_))));
+    }
+}
@@ -0,0 +1,172 @@
+//! Sensor readings from AIDA64's shared memory.
+//!
+//! With "Enable shared memory" checked under Preferences → Hardware
+//! Monitoring → External Applications, AIDA64 publishes its sensor values in
+//! the `AIDA64_SensorValues` file mapping as a NUL-terminated list of XML
+//! elements, one per value:
+//!
+//! ```text
+//! <temp><id>TCPU</id><label>CPU</label><value>45</value></temp>
+//! <temp><id>TCC-1-1</id><label>CPU Core #1</label><value>42</value></temp>
+//! <fan><id>FCPU</id><label>CPU</label><value>1180</value></fan>
+//! <volt><id>VCPU</id><label>CPU Core</label><value>1.248</value></volt>
+//! ```
+//!
+//! Temperatures are in the unit AIDA64 displays, Celsius by default.
+//!
+//! Only available on Windows with the `shared-memory` feature.
+
+use regex::Regex;
+
+use crate::sensor::{SensorKind, SensorProvider, SensorReading};
+use crate::{is_plausible, shared_memory, CpuTemperature, TemperatureError, TemperatureProvider, ThermalZoneReading};
+
+/// The name of AIDA64's file mapping.
+const MAPPING_NAME: &str = "AIDA64_SensorValues";
+
+/// Reads CPU temperatures, and other sensors through [`SensorProvider`],
+/// from AIDA64 while it is running.
+///
+/// AIDA64 must run with its shared memory enabled under External
+/// Applications, and show temperatures in Celsius.
+///
+/// As a [`TemperatureProvider`], this returns the CPU temperatures, such as
+/// `CPU Package` and `CPU Core #1`, named by their label. As a
+/// [`SensorProvider`], it also returns GPU and drive temperatures, fan
+/// speeds and voltages.
+///
+/// # Errors
+///
+/// Reads fail with [`TemperatureError::NoSensors`] while AIDA64 is not
+/// publishing its shared memory.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::{Aida64Provider, TemperatureProvider};
+///
+/// for reading in Aida64Provider.read()? {
+///     println!("{}: {:.0}°C", reading.instance_name.unwrap_or_default(), reading.temperature.celsius());
+/// }
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Aida64Provider;
+
+impl TemperatureProvider for Aida64Provider {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        let readings: Vec<ThermalZoneReading> = values_from(&read_values()?)?
+            .into_iter()
+            .filter(|value| value.category == "temp" && value_kind(value) == Some(SensorKind::Cpu))
+            .map(|value| ThermalZoneReading::new(Some(value.label), CpuTemperature::from_celsius(value.value)))
+            .collect();
+        if readings.is_empty() {
+            return Err(TemperatureError::NoValidReadings);
+        }
+
+        Ok(readings)
+    }
+}
+
+impl SensorProvider for Aida64Provider {
+    fn read_sensors(&self) -> Result<Vec<SensorReading>, TemperatureError> {
+        let readings: Vec<SensorReading> = values_from(&read_values()?)?
+            .into_iter()
+            .filter_map(|value| Some(SensorReading::new(value_kind(&value)?, Some(value.label), value.value)))
+            .collect();
+        if readings.is_empty() {
+            return Err(TemperatureError::NoValidReadings);
+        }
+
+        Ok(readings)
+    }
+}
+
+/// One element of the shared memory.
+#[derive(Debug, Clone, PartialEq)]
+struct Value {
+    category: String,
+    id: String,
+    label: String,
+    value: f64,
+}
+
+/// Reads the shared memory up to its terminating NUL.
+fn read_values() -> Result<String, TemperatureError> {
+    let data = shared_memory::read(MAPPING_NAME)?;
+    let end = data.iter().position(|&byte| byte == 0).unwrap_or(data.len());
+    Ok(String::from_utf8_lossy(&data[..end]).into_owned())
+}
+
+/// Parses every element with a numeric value, skipping implausible
+/// temperatures.
+fn values_from(text: &str) -> Result<Vec<Value>, TemperatureError> {
+    let re_value = Regex::new(r"<(\w+)><id>([^<]*)</id><label>([^<]*)</label><value>([^<]*)</value></\w+>")
+        .map_err(|e| TemperatureError::ParseError(format!("regex compilation failed: {}", e)))?;
+
+    let mut values = Vec::new();
+    for cap in re_value.captures_iter(text) {
+        let Ok(value) = cap[4].trim().parse::<f64>() else {
+            continue;
+        };
+        if &cap[1] == "temp" && !is_plausible(value) {
+            continue;
+        }
+        values.push(Value { category: cap[1].to_string(), id: cap[2].to_string(), label: cap[3].trim().to_string(), value });
+    }
+    if values.is_empty() {
+        return Err(TemperatureError::NoSensors);
+    }
+
+    Ok(values)
+}
+
+/// Classifies a value by its category and id, or returns `None` for values
+/// such as motherboard temperatures or clock speeds that fit no
+/// [`SensorKind`].
+fn value_kind(value: &Value) -> Option<SensorKind> {
+    match value.category.as_str() {
+        "temp" if value.id.starts_with("TCPU") || value.id.starts_with("TCC") => Some(SensorKind::Cpu),
+        "temp" if value.id.starts_with("TGPU") => Some(SensorKind::Gpu),
+        "temp" if value.id.starts_with("THDD") => Some(SensorKind::Disk),
+        "fan" => Some(SensorKind::Fan),
+        "volt" => Some(SensorKind::Voltage),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "<sys><id>SCPUCLK</id><label>CPU Clock</label><value>3600</value></sys>\
+        <temp><id>TMOBO</id><label>Motherboard</label><value>34</value></temp>\
+        <temp><id>TCPUPKG</id><label>CPU Package</label><value>58</value></temp>\
+        <temp><id>TCC-1-1</id><label>CPU Core #1</label><value>52</value></temp>\
+        <temp><id>TGPU1DIO</id><label>GPU1 Diode</label><value>61</value></temp>\
+        <temp><id>THDD1</id><label>Samsung SSD 980 PRO 1TB</label><value>255</value></temp>\
+        <fan><id>FCPU</id><label>CPU</label><value>1180</value></fan>\
+        <duty><id>DCPU</id><label>CPU</label><value>42</value></duty>\
+        <volt><id>VCPU</id><label>CPU Core</label><value>1.248</value></volt>";
+
+    #[test]
+    fn test_parse_values() {
+        let values = values_from(SAMPLE).unwrap();
+        assert_eq!(values.len(), 8);
+        assert_eq!(
+            values[2],
+            Value { category: "temp".to_string(), id: "TCPUPKG".to_string(), label: "CPU Package".to_string(), value: 58.0 }
+        );
+
+        let kinds: Vec<Option<SensorKind>> = values.iter().map(value_kind).collect();
+        assert_eq!(
+            kinds,
+            [None, None, Some(SensorKind::Cpu), Some(SensorKind::Cpu), Some(SensorKind::Gpu), Some(SensorKind::Fan), None, Some(SensorKind::Voltage)]
+        );
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        assert_eq!(values_from(""), Err(TemperatureError::NoSensors));
+    }
+}
@@ -0,0 +1,81 @@
+//! Alert payloads for chat and webhook integrations.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{json, CpuTemperature, ThermalState};
+
+impl CpuTemperature {
+    /// Builds a JSON alert body suitable for POSTing to a webhook.
+    ///
+    /// The payload is a single object:
+    ///
+    /// ```json
+    /// {
+    ///   "message": "CPU is overheating",
+    ///   "status": "hot",
+    ///   "temperature": { "celsius": 88.25, "fahrenheit": 190.85 },
+    ///   "timestamp": 1760400000
+    /// }
+    /// ```
+    ///
+    /// `status` is the [`ThermalState`] of the reading: `normal` below 70 °C,
+    /// `warm` from 70 °C, `hot` from 85 °C and `critical` from 95 °C. `timestamp` is the current time in whole
+    /// seconds since the Unix epoch. Temperatures are rounded to two decimals.
+    ///
+    /// Chat services expect their own envelope (for example Slack's `text`
+    /// field), so forward this object as-is to generic webhooks or embed it in
+    /// the service-specific body.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// let payload = CpuTemperature::from_celsius(96.0).alert_payload("CPU is overheating");
+    /// assert!(payload.contains(r#""status":"critical""#));
+    /// ```
+    pub fn alert_payload(&self, message: &str) -> String {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+
+        payload_at(self, message, timestamp)
+    }
+}
+
+/// Builds the alert payload for a fixed Unix timestamp.
+fn payload_at(reading: &CpuTemperature, message: &str, timestamp: u64) -> String {
+    format!(
+        r#"{{"message":{},"status":"{}","temperature":{{"celsius":{},"fahrenheit":{}}},"timestamp":{}}}"#,
+        json::string(message),
+        ThermalState::from_celsius(reading.celsius()),
+        json::number(reading.celsius()),
+        json::number(reading.fahrenheit()),
+        timestamp
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_layout() {
+        let payload = payload_at(&CpuTemperature::from_celsius(88.25), "CPU \"hot\"", 1_760_400_000);
+
+        assert_eq!(
+            payload,
+            r#"{"message":"CPU \"hot\"","status":"hot","temperature":{"celsius":88.25,"fahrenheit":190.85},"timestamp":1760400000}"#
+        );
+    }
+
+    #[test]
+    fn test_status_boundaries() {
+        let status = |celsius| ThermalState::from_celsius(celsius).as_str();
+        assert_eq!(status(69.9), "normal");
+        assert_eq!(status(70.0), "warm");
+        assert_eq!(status(85.0), "hot");
+        assert_eq!(status(95.0), "critical");
+    }
+}
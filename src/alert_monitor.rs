@@ -0,0 +1,219 @@
+//! Threshold alerts delivered to callbacks and channels.
+
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::{CpuTemperature, SamplerHandle};
+
+/// Which way a reading crossed a threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crossing {
+    /// The temperature reached the threshold from below.
+    Raised,
+    /// The temperature dropped back below the threshold.
+    Cleared,
+}
+
+/// A threshold crossing reported by an [`AlertMonitor`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    /// The name the threshold was registered with, such as `"critical"`.
+    pub name: String,
+    /// The threshold in degrees Celsius.
+    pub threshold: f64,
+    /// Whether the threshold was reached or cleared.
+    pub crossing: Crossing,
+    /// The reading that crossed it.
+    pub temperature: CpuTemperature,
+}
+
+/// A callback registered with [`AlertMonitor::on_alert`].
+type Callback = Box<dyn FnMut(&Alert) + Send>;
+
+/// Watches readings for named temperature thresholds and reports each time
+/// one is crossed.
+///
+/// A threshold is raised by the first reading at or above it and cleared by
+/// the first reading below it afterwards; readings that stay on the same side
+/// report nothing, so a CPU sitting at 90 °C raises `warn` once rather than
+/// every second. When one reading crosses several thresholds, rising alerts
+/// are reported lowest threshold first and clearing alerts highest first.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use tunjukin_suhu_cpu_windows::{AlertMonitor, Crossing, TemperatureSampler};
+///
+/// let sampler = TemperatureSampler::new(Duration::from_secs(1)).spawn();
+///
+/// let mut monitor = AlertMonitor::new()
+///     .threshold("warn", 85.0)
+///     .threshold("critical", 95.0)
+///     .on_alert(|alert| eprintln!("{} {:?} at {:.1}°C", alert.name, alert.crossing, alert.temperature.celsius()));
+/// let alerts = monitor.subscribe();
+/// monitor.attach(&sampler);
+///
+/// for alert in alerts {
+///     if alert.name == "critical" && alert.crossing == Crossing::Raised {
+///         // Shut down the render job.
+///     }
+/// }
+/// ```
+#[derive(Default)]
+pub struct AlertMonitor {
+    /// Sorted by threshold, lowest first.
+    thresholds: Vec<Threshold>,
+    callbacks: Vec<Callback>,
+    channels: Vec<Sender<Alert>>,
+}
+
+#[derive(Debug)]
+struct Threshold {
+    name: String,
+    celsius: f64,
+    raised: bool,
+}
+
+impl AlertMonitor {
+    /// Creates a monitor with no thresholds.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a threshold named `name` at `celsius` degrees.
+    pub fn threshold(mut self, name: impl Into<String>, celsius: f64) -> Self {
+        let index = self.thresholds.partition_point(|threshold| threshold.celsius <= celsius);
+        self.thresholds.insert(index, Threshold { name: name.into(), celsius, raised: false });
+        self
+    }
+
+    /// Calls `callback` with every alert, on the thread that checks the
+    /// readings.
+    pub fn on_alert(mut self, callback: impl FnMut(&Alert) + Send + 'static) -> Self {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
+    /// Returns a channel that receives every future alert.
+    ///
+    /// Dropping the receiver unsubscribes it.
+    pub fn subscribe(&mut self) -> Receiver<Alert> {
+        let (sender, receiver) = mpsc::channel();
+        self.channels.push(sender);
+        receiver
+    }
+
+    /// Checks `reading` against every threshold, delivers the resulting
+    /// alerts and returns them.
+    pub fn check(&mut self, reading: &CpuTemperature) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+
+        for threshold in self.thresholds.iter_mut() {
+            if !threshold.raised && reading.celsius() >= threshold.celsius {
+                threshold.raised = true;
+                alerts.push(threshold.alert(Crossing::Raised, reading));
+            }
+        }
+        for threshold in self.thresholds.iter_mut().rev() {
+            if threshold.raised && reading.celsius() < threshold.celsius {
+                threshold.raised = false;
+                alerts.push(threshold.alert(Crossing::Cleared, reading));
+            }
+        }
+
+        for alert in &alerts {
+            self.channels.retain(|channel| channel.send(alert.clone()).is_ok());
+            for callback in &mut self.callbacks {
+                callback(alert);
+            }
+        }
+        alerts
+    }
+
+    /// Returns the names of the thresholds currently raised, lowest first.
+    pub fn raised(&self) -> Vec<&str> {
+        self.thresholds
+            .iter()
+            .filter(|threshold| threshold.raised)
+            .map(|threshold| threshold.name.as_str())
+            .collect()
+    }
+
+    /// Checks every successful sample from `sampler` on the sampler's thread.
+    ///
+    /// Failed reads leave every threshold as it was.
+    pub fn attach(mut self, sampler: &SamplerHandle) {
+        sampler.subscribe_with(move |sample| {
+            if let Ok(reading) = sample {
+                self.check(reading);
+            }
+        });
+    }
+}
+
+impl Threshold {
+    fn alert(&self, crossing: Crossing, reading: &CpuTemperature) -> Alert {
+        Alert {
+            name: self.name.clone(),
+            threshold: self.celsius,
+            crossing,
+            temperature: reading.clone(),
+        }
+    }
+}
+
+impl std::fmt::Debug for AlertMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AlertMonitor")
+            .field("thresholds", &self.thresholds)
+            .field("callbacks", &self.callbacks.len())
+            .field("channels", &self.channels.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    fn summary(alerts: &[Alert]) -> Vec<(&str, Crossing)> {
+        alerts.iter().map(|alert| (alert.name.as_str(), alert.crossing)).collect()
+    }
+
+    #[test]
+    fn test_crossings_fire_once() {
+        let mut monitor = AlertMonitor::new().threshold("critical", 95.0).threshold("warn", 85.0);
+        let mut check = |celsius| monitor.check(&CpuTemperature::from_celsius(celsius));
+
+        assert!(check(80.0).is_empty());
+        assert_eq!(summary(&check(85.0)), [("warn", Crossing::Raised)]);
+        assert!(check(90.0).is_empty());
+        assert_eq!(summary(&check(96.0)), [("critical", Crossing::Raised)]);
+        assert_eq!(summary(&check(70.0)), [("critical", Crossing::Cleared), ("warn", Crossing::Cleared)]);
+        assert_eq!(summary(&check(99.0)), [("warn", Crossing::Raised), ("critical", Crossing::Raised)]);
+
+        assert_eq!(monitor.raised(), ["warn", "critical"]);
+    }
+
+    #[test]
+    fn test_delivers_to_callbacks_and_channels() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&seen);
+        let mut monitor = AlertMonitor::new()
+            .threshold("warn", 85.0)
+            .on_alert(move |alert| recorded.lock().unwrap().push(alert.crossing));
+        let alerts = monitor.subscribe();
+        drop(monitor.subscribe());
+
+        monitor.check(&CpuTemperature::from_celsius(88.0));
+        monitor.check(&CpuTemperature::from_celsius(80.0));
+
+        assert_eq!(*seen.lock().unwrap(), [Crossing::Raised, Crossing::Cleared]);
+        let alert = alerts.recv().unwrap();
+        assert_eq!(alert.threshold, 85.0);
+        assert_eq!(alert.temperature.celsius(), 88.0);
+        assert_eq!(alerts.recv().unwrap().crossing, Crossing::Cleared);
+        assert_eq!(monitor.channels.len(), 1);
+    }
+}
@@ -0,0 +1,118 @@
+//! Async wrappers for tokio users.
+//!
+//! Every query spawns PowerShell (or makes blocking COM calls) and waits for
+//! it, which would stall an async runtime's worker thread for hundreds of
+//! milliseconds. These wrappers move that work onto tokio's blocking thread
+//! pool instead.
+//!
+//! Only available with the `async` feature.
+
+use std::time::Duration;
+
+use crate::{CpuTemperature, TemperatureError};
+
+impl CpuTemperature {
+    /// Retrieves the current CPU temperature without blocking the async
+    /// runtime.
+    ///
+    /// This performs the same query as [`CpuTemperature::get`] on tokio's
+    /// blocking thread pool, so it must be called from within a tokio
+    /// runtime.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// # async fn run() -> Result<(), tunjukin_suhu_cpu_windows::TemperatureError> {
+    /// let temp = CpuTemperature::get_async().await?;
+    /// println!("CPU Temperature: {:.1}°C", temp.celsius());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_async() -> Result<Self, TemperatureError> {
+        run_blocking(Self::get).await
+    }
+
+    /// Retrieves the current CPU temperature without blocking the async
+    /// runtime, giving up after `timeout`.
+    ///
+    /// This is the async counterpart of [`CpuTemperature::get_with_timeout`]:
+    /// the query runs on tokio's blocking thread pool and PowerShell is
+    /// killed if it is still running at the deadline. The await itself is
+    /// also raced against a timer, so it completes after `timeout` even while
+    /// the query is waiting for the process-wide query limit.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`TemperatureError::Timeout`] if no reading arrived in time.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// # async fn run() -> Result<(), tunjukin_suhu_cpu_windows::TemperatureError> {
+    /// let temp = CpuTemperature::get_async_timeout(Duration::from_secs(2)).await?;
+    /// println!("CPU Temperature: {:.1}°C", temp.celsius());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_async_timeout(timeout: Duration) -> Result<Self, TemperatureError> {
+        run_blocking_timeout(timeout, move || Self::get_with_timeout(timeout)).await
+    }
+}
+
+/// Runs `read` on tokio's blocking thread pool.
+async fn run_blocking<T: Send + 'static>(
+    read: impl FnOnce() -> Result<T, TemperatureError> + Send + 'static,
+) -> Result<T, TemperatureError> {
+    tokio::task::spawn_blocking(read)
+        .await
+        .unwrap_or_else(|e| Err(TemperatureError::Other(format!("Temperature query task failed: {}", e))))
+}
+
+/// Like [`run_blocking`], but fails with [`TemperatureError::Timeout`] if
+/// `read` has not finished after `timeout`.
+async fn run_blocking_timeout<T: Send + 'static>(
+    timeout: Duration,
+    read: impl FnOnce() -> Result<T, TemperatureError> + Send + 'static,
+) -> Result<T, TemperatureError> {
+    tokio::time::timeout(timeout, run_blocking(read))
+        .await
+        .unwrap_or(Err(TemperatureError::Timeout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap()
+    }
+
+    #[test]
+    fn test_run_blocking_returns_result() {
+        let reading = runtime().block_on(run_blocking(|| Ok(CpuTemperature::from_celsius(42.0))));
+        assert_eq!(reading, Ok(CpuTemperature::from_celsius(42.0)));
+    }
+
+    #[test]
+    fn test_run_blocking_reports_panics() {
+        let result: Result<CpuTemperature, _> = runtime().block_on(run_blocking(|| panic!("sensor driver crashed")));
+        assert!(matches!(result, Err(TemperatureError::Other(message)) if message.contains("task failed")));
+    }
+
+    #[test]
+    fn test_run_blocking_timeout() {
+        let slow = runtime().block_on(run_blocking_timeout(Duration::from_millis(10), || {
+            std::thread::sleep(Duration::from_millis(500));
+            Ok(CpuTemperature::from_celsius(42.0))
+        }));
+        assert_eq!(slow, Err(TemperatureError::Timeout));
+
+        let fast = runtime().block_on(run_blocking_timeout(Duration::from_secs(60), || Ok(CpuTemperature::from_celsius(42.0))));
+        assert_eq!(fast, Ok(CpuTemperature::from_celsius(42.0)));
+    }
+}
@@ -0,0 +1,90 @@
+//! Laptop battery temperatures.
+//!
+//! Battery drivers that report a temperature publish it as
+//! `BatteryTemperature` in the `root/wmi` namespace, one instance per
+//! battery. Like the ACPI thermal zones, `Temperature` is in tenths of a
+//! Kelvin. Drivers that do not measure it leave the class empty or report 0.
+
+use crate::sensor::read_tagged;
+use crate::{
+    is_plausible, parse_records, record_value, CpuTemperature, SensorKind, SensorProvider, SensorReading, TemperatureError, TemperatureProvider,
+    ThermalZoneReading,
+};
+
+/// The query listing every battery's temperature.
+const BATTERY_QUERY: &str = "Get-CimInstance -Namespace root/wmi -ClassName BatteryTemperature | Format-List InstanceName,Temperature";
+
+/// Reads the temperature of laptop batteries.
+///
+/// An overheating battery is a safety problem rather than a performance
+/// one, so applications that watch the CPU often need to watch it too. Each
+/// battery is named by its WMI instance name.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::{BatteryTemperature, CpuTemperature};
+///
+/// let cpu = CpuTemperature::get()?;
+/// let battery = BatteryTemperature::get()?;
+/// println!("CPU {:.1}°C, battery {:.1}°C", cpu.celsius(), battery.celsius());
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatteryTemperature;
+
+impl BatteryTemperature {
+    /// Reads the temperature of the first battery that reports one.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the query fails, which it does on many machines without a
+    /// battery, or if no battery reports a temperature.
+    pub fn get() -> Result<CpuTemperature, TemperatureError> {
+        BatteryTemperature.read_one()
+    }
+}
+
+impl TemperatureProvider for BatteryTemperature {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        batteries_from(&CpuTemperature::run_powershell(BATTERY_QUERY)?)
+    }
+}
+
+impl SensorProvider for BatteryTemperature {
+    fn read_sensors(&self) -> Result<Vec<SensorReading>, TemperatureError> {
+        read_tagged(self, SensorKind::Battery)
+    }
+}
+
+/// Parses the query output, failing if it lists no battery with a
+/// temperature.
+fn batteries_from(output: &str) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+    parse_records(output, |record| {
+        let decikelvin: f64 = record_value(record, "Temperature")?.parse().ok().filter(|&decikelvin| decikelvin > 0.0)?;
+        let temperature = CpuTemperature::from_decikelvin(decikelvin);
+        is_plausible(temperature.celsius()).then(|| ThermalZoneReading::new(record_value(record, "InstanceName").map(str::to_string), temperature))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_batteries() {
+        let output = "\r\nInstanceName : ACPI\\PNP0C0A\\1_0\r\nTemperature  : 3082\r\n\r\n\
+                      InstanceName : ACPI\\PNP0C0A\\2_0\r\nTemperature  : 0\r\n\r\n";
+
+        let batteries = batteries_from(output).unwrap();
+        assert_eq!(batteries.len(), 1);
+        assert_eq!(batteries[0].instance_name.as_deref(), Some(r"ACPI\PNP0C0A\1_0"));
+        assert!((batteries[0].temperature.celsius() - 35.05).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_battery_errors() {
+        assert_eq!(batteries_from(""), Err(TemperatureError::NoSensors));
+        assert_eq!(batteries_from("InstanceName : ACPI\\PNP0C0A\\1_0\nTemperature : 0\n"), Err(TemperatureError::NoValidReadings));
+    }
+}
@@ -0,0 +1,126 @@
+//! `suhu exporter`: serves readings in the Prometheus text format.
+//!
+//! Every scrape of `/metrics` reads all thermal zones. A failed read still
+//! answers with `cpu_temperature_up 0`, so the scrape succeeds and the
+//! failure can be alerted on.
+
+use std::collections::HashSet;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use tunjukin_suhu_cpu_windows::{TemperatureError, TemperatureReader, ThermalZoneReading};
+
+use crate::http::{self, Request, Response};
+
+/// The content type of the Prometheus text exposition format.
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Serves `/metrics` on `listen`, reading with `reader`, until the process is
+/// stopped.
+pub fn run(listen: SocketAddr, reader: Arc<TemperatureReader>) -> io::Result<()> {
+    http::serve(listen, move |request| route(request, &reader))
+}
+
+fn route(request: &Request, reader: &TemperatureReader) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/metrics") => Response::ok(CONTENT_TYPE, render(&reader.get_all())),
+        ("GET", "/") => Response::ok("text/html; charset=utf-8", "<a href=\"/metrics\">Metrics</a>\n".to_string()),
+        (_, "/metrics" | "/") => Response::method_not_allowed(),
+        _ => Response::not_found(),
+    }
+}
+
+/// Renders one scrape.
+fn render(zones: &Result<Vec<ThermalZoneReading>, TemperatureError>) -> String {
+    let mut out = String::new();
+
+    out.push_str("# HELP cpu_temperature_celsius Temperature of a CPU thermal zone in degrees Celsius.\n");
+    out.push_str("# TYPE cpu_temperature_celsius gauge\n");
+    if let Ok(zones) = zones {
+        for (zone, label) in zones.iter().zip(zone_labels(zones)) {
+            out.push_str(&format!("cpu_temperature_celsius{{zone=\"{}\"}} {}\n", escape_label(&label), zone.temperature.celsius()));
+        }
+    }
+
+    out.push_str("# HELP cpu_temperature_up Whether the last read of the thermal zones succeeded.\n");
+    out.push_str("# TYPE cpu_temperature_up gauge\n");
+    out.push_str(&format!("cpu_temperature_up {}\n", u8::from(zones.is_ok())));
+
+    out
+}
+
+/// Returns a short, unique label for each zone.
+///
+/// `ACPI\ThermalZone\TZ00_0` becomes `TZ00`. If shortening makes two labels
+/// equal, every zone keeps its full instance name instead. Unnamed zones are
+/// labelled by position, as `zone0`, `zone1` and so on.
+fn zone_labels(zones: &[ThermalZoneReading]) -> Vec<String> {
+    let full: Vec<String> = zones
+        .iter()
+        .enumerate()
+        .map(|(i, zone)| zone.instance_name.clone().unwrap_or_else(|| format!("zone{}", i)))
+        .collect();
+    let short: Vec<String> = full.iter().map(|name| short_name(name).to_string()).collect();
+
+    let mut seen = HashSet::new();
+    if short.iter().all(|label| seen.insert(label.as_str())) {
+        short
+    } else {
+        full
+    }
+}
+
+/// Drops the path and the instance suffix from a WMI instance name.
+fn short_name(name: &str) -> &str {
+    let tail = name.rsplit('\\').next().unwrap_or(name);
+    match tail.rsplit_once('_') {
+        Some((head, index)) if !head.is_empty() && !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()) => head,
+        _ => tail,
+    }
+}
+
+/// Escapes a label value as the text format requires.
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tunjukin_suhu_cpu_windows::CpuTemperature;
+
+    fn zone(name: Option<&str>, celsius: f64) -> ThermalZoneReading {
+        ThermalZoneReading::new(name.map(str::to_string), CpuTemperature::from_celsius(celsius))
+    }
+
+    #[test]
+    fn test_render_zones() {
+        let zones = vec![zone(Some(r"ACPI\ThermalZone\TZ00_0"), 45.5), zone(None, 50.0)];
+
+        let text = render(&Ok(zones));
+
+        assert!(text.contains("# TYPE cpu_temperature_celsius gauge\n"));
+        assert!(text.contains("cpu_temperature_celsius{zone=\"TZ00\"} 45.5\n"));
+        assert!(text.contains("cpu_temperature_celsius{zone=\"zone1\"} 50\n"));
+        assert!(text.ends_with("cpu_temperature_up 1\n"));
+    }
+
+    #[test]
+    fn test_render_failure() {
+        let text = render(&Err(TemperatureError::NoSensors));
+
+        assert!(!text.contains("cpu_temperature_celsius{"));
+        assert!(text.ends_with("cpu_temperature_up 0\n"));
+    }
+
+    #[test]
+    fn test_zone_labels_stay_unique() {
+        let zones = [zone(Some(r"ACPI\ThermalZone\CPUZ_0"), 40.0), zone(Some(r"ACPI\ThermalZone\CPUZ_1"), 41.0)];
+        assert_eq!(zone_labels(&zones), [r"ACPI\ThermalZone\CPUZ_0", r"ACPI\ThermalZone\CPUZ_1"]);
+
+        assert_eq!(short_name("CPU Package"), "CPU Package");
+        assert_eq!(short_name("THRM_"), "THRM_");
+        assert_eq!(escape_label(r#"A\"B"#), r#"A\\\"B"#);
+    }
+}
@@ -0,0 +1,199 @@
+//! A minimal HTTP/1.1 server for the serving commands.
+//!
+//! Each connection gets its own thread, carries one request and is closed
+//! after the response, unless the handler takes the connection over, as a
+//! WebSocket does. That is all a scraper or a health check needs, and it
+//! keeps the tool free of an async runtime.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An incoming request without its body.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Request {
+    pub method: String,
+    /// The path without its query string.
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl Request {
+    /// Returns the value of the first header called `name`, ignoring case.
+    #[cfg_attr(not(feature = "server"), allow(dead_code))]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+    }
+}
+
+/// What a handler does with a request.
+pub enum Reply {
+    /// Send a response and close the connection.
+    Respond(Response),
+    /// Hand the connection to a function that writes its own response.
+    #[cfg(feature = "server")]
+    Takeover(Box<dyn FnOnce(TcpStream) -> io::Result<()> + Send>),
+}
+
+impl From<Response> for Reply {
+    fn from(response: Response) -> Self {
+        Reply::Respond(response)
+    }
+}
+
+/// A complete response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub fn ok(content_type: &'static str, body: String) -> Self {
+        Response { status: 200, content_type, body }
+    }
+
+    pub fn not_found() -> Self {
+        Response { status: 404, content_type: "text/plain; charset=utf-8", body: "not found\n".to_string() }
+    }
+
+    pub fn bad_request() -> Self {
+        Response { status: 400, content_type: "text/plain; charset=utf-8", body: "bad request\n".to_string() }
+    }
+
+    pub fn method_not_allowed() -> Self {
+        Response { status: 405, content_type: "text/plain; charset=utf-8", body: "method not allowed\n".to_string() }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            503 => "Service Unavailable",
+            _ => "",
+        }
+    }
+
+    /// Writes the response, including a `Content-Length` header and
+    /// `Connection: close`.
+    pub fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(
+            out,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.reason(),
+            self.content_type,
+            self.body.len(),
+            self.body
+        )?;
+        out.flush()
+    }
+}
+
+/// Reads the request line and the headers.
+///
+/// Returns `None` for a request that is not HTTP.
+pub fn read_request(input: &mut impl BufRead) -> io::Result<Option<Request>> {
+    let mut line = String::new();
+    input.read_line(&mut line)?;
+
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next()) else {
+        return Ok(None);
+    };
+    if !version.starts_with("HTTP/") {
+        return Ok(None);
+    }
+    let mut request = Request {
+        method: method.to_string(),
+        path: target.split('?').next().unwrap_or_default().to_string(),
+        headers: Vec::new(),
+    };
+
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 || header.trim_end().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            request.headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    Ok(Some(request))
+}
+
+/// Accepts connections on `listen` forever, answering each request with
+/// `handler`.
+pub fn serve<R: Into<Reply>>(listen: SocketAddr, handler: impl Fn(&Request) -> R + Send + Sync + 'static) -> io::Result<()> {
+    let listener = TcpListener::bind(listen)?;
+    eprintln!("listening on http://{}", listener.local_addr()?);
+
+    let handler = Arc::new(handler);
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let handler = Arc::clone(&handler);
+        thread::spawn(move || {
+            if let Err(e) = handle(stream, |request| handler(request).into()) {
+                eprintln!("connection error: {}", e);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+fn handle(stream: TcpStream, handler: impl FnOnce(&Request) -> Reply) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut input = BufReader::new(&stream);
+
+    let reply = match read_request(&mut input)? {
+        Some(request) => handler(&request),
+        None => Response::bad_request().into(),
+    };
+
+    match reply {
+        Reply::Respond(response) => response.write_to(&mut &stream),
+        #[cfg(feature = "server")]
+        Reply::Takeover(takeover) => takeover(stream),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_request() {
+        let mut input = Cursor::new("GET /metrics?name[]=x HTTP/1.1\r\nHost: localhost\r\nAccept: */*\r\n\r\n");
+
+        let request = read_request(&mut input).unwrap().unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/metrics");
+        assert_eq!(request.header("host"), Some("localhost"));
+        assert_eq!(request.header("Upgrade"), None);
+        assert_eq!(input.position() as usize, input.get_ref().len());
+
+        assert_eq!(read_request(&mut Cursor::new("\u{16}\u{3}\u{1}")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_write_response() {
+        let mut out = Vec::new();
+        Response::ok("text/plain", "hi\n".to_string()).write_to(&mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 3\r\nConnection: close\r\n\r\nhi\n"
+        );
+    }
+}
@@ -0,0 +1,471 @@
+//! `suhu`: read CPU temperatures from the command line.
+//!
+//! Built on the library with the `cli` feature:
+//!
+//! ```text
+//! cargo install tunjukin_suhu_cpu_windows --features cli
+//! suhu read --threshold 90
+//! ```
+
+#[cfg(feature = "exporter")]
+mod exporter;
+#[cfg(any(feature = "exporter", feature = "server"))]
+mod http;
+mod output;
+#[cfg(feature = "server")]
+mod server;
+#[cfg(all(windows, feature = "service"))]
+mod service;
+#[cfg(all(windows, feature = "tray"))]
+mod tray;
+#[cfg(feature = "tui")]
+mod tui;
+#[cfg(feature = "server")]
+mod websocket;
+
+#[cfg(any(feature = "exporter", feature = "server"))]
+use std::net::SocketAddr;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use clap::{Parser, Subcommand};
+use serde_json::json;
+use tunjukin_suhu_cpu_windows::{format_rfc3339, parse_duration, Config, Providers, TemperatureError, TemperatureReader};
+
+use output::{csv_field, hostname, influx_string, influx_tag, Format, Record, Sparkline, Unit, CSV_HEADER};
+
+/// Reads the CPU temperature on Windows.
+#[derive(Debug, Parser)]
+#[command(name = "suhu", version)]
+struct Cli {
+    /// Read providers, offsets, thresholds, the interval, the unit and
+    /// sinks from this TOML file, `SUHU_CONFIG` if not given. `SUHU_*`
+    /// variables override the file, and options given on the command line
+    /// override both.
+    #[arg(long, short, value_name = "FILE", global = true)]
+    config: Option<PathBuf>,
+
+    /// Unit for printed temperatures; Celsius unless the configuration sets
+    /// one.
+    #[arg(long, short, value_enum, global = true)]
+    unit: Option<Unit>,
+
+    /// Output format.
+    #[arg(long, short, value_enum, default_value_t = Format::Plain, global = true)]
+    format: Format,
+
+    /// What to do; `read` if omitted.
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Print the current CPU temperature.
+    Read {
+        /// Act as a Nagios/Icinga check: exit with code 2 if the temperature
+        /// exceeds CELSIUS, 0 otherwise, or 3 if it cannot be read. Plain
+        /// output becomes a check-plugin status line. Defaults to the
+        /// `critical` threshold of the configuration.
+        #[arg(long, value_name = "CELSIUS")]
+        threshold: Option<f64>,
+    },
+    /// Print every thermal zone with its name.
+    List,
+    /// Probe every temperature backend and print which ones work on this
+    /// machine, the first of them being the preferred source.
+    Providers,
+    /// Print a timestamped reading at a fixed interval.
+    Watch {
+        /// Time between readings, such as `500ms`, `2s` or `1m`; 1s unless
+        /// the configuration sets one.
+        #[arg(long, short, value_name = "DURATION", value_parser = parse_duration)]
+        interval: Option<Duration>,
+
+        /// Stop after this many readings instead of running until
+        /// interrupted.
+        #[arg(long, short = 'n')]
+        count: Option<u64>,
+
+        /// Show a sparkline of this many recent readings after each plain
+        /// reading; 0 hides it.
+        #[arg(long, value_name = "READINGS", default_value_t = 20)]
+        sparkline: usize,
+    },
+    /// Serve readings to Prometheus on `/metrics`.
+    #[cfg(feature = "exporter")]
+    Exporter {
+        /// Address and port to listen on.
+        #[arg(long, short, value_name = "ADDRESS", default_value = "0.0.0.0:9123")]
+        listen: SocketAddr,
+    },
+    /// Serve the current reading as JSON on `/temperature`, and a live
+    /// stream of readings over WebSocket on `/ws`.
+    #[cfg(feature = "server")]
+    Serve {
+        /// Address and port to listen on.
+        #[arg(long, short, value_name = "ADDRESS", default_value = "0.0.0.0:8080")]
+        listen: SocketAddr,
+
+        /// Time between readings pushed to WebSocket clients; 1s unless the
+        /// configuration sets one.
+        #[arg(long, short, value_name = "DURATION", value_parser = parse_duration)]
+        interval: Option<Duration>,
+    },
+    /// Install, remove or run the exporter and CSV logging as a Windows
+    /// service.
+    #[cfg(all(windows, feature = "service"))]
+    Service {
+        #[command(subcommand)]
+        action: service::ServiceAction,
+    },
+    /// Show the temperature in the notification area, with a menu to change
+    /// the unit and interval.
+    #[cfg(all(windows, feature = "tray"))]
+    Tray {
+        /// Time between readings; 5s unless the configuration sets one.
+        #[arg(long, short, value_name = "DURATION", value_parser = parse_duration)]
+        interval: Option<Duration>,
+    },
+    /// Show a live dashboard of every thermal zone in the terminal.
+    #[cfg(feature = "tui")]
+    Tui {
+        /// Time between readings, 1s unless the configuration sets one; `f`
+        /// and `s` change it while running.
+        #[arg(long, short, value_name = "DURATION", value_parser = parse_duration)]
+        interval: Option<Duration>,
+    },
+}
+
+/// The outcome of a threshold check, following the monitoring plugin
+/// convention for exit codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckStatus {
+    Ok = 0,
+    Critical = 2,
+    Unknown = 3,
+}
+
+impl CheckStatus {
+    fn label(self) -> &'static str {
+        match self {
+            CheckStatus::Ok => "OK",
+            CheckStatus::Critical => "CRITICAL",
+            CheckStatus::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+impl From<CheckStatus> for ExitCode {
+    fn from(status: CheckStatus) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let config_path = cli.config.or_else(|| env::var_os("SUHU_CONFIG").map(PathBuf::from));
+    let (config, reader) = match load(config_path.as_deref()) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let unit = cli.unit.unwrap_or(config.unit.into());
+    let interval = |interval: Option<Duration>, default: Duration| interval.or(config.interval).unwrap_or(default);
+
+    match cli.command.unwrap_or(Command::Read { threshold: None }) {
+        Command::Read { threshold } => match threshold.or_else(|| config.thresholds.get("critical").copied()) {
+            Some(threshold) => {
+                let (status, output) = check(read(&reader), threshold, unit, cli.format);
+                println!("{}", output);
+                status.into()
+            }
+            None => report(read(&reader).map(|record| match cli.format {
+                Format::Plain => record.plain(unit, false),
+                Format::Json => record.json(unit).to_string(),
+                Format::Csv => format!("{}\n{}", CSV_HEADER, record.csv(unit)),
+                Format::Influx => record.influx(&hostname(), ""),
+            })),
+        },
+        Command::List => report(reader.get_all().map(|zones| {
+            let records: Vec<Record> = zones.into_iter().map(Record::from_zone).collect();
+            match cli.format {
+                Format::Plain => records.iter().map(|record| record.plain(unit, true)).collect::<Vec<_>>().join("\n"),
+                Format::Json => serde_json::Value::from(records.iter().map(|record| record.json(unit)).collect::<Vec<_>>()).to_string(),
+                Format::Csv => std::iter::once(CSV_HEADER.to_string())
+                    .chain(records.iter().map(|record| record.csv(unit)))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                Format::Influx => {
+                    let host = hostname();
+                    records.iter().map(|record| record.influx(&host, "")).collect::<Vec<_>>().join("\n")
+                }
+            }
+        })),
+        Command::Providers => {
+            println!("{}", providers(Providers::detect(), cli.format));
+            ExitCode::SUCCESS
+        }
+        Command::Watch { interval: every, count, sparkline } => {
+            watch(&reader, interval(every, Duration::from_secs(1)), count, sparkline, unit, cli.format);
+            ExitCode::SUCCESS
+        }
+        #[cfg(feature = "exporter")]
+        Command::Exporter { listen } => served(listen, exporter::run(listen, reader)),
+        #[cfg(feature = "server")]
+        Command::Serve { listen, interval: every } => served(listen, server::run(listen, reader, unit, interval(every, Duration::from_secs(1)))),
+        #[cfg(all(windows, feature = "service"))]
+        Command::Service { action } => match service::run(action, config_path.as_deref(), config, reader) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                ExitCode::FAILURE
+            }
+        },
+        #[cfg(all(windows, feature = "tray"))]
+        Command::Tray { interval: every } => match tray::run(reader, unit, interval(every, Duration::from_secs(5))) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("error: cannot show the tray icon: {}", e);
+                ExitCode::FAILURE
+            }
+        },
+        #[cfg(feature = "tui")]
+        Command::Tui { interval: every } => match tui::run(reader, unit, interval(every, Duration::from_secs(1))) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("error: cannot run the dashboard: {}", e);
+                ExitCode::FAILURE
+            }
+        },
+    }
+}
+
+/// Loads the configuration at `path`, or the default one without a path,
+/// applies the environment overrides and builds its reader.
+fn load(path: Option<&Path>) -> Result<(Config, Arc<TemperatureReader>), TemperatureError> {
+    let config = path.map(Config::from_file).transpose()?.unwrap_or_default().env_overrides()?;
+    let reader = config.builder()?.build();
+    Ok((config, Arc::new(reader)))
+}
+
+/// Reports why a server on `listen` stopped.
+#[cfg(any(feature = "exporter", feature = "server"))]
+fn served(listen: SocketAddr, result: std::io::Result<()>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: cannot serve on {}: {}", listen, e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Reads the first thermal zone with `reader`, keeping its name.
+fn read(reader: &TemperatureReader) -> Result<Record, TemperatureError> {
+    reader
+        .get_all()?
+        .into_iter()
+        .next()
+        .map(Record::from_zone)
+        .ok_or(TemperatureError::NoSensors)
+}
+
+/// Prints `count` readings, or readings forever, `interval` apart, with a
+/// sparkline of the last `sparkline` readings in plain output. Failed reads
+/// are reported on standard error and count as ticks.
+fn watch(reader: &TemperatureReader, interval: Duration, count: Option<u64>, sparkline: usize, unit: Unit, format: Format) {
+    if format == Format::Csv {
+        println!("{}", CSV_HEADER);
+    }
+
+    let host = hostname();
+    let mut recent = Sparkline::new(sparkline);
+    let mut tick = 0;
+    loop {
+        match read(reader) {
+            Ok(record) => match format {
+                Format::Plain => {
+                    recent.push(record.reading.celsius());
+                    let line = format!("{}  {}", format_rfc3339(record.timestamp), record.plain(unit, false));
+                    match sparkline {
+                        0 => println!("{}", line),
+                        _ => println!("{}  {}", line, recent.render()),
+                    }
+                }
+                Format::Json => println!("{}", record.json(unit)),
+                Format::Csv => println!("{}", record.csv(unit)),
+                Format::Influx => println!("{}", record.influx(&host, "")),
+            },
+            Err(e) => eprintln!("{}  error: {}", format_rfc3339(SystemTime::now()), e),
+        }
+
+        tick += 1;
+        if count.is_some_and(|count| tick >= count) {
+            break;
+        }
+        thread::sleep(interval);
+    }
+}
+
+/// Formats the probed backends of `detected`: one line per backend in plain
+/// output, or an array of objects in JSON. CSV and Influx output fall back to
+/// plain lines.
+fn providers(detected: &Providers, format: Format) -> String {
+    let preferred = detected.preferred();
+    let statuses = detected.statuses();
+    match format {
+        Format::Json => serde_json::Value::from(
+            statuses
+                .iter()
+                .map(|status| {
+                    json!({
+                        "provider": status.kind.name(),
+                        "working": status.is_working(),
+                        "preferred": Some(status.kind) == preferred,
+                        "zones": status.result.as_ref().ok(),
+                        "error": status.result.as_ref().err().map(|e| e.to_string()),
+                        "elapsed_ms": status.elapsed.as_millis() as u64,
+                    })
+                })
+                .collect::<Vec<_>>(),
+        )
+        .to_string(),
+        _ => statuses
+            .iter()
+            .map(|status| {
+                let marker = if Some(status.kind) == preferred { '*' } else { ' ' };
+                let outcome = match &status.result {
+                    Ok(zones) => format!("{} plausible zone(s) in {} ms", zones, status.elapsed.as_millis()),
+                    Err(e) => e.to_string(),
+                };
+                format!("{} {:<24} {}", marker, status.kind.name(), outcome)
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// Prints `output`, or the error, and returns the matching exit code.
+fn report(output: Result<String, TemperatureError>) -> ExitCode {
+    match output {
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Compares `record` against `threshold` degrees Celsius, producing a
+/// monitoring plugin status line with performance data, or a record in
+/// another format with the status attached. Influx thresholds are always
+/// in Celsius, like the fields they compare against.
+fn check(record: Result<Record, TemperatureError>, threshold: f64, unit: Unit, format: Format) -> (CheckStatus, String) {
+    let record = match record {
+        Ok(record) => record,
+        Err(e) => {
+            let status = CheckStatus::Unknown;
+            let output = match format {
+                Format::Plain => format!("CPU TEMPERATURE {} - {}", status.label(), e),
+                Format::Json => json!({ "status": status.label(), "error": e.to_string() }).to_string(),
+                Format::Csv => format!("status,error\n{},{}", status.label(), csv_field(&e.to_string())),
+                Format::Influx => format!(
+                    "cpu_temperature,host={} status={},error={}",
+                    influx_tag(&hostname()),
+                    influx_string(status.label()),
+                    influx_string(&e.to_string())
+                ),
+            };
+            return (status, output);
+        }
+    };
+
+    let status = if record.reading.celsius() > threshold { CheckStatus::Critical } else { CheckStatus::Ok };
+    let limit = unit.convert(threshold);
+
+    let output = match format {
+        Format::Plain => format!(
+            "CPU TEMPERATURE {} - {} (threshold {:.1} {}) | temperature={:.1};;{:.1}",
+            status.label(),
+            unit.format(&record.reading),
+            limit,
+            unit.symbol(),
+            unit.value(&record.reading),
+            limit
+        ),
+        Format::Json => {
+            let mut output = record.json(unit);
+            output["status"] = json!(status.label());
+            output["threshold"] = json!(limit);
+            output.to_string()
+        }
+        Format::Csv => format!("{},status,threshold\n{},{},{:.2}", CSV_HEADER, record.csv(unit), status.label(), limit),
+        Format::Influx => record.influx(&hostname(), &format!(",status={},threshold={:.2}", influx_string(status.label()), threshold)),
+    };
+
+    (status, output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tunjukin_suhu_cpu_windows::{CpuTemperature, ThermalZoneReading};
+
+    fn record(celsius: f64) -> Result<Record, TemperatureError> {
+        Ok(Record::from_zone(ThermalZoneReading::new(Some("TZ00".to_string()), CpuTemperature::from_celsius(celsius))))
+    }
+
+    #[test]
+    fn test_check_status_line() {
+        let (status, line) = check(record(95.0), 90.0, Unit::Celsius, Format::Plain);
+        assert_eq!(status, CheckStatus::Critical);
+        assert_eq!(line, "CPU TEMPERATURE CRITICAL - 95.0 °C (threshold 90.0 °C) | temperature=95.0;;90.0");
+
+        let (status, line) = check(record(90.0), 90.0, Unit::Fahrenheit, Format::Plain);
+        assert_eq!(status, CheckStatus::Ok);
+        assert_eq!(line, "CPU TEMPERATURE OK - 194.0 °F (threshold 194.0 °F) | temperature=194.0;;194.0");
+    }
+
+    #[test]
+    fn test_check_unknown_and_json() {
+        let (status, line) = check(Err(TemperatureError::NoSensors), 90.0, Unit::Celsius, Format::Plain);
+        assert_eq!(status, CheckStatus::Unknown);
+        assert!(line.starts_with("CPU TEMPERATURE UNKNOWN - No temperature data"));
+
+        let (status, line) = check(record(95.0), 90.0, Unit::Celsius, Format::Json);
+        let output: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(status, CheckStatus::Critical);
+        assert_eq!(output["status"], "CRITICAL");
+        assert_eq!(output["threshold"], 90.0);
+        assert_eq!(output["sensor"], "TZ00");
+    }
+
+    #[test]
+    fn test_check_influx() {
+        let (_, output) = check(record(95.0), 90.0, Unit::Fahrenheit, Format::Influx);
+        assert!(output.contains(",zone=TZ00 celsius=95.00,fahrenheit=203.00,status=\"CRITICAL\",threshold=90.00 "));
+
+        let (_, output) = check(Err(TemperatureError::NoSensors), 90.0, Unit::Celsius, Format::Influx);
+        assert!(output.contains(" status=\"UNKNOWN\",error=\"No temperature data"));
+    }
+
+    #[test]
+    fn test_check_csv() {
+        let (_, output) = check(record(95.0), 90.0, Unit::Celsius, Format::Csv);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[0], "timestamp,sensor,celsius,fahrenheit,unit,value,status,threshold");
+        assert!(lines[1].ends_with(",TZ00,95.00,203.00,celsius,95.00,CRITICAL,90.00"));
+    }
+}
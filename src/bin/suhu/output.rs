@@ -0,0 +1,306 @@
+//! Output units and formats shared by every command.
+
+use std::collections::VecDeque;
+use std::env;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::ValueEnum;
+use serde_json::json;
+use tunjukin_suhu_cpu_windows::{format_rfc3339, CpuTemperature, TemperatureUnit, ThermalZoneReading};
+
+/// A temperature unit for output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Unit {
+    #[value(alias = "c")]
+    Celsius,
+    #[value(alias = "f")]
+    Fahrenheit,
+    #[value(alias = "k")]
+    Kelvin,
+}
+
+impl Unit {
+    /// Returns `reading` in this unit.
+    pub fn value(self, reading: &CpuTemperature) -> f64 {
+        self.convert(reading.celsius())
+    }
+
+    /// Converts a Celsius value to this unit.
+    pub fn convert(self, celsius: f64) -> f64 {
+        match self {
+            Unit::Celsius => celsius,
+            Unit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+            Unit::Kelvin => celsius + 273.15,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Unit::Celsius => "°C",
+            Unit::Fahrenheit => "°F",
+            Unit::Kelvin => "K",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Unit::Celsius => "celsius",
+            Unit::Fahrenheit => "fahrenheit",
+            Unit::Kelvin => "kelvin",
+        }
+    }
+
+    /// Formats `reading` as `45.2 °C`.
+    pub fn format(self, reading: &CpuTemperature) -> String {
+        format!("{:.1} {}", self.value(reading), self.symbol())
+    }
+}
+
+impl From<TemperatureUnit> for Unit {
+    fn from(unit: TemperatureUnit) -> Self {
+        match unit {
+            TemperatureUnit::Celsius => Unit::Celsius,
+            TemperatureUnit::Fahrenheit => Unit::Fahrenheit,
+            TemperatureUnit::Kelvin => Unit::Kelvin,
+        }
+    }
+}
+
+/// How readings are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Human-readable text.
+    Plain,
+    /// JSON: one object per reading, or an array for `list`.
+    Json,
+    /// Comma-separated values with a header row.
+    Csv,
+    /// InfluxDB line protocol, tagged with the host and zone.
+    Influx,
+}
+
+/// The CSV header row matching [`Record::csv`].
+pub const CSV_HEADER: &str = "timestamp,sensor,celsius,fahrenheit,unit,value";
+
+/// One reading together with where and when it was taken.
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub timestamp: SystemTime,
+    pub sensor: Option<String>,
+    pub reading: CpuTemperature,
+}
+
+impl Record {
+    /// Records `zone` at the time it was read.
+    pub fn from_zone(zone: ThermalZoneReading) -> Self {
+        Record {
+            timestamp: zone.time,
+            sensor: zone.instance_name,
+            reading: zone.temperature,
+        }
+    }
+
+    /// Formats the record as `45.2 °C`, prefixed with its sensor name if
+    /// `with_sensor` is set.
+    pub fn plain(&self, unit: Unit, with_sensor: bool) -> String {
+        if with_sensor {
+            format!("{}: {}", self.sensor.as_deref().unwrap_or("unnamed sensor"), unit.format(&self.reading))
+        } else {
+            unit.format(&self.reading)
+        }
+    }
+
+    pub fn json(&self, unit: Unit) -> serde_json::Value {
+        json!({
+            "timestamp": format_rfc3339(self.timestamp),
+            "sensor": self.sensor,
+            "celsius": self.reading.celsius(),
+            "fahrenheit": self.reading.fahrenheit(),
+            "unit": unit.name(),
+            "value": unit.value(&self.reading),
+        })
+    }
+
+    /// Formats the record as a row below [`CSV_HEADER`].
+    pub fn csv(&self, unit: Unit) -> String {
+        format!(
+            "{},{},{:.2},{:.2},{},{:.2}",
+            format_rfc3339(self.timestamp),
+            csv_field(self.sensor.as_deref().unwrap_or_default()),
+            self.reading.celsius(),
+            self.reading.fahrenheit(),
+            unit.name(),
+            unit.value(&self.reading)
+        )
+    }
+
+    /// Formats the record as an InfluxDB line-protocol point in the
+    /// `cpu_temperature` measurement, tagged with `host` and the sensor
+    /// name. `extra_fields` is appended to the field set as is and must
+    /// start with a comma.
+    pub fn influx(&self, host: &str, extra_fields: &str) -> String {
+        let mut tags = format!("host={}", influx_tag(host));
+        if let Some(sensor) = &self.sensor {
+            tags.push_str(",zone=");
+            tags.push_str(&influx_tag(sensor));
+        }
+
+        format!(
+            "cpu_temperature,{} celsius={:.2},fahrenheit={:.2}{} {}",
+            tags,
+            self.reading.celsius(),
+            self.reading.fahrenheit(),
+            extra_fields,
+            self.timestamp.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos()
+        )
+    }
+}
+
+/// Returns the machine's name for the Influx `host` tag.
+pub fn hostname() -> String {
+    env::var("COMPUTERNAME")
+        .or_else(|_| env::var("HOSTNAME"))
+        .unwrap_or_else(|_| "localhost".to_string())
+}
+
+/// Escapes a tag value for the line protocol.
+pub fn influx_tag(value: &str) -> String {
+    value.replace(',', "\\,").replace('=', "\\=").replace(' ', "\\ ")
+}
+
+/// Quotes a string field value for the line protocol.
+pub fn influx_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Quotes `value` for CSV if it contains a separator, quote or line break.
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// The bars of a sparkline, lowest first.
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// A sparkline of the last few readings, such as `▁▂▄▆█▇`.
+///
+/// Bars are scaled between the lowest and highest reading shown, over at
+/// least one degree, so a steady temperature stays flat instead of turning
+/// sensor noise into spikes.
+#[derive(Debug, Clone)]
+pub struct Sparkline {
+    width: usize,
+    values: VecDeque<f64>,
+}
+
+impl Sparkline {
+    /// Creates an empty sparkline of the last `width` readings.
+    pub fn new(width: usize) -> Self {
+        Sparkline { width, values: VecDeque::with_capacity(width) }
+    }
+
+    /// Adds a reading, forgetting the oldest once `width` are shown.
+    pub fn push(&mut self, celsius: f64) {
+        if self.width == 0 {
+            return;
+        }
+        if self.values.len() == self.width {
+            self.values.pop_front();
+        }
+        self.values.push_back(celsius);
+    }
+
+    /// Returns the bars, oldest first.
+    pub fn render(&self) -> String {
+        let low = self.values.iter().copied().fold(f64::INFINITY, f64::min);
+        let high = self.values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let span = (high - low).max(1.0);
+        let top = (SPARKS.len() - 1) as f64;
+
+        self.values
+            .iter()
+            .map(|value| SPARKS[((value - low) / span * top).round() as usize])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn record(sensor: Option<&str>) -> Record {
+        Record {
+            timestamp: UNIX_EPOCH + Duration::from_secs(1_760_400_000),
+            sensor: sensor.map(str::to_string),
+            reading: CpuTemperature::from_celsius(40.0),
+        }
+    }
+
+    #[test]
+    fn test_unit_conversion() {
+        let reading = CpuTemperature::from_celsius(40.0);
+
+        assert_eq!(Unit::Celsius.format(&reading), "40.0 °C");
+        assert_eq!(Unit::Fahrenheit.format(&reading), "104.0 °F");
+        assert_eq!(Unit::Kelvin.format(&reading), "313.1 K");
+    }
+
+    #[test]
+    fn test_record_formats() {
+        let zone = record(Some(r"ACPI\ThermalZone\TZ00_0"));
+
+        assert_eq!(zone.plain(Unit::Celsius, true), r"ACPI\ThermalZone\TZ00_0: 40.0 °C");
+        assert_eq!(record(None).plain(Unit::Celsius, false), "40.0 °C");
+        assert_eq!(zone.csv(Unit::Fahrenheit), r"2025-10-14T00:00:00.000Z,ACPI\ThermalZone\TZ00_0,40.00,104.00,fahrenheit,104.00");
+
+        let json = zone.json(Unit::Celsius);
+        assert_eq!(json["timestamp"], "2025-10-14T00:00:00.000Z");
+        assert_eq!(json["sensor"], r"ACPI\ThermalZone\TZ00_0");
+        assert_eq!(json["fahrenheit"], 104.0);
+        assert!(record(None).json(Unit::Celsius)["sensor"].is_null());
+    }
+
+    #[test]
+    fn test_influx_line() {
+        assert_eq!(
+            record(Some(r"ACPI\ThermalZone\TZ00_0")).influx("GAMING PC", ""),
+            r"cpu_temperature,host=GAMING\ PC,zone=ACPI\ThermalZone\TZ00_0 celsius=40.00,fahrenheit=104.00 1760400000000000000"
+        );
+        assert_eq!(
+            record(None).influx("pc", ",status=\"OK\""),
+            "cpu_temperature,host=pc celsius=40.00,fahrenheit=104.00,status=\"OK\" 1760400000000000000"
+        );
+        assert_eq!(influx_tag("a,b=c"), r"a\,b\=c");
+        assert_eq!(influx_string(r#"say "hi" \"#), r#""say \"hi\" \\""#);
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("TZ00"), "TZ00");
+        assert_eq!(csv_field("Core #1, \"hot\""), "\"Core #1, \"\"hot\"\"\"");
+    }
+
+    #[test]
+    fn test_sparkline() {
+        let mut sparkline = Sparkline::new(4);
+        assert_eq!(sparkline.render(), "");
+
+        for celsius in [40.0, 40.2, 40.1] {
+            sparkline.push(celsius);
+        }
+        assert_eq!(sparkline.render(), "▁▂▂");
+
+        for celsius in [44.0, 47.0] {
+            sparkline.push(celsius);
+        }
+        assert_eq!(sparkline.render(), "▁▁▅█");
+
+        let mut hidden = Sparkline::new(0);
+        hidden.push(40.0);
+        assert_eq!(hidden.render(), "");
+    }
+}
@@ -0,0 +1,160 @@
+//! `suhu serve`: answers HTTP requests with the current reading as JSON,
+//! and streams readings to WebSocket clients on `/ws`.
+
+use std::io;
+use std::net::{Shutdown, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use serde_json::json;
+use tunjukin_suhu_cpu_windows::{format_rfc3339, CpuTemperature, SamplerHandle, TemperatureError, TemperatureReader, TemperatureSampler, ThermalZoneReading};
+
+use crate::http::{self, Reply, Request, Response};
+use crate::output::{Record, Unit};
+use crate::websocket;
+
+const JSON: &str = "application/json";
+
+/// How long a WebSocket client may stall a write before it is dropped.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Serves `/temperature` and `/ws` on `listen` until the process is stopped.
+///
+/// Readings are taken with `reader`. WebSocket clients share one
+/// [`TemperatureSampler`] reading every `interval`, started when the first
+/// client connects.
+pub fn run(listen: SocketAddr, reader: Arc<TemperatureReader>, unit: Unit, interval: Duration) -> io::Result<()> {
+    let sampler = OnceLock::new();
+
+    http::serve(listen, move |request| match request.path.as_str() {
+        "/ws" => stream(request, unit, || sampler.get_or_init(|| TemperatureSampler::with_provider(Arc::clone(&reader), interval).spawn())),
+        _ => route(request, unit, || crate::read(&reader)).into(),
+    })
+}
+
+/// Answers `request`, taking a reading with `read` if it asks for one.
+fn route(request: &Request, unit: Unit, read: impl FnOnce() -> Result<Record, TemperatureError>) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/temperature") => temperature(read(), unit),
+        (_, "/temperature") => Response::method_not_allowed(),
+        _ => Response::not_found(),
+    }
+}
+
+/// Upgrades `request` to a WebSocket that receives every sample as JSON.
+fn stream<'a>(request: &Request, unit: Unit, sampler: impl FnOnce() -> &'a SamplerHandle) -> Reply {
+    let Some(key) = websocket::upgrade_key(request) else {
+        return Response::bad_request().into();
+    };
+    let key = key.to_string();
+    let samples = sampler().subscribe();
+
+    Reply::Takeover(Box::new(move |stream| {
+        stream.set_read_timeout(None)?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        websocket::accept(&mut &stream, &key)?;
+
+        let closed = Arc::new(AtomicBool::new(false));
+        {
+            let closed = Arc::clone(&closed);
+            let mut input = stream.try_clone()?;
+            thread::spawn(move || {
+                websocket::wait_for_close(&mut input);
+                closed.store(true, Ordering::Relaxed);
+            });
+        }
+
+        // A failed write means the client is gone, which is how streams end.
+        for sample in samples {
+            if closed.load(Ordering::Relaxed) {
+                let _ = websocket::send_close(&mut &stream);
+                break;
+            }
+            if websocket::send_text(&mut &stream, &sample_json(sample, unit).to_string()).is_err() {
+                break;
+            }
+        }
+
+        let _ = stream.shutdown(Shutdown::Both);
+        Ok(())
+    }))
+}
+
+/// Describes one sample for WebSocket clients.
+///
+/// The sampler reads the first thermal zone without its name, so `sensor`
+/// is always null.
+fn sample_json(sample: Result<CpuTemperature, TemperatureError>, unit: Unit) -> serde_json::Value {
+    match sample {
+        Ok(temperature) => Record::from_zone(ThermalZoneReading::new(None, temperature)).json(unit),
+        Err(e) => json!({ "timestamp": format_rfc3339(SystemTime::now()), "error": e.to_string() }),
+    }
+}
+
+/// The reading as a JSON record, or `503 Service Unavailable` with the error
+/// so health checks fail while the sensors cannot be read.
+fn temperature(record: Result<Record, TemperatureError>, unit: Unit) -> Response {
+    match record {
+        Ok(record) => Response::ok(JSON, format!("{}\n", record.json(unit))),
+        Err(e) => Response {
+            status: 503,
+            content_type: JSON,
+            body: format!("{}\n", json!({ "error": e.to_string() })),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(path: &str) -> Request {
+        Request { method: "GET".to_string(), path: path.to_string(), ..Request::default() }
+    }
+
+    fn reading() -> Result<Record, TemperatureError> {
+        Ok(Record::from_zone(ThermalZoneReading::new(Some("TZ00".to_string()), CpuTemperature::from_celsius(45.0))))
+    }
+
+    #[test]
+    fn test_temperature_route() {
+        let response = route(&get("/temperature"), Unit::Fahrenheit, reading);
+        let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.content_type, "application/json");
+        assert_eq!(body["sensor"], "TZ00");
+        assert_eq!(body["celsius"], 45.0);
+        assert_eq!(body["value"], 113.0);
+    }
+
+    #[test]
+    fn test_failed_read_is_unavailable() {
+        let response = route(&get("/temperature"), Unit::Celsius, || Err(TemperatureError::NoSensors));
+        let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+        assert_eq!(response.status, 503);
+        assert!(body["error"].as_str().unwrap().contains("No temperature data"));
+    }
+
+    #[test]
+    fn test_other_routes() {
+        let post = Request { method: "POST".to_string(), ..get("/temperature") };
+
+        assert_eq!(route(&post, Unit::Celsius, reading).status, 405);
+        assert_eq!(route(&get("/"), Unit::Celsius, reading).status, 404);
+    }
+
+    #[test]
+    fn test_sample_json() {
+        let reading = sample_json(Ok(CpuTemperature::from_celsius(45.0)), Unit::Celsius);
+        assert_eq!(reading["celsius"], 45.0);
+        assert!(reading["sensor"].is_null());
+
+        let failure = sample_json(Err(TemperatureError::Timeout), Unit::Celsius);
+        assert!(failure["error"].is_string());
+        assert!(failure["timestamp"].is_string());
+    }
+}
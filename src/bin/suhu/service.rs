@@ -0,0 +1,271 @@
+//! `suhu service`: runs the Prometheus exporter, and optionally CSV logging
+//! and the sinks of a configuration file, as a Windows service.
+//!
+//! `install` registers the service to start with Windows and passes its
+//! options, and the path of the configuration file, as launch arguments to
+//! `suhu service run`, which the service control manager invokes. Pausing
+//! the service stops logging; the exporter keeps answering scrapes until the
+//! service is stopped.
+
+use std::ffi::{OsStr, OsString};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::{Args, Subcommand};
+use tunjukin_suhu_cpu_windows::{parse_duration, Config, SamplerHandle, SinkConfig, TemperatureError, TemperatureReader};
+use windows_service::service::{
+    ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl, ServiceExitCode, ServiceInfo, ServiceStartType,
+    ServiceState, ServiceStatus, ServiceType,
+};
+use windows_service::service_control_handler::{self, ServiceControlHandlerResult, ServiceStatusHandle};
+use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+use windows_service::{define_windows_service, service_dispatcher};
+
+use crate::exporter;
+
+/// The name the service is registered under.
+const SERVICE_NAME: &str = "suhu";
+
+/// How long `uninstall` waits for a running service to stop.
+const STOP_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Subcommand)]
+pub enum ServiceAction {
+    /// Register and start the service. Requires an elevated prompt.
+    Install(ServiceOptions),
+    /// Stop and remove the service. Requires an elevated prompt.
+    Uninstall,
+    /// Run as the service; only the service control manager calls this.
+    #[command(hide = true)]
+    Run(ServiceOptions),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ServiceOptions {
+    /// Address and port the exporter listens on.
+    #[arg(long, short, value_name = "ADDRESS", default_value = "0.0.0.0:9123")]
+    listen: SocketAddr,
+
+    /// Log every sample to rotating CSV files in this directory.
+    #[arg(long, value_name = "DIRECTORY")]
+    log_dir: Option<PathBuf>,
+
+    /// Time between logged samples; 5s unless the configuration sets one.
+    #[arg(long, short, value_name = "DURATION", value_parser = parse_duration)]
+    interval: Option<Duration>,
+}
+
+impl ServiceOptions {
+    /// Returns the arguments that make `suhu` run the service with these
+    /// options and the configuration file at `config`.
+    fn launch_arguments(&self, config: Option<&Path>) -> Vec<OsString> {
+        let mut arguments: Vec<OsString> = vec!["service".into(), "run".into(), "--listen".into(), self.listen.to_string().into()];
+        if let Some(interval) = self.interval {
+            arguments.push("--interval".into());
+            arguments.push(format!("{}ms", interval.as_millis()).into());
+        }
+        if let Some(dir) = &self.log_dir {
+            arguments.push("--log-dir".into());
+            arguments.push(dir.clone().into_os_string());
+        }
+        if let Some(config) = config {
+            arguments.push("--config".into());
+            arguments.push(config.into());
+        }
+        arguments
+    }
+}
+
+/// What the service runs with: its options, the configuration and the
+/// reader built from it.
+struct Setup {
+    options: ServiceOptions,
+    config: Config,
+    reader: Arc<TemperatureReader>,
+}
+
+/// Carries out `action` with the configuration loaded from `config_path`.
+pub fn run(action: ServiceAction, config_path: Option<&Path>, config: Config, reader: Arc<TemperatureReader>) -> windows_service::Result<()> {
+    match action {
+        ServiceAction::Install(options) => install(&options, config_path),
+        ServiceAction::Uninstall => uninstall(),
+        ServiceAction::Run(options) => {
+            let _ = SETUP.set(Setup { options, config, reader });
+            service_dispatcher::start(SERVICE_NAME, ffi_service_main)
+        }
+    }
+}
+
+fn install(options: &ServiceOptions, config_path: Option<&Path>) -> windows_service::Result<()> {
+    // The service starts in the system directory, so a relative path would
+    // not find the file.
+    let config_path = config_path.map(std::path::absolute).transpose().map_err(windows_service::Error::Winapi)?;
+
+
+    let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE)?;
+    let info = ServiceInfo {
+        name: SERVICE_NAME.into(),
+        display_name: "CPU temperature (suhu)".into(),
+        service_type: ServiceType::OWN_PROCESS,
+        start_type: ServiceStartType::AutoStart,
+        error_control: ServiceErrorControl::Normal,
+        executable_path: std::env::current_exe().map_err(windows_service::Error::Winapi)?,
+        launch_arguments: options.launch_arguments(config_path.as_deref()),
+        dependencies: Vec::new(),
+        account_name: None,
+        account_password: None,
+    };
+
+    let service = manager.create_service(&info, ServiceAccess::CHANGE_CONFIG | ServiceAccess::START)?;
+    service.set_description("Serves CPU temperatures to Prometheus and logs them to CSV files.")?;
+    service.start::<&OsStr>(&[])?;
+
+    println!("Installed and started the `{}` service; metrics on http://{}/metrics", SERVICE_NAME, options.listen);
+    Ok(())
+}
+
+fn uninstall() -> windows_service::Result<()> {
+    let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)?;
+    let service = manager.open_service(SERVICE_NAME, ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE)?;
+
+    if service.query_status()?.current_state != ServiceState::Stopped {
+        service.stop()?;
+        let started = Instant::now();
+        while service.query_status()?.current_state != ServiceState::Stopped && started.elapsed() < STOP_TIMEOUT {
+            thread::sleep(Duration::from_millis(250));
+        }
+    }
+    service.delete()?;
+
+    println!("Removed the `{}` service", SERVICE_NAME);
+    Ok(())
+}
+
+/// The setup of `suhu service run`, handed to the service's own thread.
+static SETUP: OnceLock<Setup> = OnceLock::new();
+
+define_windows_service!(ffi_service_main, service_main);
+
+fn service_main(_arguments: Vec<OsString>) {
+    if let Some(setup) = SETUP.get() {
+        // Errors here cannot be reported anywhere but the status, which
+        // failed to be set.
+        let _ = run_service(setup);
+    }
+}
+
+/// Something the service has to react to.
+enum Event {
+    Control(ServiceControl),
+    ExporterStopped,
+}
+
+fn run_service(setup: &Setup) -> windows_service::Result<()> {
+    let (events, received) = mpsc::channel();
+
+    let controls = events.clone();
+    let status = service_control_handler::register(SERVICE_NAME, move |control| match control {
+        ServiceControl::Stop | ServiceControl::Shutdown | ServiceControl::Pause | ServiceControl::Continue => {
+            let _ = controls.send(Event::Control(control));
+            ServiceControlHandlerResult::NoError
+        }
+        ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+        _ => ServiceControlHandlerResult::NotImplemented,
+    })?;
+
+    let listen = setup.options.listen;
+    let reader = Arc::clone(&setup.reader);
+    thread::spawn(move || {
+        let _ = exporter::run(listen, reader);
+        let _ = events.send(Event::ExporterStopped);
+    });
+
+    let Ok(mut logging) = start_logging(setup) else {
+        // A sink of the configuration is not available in this build.
+        return set_state(&status, ServiceState::Stopped, ServiceExitCode::ServiceSpecific(2));
+    };
+    set_state(&status, ServiceState::Running, ServiceExitCode::Win32(0))?;
+
+    let exit_code = loop {
+        match received.recv() {
+            Ok(Event::Control(ServiceControl::Pause)) => {
+                if let Some(sampler) = logging.take() {
+                    sampler.stop();
+                }
+                set_state(&status, ServiceState::Paused, ServiceExitCode::Win32(0))?;
+            }
+            Ok(Event::Control(ServiceControl::Continue)) => {
+                logging = logging.or_else(|| start_logging(setup).ok().flatten());
+                set_state(&status, ServiceState::Running, ServiceExitCode::Win32(0))?;
+            }
+            Ok(Event::Control(_)) => break ServiceExitCode::Win32(0),
+            // The exporter only returns if it cannot listen.
+            Ok(Event::ExporterStopped) | Err(_) => break ServiceExitCode::ServiceSpecific(1),
+        }
+    };
+
+    if let Some(sampler) = logging {
+        sampler.stop();
+    }
+    set_state(&status, ServiceState::Stopped, exit_code)
+}
+
+/// Starts sampling into the sinks and thresholds of the configuration, with
+/// the log directory and interval of the options taking precedence, unless
+/// there is nothing to sample into.
+fn start_logging(setup: &Setup) -> Result<Option<SamplerHandle>, TemperatureError> {
+    let mut config = setup.config.clone();
+    config.interval = setup.options.interval.or(config.interval);
+    config.sinks.csv = setup.options.log_dir.clone().or(config.sinks.csv);
+
+    if config.sinks == SinkConfig::default() && config.thresholds.is_empty() {
+        return Ok(None);
+    }
+    config.spawn().map(Some)
+}
+
+fn set_state(status: &ServiceStatusHandle, state: ServiceState, exit_code: ServiceExitCode) -> windows_service::Result<()> {
+    let controls_accepted = match state {
+        ServiceState::Stopped => ServiceControlAccept::empty(),
+        _ => ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN | ServiceControlAccept::PAUSE_CONTINUE,
+    };
+
+    status.set_service_status(ServiceStatus {
+        service_type: ServiceType::OWN_PROCESS,
+        current_state: state,
+        controls_accepted,
+        exit_code,
+        checkpoint: 0,
+        wait_hint: Duration::ZERO,
+        process_id: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_launch_arguments() {
+        let options = ServiceOptions {
+            listen: "127.0.0.1:9123".parse().unwrap(),
+            log_dir: Some(PathBuf::from(r"C:\Logs\cpu")),
+            interval: Some(Duration::from_millis(1500)),
+        };
+
+        assert_eq!(
+            options.launch_arguments(None),
+            ["service", "run", "--listen", "127.0.0.1:9123", "--interval", "1500ms", "--log-dir", r"C:\Logs\cpu"]
+        );
+
+        let options = ServiceOptions { log_dir: None, interval: None, ..options };
+        assert_eq!(
+            options.launch_arguments(Some(Path::new(r"C:\ProgramData\suhu\suhu.toml"))),
+            ["service", "run", "--listen", "127.0.0.1:9123", "--config", r"C:\ProgramData\suhu\suhu.toml"]
+        );
+    }
+}
@@ -0,0 +1,396 @@
+//! `suhu tray`: shows the current temperature in the notification area.
+//!
+//! The icon is a dot coloured by [`ThermalState`], and its tooltip shows the
+//! reading. Clicking the icon opens a menu to change the unit and the
+//! interval, or to quit. Readings come from a [`TemperatureSampler`] whose
+//! callback wakes the window thread, so the tray never blocks on a query.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tunjukin_suhu_cpu_windows::{
+    CpuTemperature, SamplerHandle, TemperatureError, TemperatureReader, TemperatureSampler, ThermalState, ThermalStateMachine,
+};
+use windows::core::{w, Error, Result, PCWSTR};
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, POINT, TRUE, WPARAM};
+use windows::Win32::Graphics::Gdi::{CreateBitmap, DeleteObject};
+use windows::Win32::System::Console::FreeConsole;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::Shell::{Shell_NotifyIconW, NIF_ICON, NIF_MESSAGE, NIF_TIP, NIM_ADD, NIM_DELETE, NIM_MODIFY, NOTIFYICONDATAW};
+use windows::Win32::UI::WindowsAndMessaging::{
+    AppendMenuW, CreateIconIndirect, CreatePopupMenu, CreateWindowExW, DefWindowProcW, DestroyIcon, DestroyMenu, DestroyWindow,
+    DispatchMessageW, GetCursorPos, GetMessageW, GetSystemMetrics, PostMessageW, PostQuitMessage, RegisterClassW,
+    RegisterWindowMessageW, SetForegroundWindow, TrackPopupMenu, HICON, HMENU, ICONINFO, MENU_ITEM_FLAGS, MF_CHECKED, MF_GRAYED,
+    MF_SEPARATOR, MF_STRING, MSG, SM_CXSMICON, TPM_NONOTIFY, TPM_RETURNCMD, TPM_RIGHTBUTTON, WINDOW_EX_STYLE, WINDOW_STYLE, WM_APP,
+    WM_DESTROY, WM_LBUTTONUP, WM_RBUTTONUP, WNDCLASSW,
+};
+
+use crate::output::Unit;
+
+/// Sent by the shell when the icon is clicked.
+const WM_TRAY: u32 = WM_APP + 1;
+/// Posted by the sampler when a sample is waiting.
+const WM_SAMPLE: u32 = WM_APP + 2;
+
+const ICON_ID: u32 = 1;
+
+const ID_QUIT: usize = 1;
+const UNITS: [(usize, Unit, PCWSTR); 3] = [
+    (10, Unit::Celsius, w!("Celsius")),
+    (11, Unit::Fahrenheit, w!("Fahrenheit")),
+    (12, Unit::Kelvin, w!("Kelvin")),
+];
+const INTERVALS: [(usize, u64, PCWSTR); 4] = [
+    (20, 1, w!("Every second")),
+    (21, 5, w!("Every 5 seconds")),
+    (22, 30, w!("Every 30 seconds")),
+    (23, 60, w!("Every minute")),
+];
+
+/// The colour of the icon while the sensors cannot be read.
+const UNKNOWN_COLOR: u32 = 0x80_80_80;
+
+/// The `TaskbarCreated` message, sent when Explorer restarts and every
+/// notification icon has to be added again.
+static TASKBAR_CREATED: AtomicU32 = AtomicU32::new(0);
+
+thread_local! {
+    /// The tray, owned by the window thread.
+    static TRAY: RefCell<Option<Tray>> = const { RefCell::new(None) };
+}
+
+type Sample = std::result::Result<CpuTemperature, TemperatureError>;
+
+/// Shows the tray icon, reading with `reader` every `interval`, until Quit is
+/// chosen.
+///
+/// The process detaches from its console first, so a console window opened
+/// just for `suhu tray` closes while the icon keeps running.
+pub fn run(reader: Arc<TemperatureReader>, unit: Unit, interval: Duration) -> Result<()> {
+    unsafe {
+        let _ = FreeConsole();
+
+        let instance = GetModuleHandleW(None)?;
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(window_proc),
+            hInstance: instance.into(),
+            lpszClassName: w!("suhu-tray"),
+            ..Default::default()
+        };
+        if RegisterClassW(&class) == 0 {
+            return Err(Error::from_win32());
+        }
+
+        // A hidden top-level window rather than a message-only one, which
+        // would not receive the TaskbarCreated broadcast.
+        let window = CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            w!("suhu-tray"),
+            w!("suhu"),
+            WINDOW_STYLE::default(),
+            0,
+            0,
+            0,
+            0,
+            HWND::default(),
+            HMENU::default(),
+            instance,
+            None,
+        )?;
+        TASKBAR_CREATED.store(RegisterWindowMessageW(w!("TaskbarCreated")), Ordering::Relaxed);
+
+        let (sampler, samples) = start_sampler(window, Arc::clone(&reader), interval);
+        let tray = Tray {
+            window,
+            reader,
+            unit,
+            interval,
+            _sampler: sampler,
+            samples,
+            latest: None,
+            states: ThermalStateMachine::new(),
+            color: UNKNOWN_COLOR,
+            icon: circle_icon(UNKNOWN_COLOR)?,
+        };
+        tray.notify(NIM_ADD)?;
+        TRAY.with(|cell| *cell.borrow_mut() = Some(tray));
+
+        let mut message = MSG::default();
+        while GetMessageW(&mut message, HWND::default(), 0, 0).as_bool() {
+            DispatchMessageW(&message);
+        }
+
+        TRAY.with(|cell| cell.borrow_mut().take());
+        Ok(())
+    }
+}
+
+/// Everything the tray shows.
+struct Tray {
+    window: HWND,
+    reader: Arc<TemperatureReader>,
+    unit: Unit,
+    interval: Duration,
+    /// Kept only so the sampler runs; dropping it stops sampling.
+    _sampler: SamplerHandle,
+    samples: Receiver<Sample>,
+    latest: Option<Sample>,
+    states: ThermalStateMachine,
+    color: u32,
+    icon: HICON,
+}
+
+impl Tray {
+    /// Adds or updates the notification icon.
+    fn notify(&self, message: windows::Win32::UI::Shell::NOTIFY_ICON_MESSAGE) -> Result<()> {
+        let mut data = NOTIFYICONDATAW {
+            cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as u32,
+            hWnd: self.window,
+            uID: ICON_ID,
+            uFlags: NIF_ICON | NIF_MESSAGE | NIF_TIP,
+            uCallbackMessage: WM_TRAY,
+            hIcon: self.icon,
+            ..Default::default()
+        };
+        let tip: Vec<u16> = self.tooltip().encode_utf16().take(data.szTip.len() - 1).collect();
+        data.szTip[..tip.len()].copy_from_slice(&tip);
+
+        unsafe { Shell_NotifyIconW(message, &data).ok() }
+    }
+
+    fn tooltip(&self) -> String {
+        tooltip(self.latest.as_ref(), self.unit, self.states.state())
+    }
+
+    /// Takes every waiting sample and updates the icon.
+    fn refresh(&mut self) -> Result<()> {
+        for sample in self.samples.try_iter() {
+            if let Ok(reading) = &sample {
+                self.states.update(reading);
+            }
+            self.latest = Some(sample);
+        }
+
+        let color = match self.latest {
+            Some(Ok(_)) => state_color(self.states.state()),
+            _ => UNKNOWN_COLOR,
+        };
+        if color != self.color {
+            let icon = circle_icon(color)?;
+            let old = std::mem::replace(&mut self.icon, icon);
+            self.color = color;
+            unsafe {
+                let _ = DestroyIcon(old);
+            }
+        }
+        self.notify(NIM_MODIFY)
+    }
+
+    /// Builds the menu for the current settings.
+    fn menu(&self) -> Result<HMENU> {
+        unsafe {
+            let menu = CreatePopupMenu()?;
+            let heading: Vec<u16> = self.tooltip().encode_utf16().chain([0]).collect();
+            AppendMenuW(menu, MF_STRING | MF_GRAYED, 0, PCWSTR(heading.as_ptr()))?;
+            AppendMenuW(menu, MF_SEPARATOR, 0, None)?;
+            for (id, unit, label) in UNITS {
+                AppendMenuW(menu, MF_STRING | checked(unit == self.unit), id, label)?;
+            }
+            AppendMenuW(menu, MF_SEPARATOR, 0, None)?;
+            for (id, seconds, label) in INTERVALS {
+                AppendMenuW(menu, MF_STRING | checked(Duration::from_secs(seconds) == self.interval), id, label)?;
+            }
+            AppendMenuW(menu, MF_SEPARATOR, 0, None)?;
+            AppendMenuW(menu, MF_STRING, ID_QUIT, w!("Quit"))?;
+            Ok(menu)
+        }
+    }
+
+    /// Carries out the menu command `id`.
+    fn command(&mut self, id: usize) -> Result<()> {
+        if id == ID_QUIT {
+            unsafe {
+                let _ = self.notify(NIM_DELETE);
+                return DestroyWindow(self.window);
+            }
+        }
+        if let Some(&(_, unit, _)) = UNITS.iter().find(|(unit_id, ..)| *unit_id == id) {
+            self.unit = unit;
+        }
+        if let Some(&(_, seconds, _)) = INTERVALS.iter().find(|(interval_id, ..)| *interval_id == id) {
+            self.interval = Duration::from_secs(seconds);
+            // Replacing the handle drops the old sampler, which stops after
+            // its current sample without blocking this thread.
+            (self._sampler, self.samples) = start_sampler(self.window, Arc::clone(&self.reader), self.interval);
+        }
+        self.notify(NIM_MODIFY)
+    }
+}
+
+impl Drop for Tray {
+    fn drop(&mut self) {
+        let _ = self.notify(NIM_DELETE);
+        unsafe {
+            let _ = DestroyIcon(self.icon);
+        }
+    }
+}
+
+/// Starts a sampler over `reader` that posts [`WM_SAMPLE`] to `window` after
+/// every sample.
+fn start_sampler(window: HWND, reader: Arc<TemperatureReader>, interval: Duration) -> (SamplerHandle, Receiver<Sample>) {
+    let sampler = TemperatureSampler::with_provider(reader, interval).spawn();
+    let samples = sampler.subscribe();
+
+    // Window handles are not `Send`, but posting to one from any thread is
+    // safe.
+    let window = window.0 as isize;
+    sampler.subscribe_with(move |_| unsafe {
+        let _ = PostMessageW(HWND(window as _), WM_SAMPLE, WPARAM(0), LPARAM(0));
+    });
+
+    (sampler, samples)
+}
+
+unsafe extern "system" fn window_proc(window: HWND, message: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    match message {
+        WM_TRAY if matches!(lparam.0 as u32, WM_LBUTTONUP | WM_RBUTTONUP) => show_menu(window),
+        WM_SAMPLE => with_tray(|tray| tray.refresh()),
+        WM_DESTROY => PostQuitMessage(0),
+        _ if message != 0 && message == TASKBAR_CREATED.load(Ordering::Relaxed) => with_tray(|tray| tray.notify(NIM_ADD)),
+        _ => return DefWindowProcW(window, message, wparam, lparam),
+    }
+    LRESULT(0)
+}
+
+/// Runs `f` on the tray unless it is already in use further up the stack.
+fn with_tray(f: impl FnOnce(&mut Tray) -> Result<()>) {
+    TRAY.with(|cell| {
+        if let Ok(mut tray) = cell.try_borrow_mut() {
+            if let Some(tray) = tray.as_mut() {
+                let _ = f(tray);
+            }
+        }
+    });
+}
+
+/// Shows the menu at the cursor and carries out the chosen command.
+///
+/// The menu runs its own message loop, which keeps delivering samples, so
+/// the tray is not borrowed while it is open.
+fn show_menu(window: HWND) {
+    let mut menu = None;
+    with_tray(|tray| {
+        menu = Some(tray.menu()?);
+        Ok(())
+    });
+    let Some(menu) = menu else {
+        return;
+    };
+
+    let command = unsafe {
+        let mut point = POINT::default();
+        let _ = GetCursorPos(&mut point);
+        // Without this the menu does not close when clicking elsewhere.
+        let _ = SetForegroundWindow(window);
+        let command = TrackPopupMenu(menu, TPM_RIGHTBUTTON | TPM_RETURNCMD | TPM_NONOTIFY, point.x, point.y, 0, window, None);
+        let _ = DestroyMenu(menu);
+        command.0 as usize
+    };
+
+    if command != 0 {
+        with_tray(|tray| tray.command(command));
+    }
+}
+
+fn checked(checked: bool) -> MENU_ITEM_FLAGS {
+    if checked {
+        MF_CHECKED
+    } else {
+        MENU_ITEM_FLAGS(0)
+    }
+}
+
+/// Describes the latest sample, such as `CPU 45.2 °C (normal)`.
+fn tooltip(latest: Option<&Sample>, unit: Unit, state: ThermalState) -> String {
+    match latest {
+        None => "CPU temperature: reading…".to_string(),
+        Some(Ok(reading)) => format!("CPU {} ({})", unit.format(reading), state),
+        Some(Err(e)) => format!("CPU temperature unavailable: {}", e),
+    }
+}
+
+/// The icon colour of `state`, as `0xRRGGBB`.
+fn state_color(state: ThermalState) -> u32 {
+    match state {
+        ThermalState::Normal => 0x2E_A0_43,
+        ThermalState::Warm => 0xD4_A0_17,
+        ThermalState::Hot => 0xE8_6A_17,
+        ThermalState::Critical => 0xD0_2C_2C,
+    }
+}
+
+/// Creates a small icon showing a dot in `color`.
+fn circle_icon(color: u32) -> Result<HICON> {
+    let size = unsafe { GetSystemMetrics(SM_CXSMICON) }.max(16) as usize;
+    let (mask, pixels) = circle(size, color);
+
+    unsafe {
+        let mask = CreateBitmap(size as i32, size as i32, 1, 1, Some(mask.as_ptr().cast()));
+        let color = CreateBitmap(size as i32, size as i32, 1, 32, Some(pixels.as_ptr().cast()));
+        let icon = CreateIconIndirect(&ICONINFO { fIcon: TRUE, xHotspot: 0, yHotspot: 0, hbmMask: mask, hbmColor: color });
+        let _ = DeleteObject(mask);
+        let _ = DeleteObject(color);
+        icon
+    }
+}
+
+/// Returns the monochrome mask and the 32-bit pixels of a `size` square
+/// icon showing a filled circle in `color`.
+///
+/// Mask rows are padded to 16 bits, and set mask bits are transparent.
+fn circle(size: usize, color: u32) -> (Vec<u8>, Vec<u32>) {
+    let stride = size.div_ceil(16) * 2;
+    let mut mask = vec![0xFF; stride * size];
+    let mut pixels = vec![0; size * size];
+
+    let radius = size as f64 / 2.0;
+    for y in 0..size {
+        for x in 0..size {
+            let (dx, dy) = (x as f64 + 0.5 - radius, y as f64 + 0.5 - radius);
+            if dx * dx + dy * dy <= radius * radius {
+                pixels[y * size + x] = color;
+                mask[y * stride + x / 8] &= !(0x80 >> (x % 8));
+            }
+        }
+    }
+
+    (mask, pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tooltip() {
+        let reading = Ok(CpuTemperature::from_celsius(45.2));
+        assert_eq!(tooltip(Some(&reading), Unit::Celsius, ThermalState::Normal), "CPU 45.2 °C (normal)");
+        assert!(tooltip(Some(&Err(TemperatureError::NoSensors)), Unit::Celsius, ThermalState::Normal).starts_with("CPU temperature unavailable"));
+    }
+
+    #[test]
+    fn test_circle() {
+        let (mask, pixels) = circle(16, 0xD0_2C_2C);
+
+        assert_eq!(mask.len(), 32);
+        // Corners are transparent, the centre is opaque.
+        assert_eq!(mask[0] & 0x80, 0x80);
+        assert_eq!(pixels[0], 0);
+        assert_eq!(mask[8 * 2] & 0x80, 0);
+        assert_eq!(pixels[8 * 16 + 8], 0xD0_2C_2C);
+    }
+}
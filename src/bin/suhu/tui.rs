@@ -0,0 +1,365 @@
+//! `suhu tui`: a live terminal dashboard of every thermal zone.
+//!
+//! Readings are taken on a background thread, so the dashboard keeps
+//! answering keys while a query runs.
+
+use std::collections::VecDeque;
+use std::io;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::symbols::Marker;
+use ratatui::text::Line;
+use ratatui::widgets::{Axis, Block, Chart, Dataset, GraphType, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use tunjukin_suhu_cpu_windows::{TemperatureError, TemperatureReader, ThermalZoneReading};
+
+use crate::output::Unit;
+
+/// How much history the chart shows.
+const HISTORY: Duration = Duration::from_secs(5 * 60);
+
+/// The shortest and longest interval the keys can select.
+const MIN_INTERVAL: Duration = Duration::from_millis(250);
+const MAX_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often the dashboard redraws and checks for keys.
+const TICK: Duration = Duration::from_millis(100);
+
+/// Line colours, one per zone, repeating after the last.
+const COLORS: [Color; 6] = [Color::Cyan, Color::Yellow, Color::Magenta, Color::Green, Color::Red, Color::Blue];
+
+type Readings = Result<Vec<ThermalZoneReading>, TemperatureError>;
+
+/// Runs the dashboard, reading with `reader`, until `q`, Esc or Ctrl+C is
+/// pressed.
+pub fn run(reader: Arc<TemperatureReader>, unit: Unit, interval: Duration) -> io::Result<()> {
+    let mut terminal = ratatui::try_init()?;
+    let result = event_loop(&mut terminal, reader, Dashboard::new(unit, interval));
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, reader: Arc<TemperatureReader>, mut dashboard: Dashboard) -> io::Result<()> {
+    let (intervals, readings) = spawn_reader(reader, dashboard.interval);
+
+    loop {
+        for result in readings.try_iter() {
+            dashboard.record(dashboard.started.elapsed(), result);
+        }
+        terminal.draw(|frame| dashboard.draw(frame))?;
+
+        if !event::poll(TICK)? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            match dashboard.key(key) {
+                Some(Action::Quit) => return Ok(()),
+                Some(Action::Interval) => {
+                    let _ = intervals.send(dashboard.interval);
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+/// Reads every zone with `reader` every `interval` on a background thread,
+/// which takes a new interval, and reads at once, whenever one is sent.
+fn spawn_reader(reader: Arc<TemperatureReader>, interval: Duration) -> (Sender<Duration>, Receiver<Readings>) {
+    let (sender, readings) = mpsc::channel();
+    let (intervals, changes) = mpsc::channel();
+
+    thread::spawn(move || {
+        let mut interval = interval;
+        loop {
+            if sender.send(reader.get_all()).is_err() {
+                break;
+            }
+            match changes.recv_timeout(interval) {
+                Ok(changed) => interval = changed,
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    });
+
+    (intervals, readings)
+}
+
+/// What a key asks the event loop to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Quit,
+    Interval,
+}
+
+/// The history of one thermal zone.
+#[derive(Debug)]
+struct Zone {
+    name: String,
+    /// Seconds since the dashboard started, and degrees Celsius.
+    points: VecDeque<(f64, f64)>,
+    min: f64,
+    max: f64,
+}
+
+/// Everything on screen.
+#[derive(Debug)]
+struct Dashboard {
+    started: Instant,
+    unit: Unit,
+    interval: Duration,
+    zones: Vec<Zone>,
+    error: Option<String>,
+}
+
+impl Dashboard {
+    fn new(unit: Unit, interval: Duration) -> Self {
+        Dashboard { started: Instant::now(), unit, interval, zones: Vec::new(), error: None }
+    }
+
+    /// Adds the readings taken `at` after the dashboard started, forgetting
+    /// points older than the chart shows.
+    fn record(&mut self, at: Duration, readings: Readings) {
+        let readings = match readings {
+            Ok(readings) => readings,
+            Err(e) => {
+                self.error = Some(e.to_string());
+                return;
+            }
+        };
+        self.error = None;
+
+        let at = at.as_secs_f64();
+        for (i, reading) in readings.into_iter().enumerate() {
+            let name = reading.instance_name.unwrap_or_else(|| format!("zone{}", i));
+            let celsius = reading.temperature.celsius();
+
+            let zone = match self.zones.iter().position(|zone| zone.name == name) {
+                Some(index) => &mut self.zones[index],
+                None => {
+                    self.zones.push(Zone { name, points: VecDeque::new(), min: celsius, max: celsius });
+                    self.zones.last_mut().expect("a zone was just added")
+                }
+            };
+            zone.points.push_back((at, celsius));
+            zone.min = zone.min.min(celsius);
+            zone.max = zone.max.max(celsius);
+            while zone.points.front().is_some_and(|&(t, _)| t < at - HISTORY.as_secs_f64()) {
+                zone.points.pop_front();
+            }
+        }
+    }
+
+    fn key(&mut self, key: KeyEvent) -> Option<Action> {
+        if key.kind != KeyEventKind::Press {
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Some(Action::Quit),
+            KeyCode::Char('f') | KeyCode::Char('+') => self.set_interval(self.interval / 2),
+            KeyCode::Char('s') | KeyCode::Char('-') => self.set_interval(self.interval * 2),
+            KeyCode::Char('u') => {
+                self.unit = match self.unit {
+                    Unit::Celsius => Unit::Fahrenheit,
+                    Unit::Fahrenheit => Unit::Kelvin,
+                    Unit::Kelvin => Unit::Celsius,
+                };
+                None
+            }
+            KeyCode::Char('r') => {
+                for zone in &mut self.zones {
+                    if let Some(&(_, celsius)) = zone.points.back() {
+                        zone.min = celsius;
+                        zone.max = celsius;
+                    }
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn set_interval(&mut self, interval: Duration) -> Option<Action> {
+        let interval = interval.clamp(MIN_INTERVAL, MAX_INTERVAL);
+        if interval == self.interval {
+            return None;
+        }
+        self.interval = interval;
+        Some(Action::Interval)
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [header, chart, table] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(8),
+            Constraint::Length(self.zones.len() as u16 + 3),
+        ])
+        .areas(frame.area());
+
+        let status = match &self.error {
+            Some(error) => Line::from(format!(" read failed: {}", error)).red(),
+            None => Line::from(format!(
+                " every {} · {} · q quit  f/s faster/slower  u unit  r reset min/max",
+                interval_label(self.interval),
+                self.unit.symbol()
+            )),
+        };
+        frame.render_widget(Paragraph::new(status), header);
+
+        self.draw_chart(frame, chart);
+        self.draw_table(frame, table);
+    }
+
+    fn draw_chart(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let data: Vec<Vec<(f64, f64)>> = self
+            .zones
+            .iter()
+            .map(|zone| zone.points.iter().map(|&(t, celsius)| (t, self.unit.convert(celsius))).collect())
+            .collect();
+
+        let now = self.zones.iter().filter_map(|zone| zone.points.back()).map(|&(t, _)| t).fold(0.0, f64::max);
+        let start = (now - HISTORY.as_secs_f64()).max(0.0);
+        let (low, high) = data
+            .iter()
+            .flatten()
+            .fold(None, |range: Option<(f64, f64)>, &(_, value)| match range {
+                Some((low, high)) => Some((low.min(value), high.max(value))),
+                None => Some((value, value)),
+            })
+            .map_or((0.0, 100.0), |(low, high)| ((low - 2.0).floor(), (high + 2.0).ceil()));
+
+        let datasets = self
+            .zones
+            .iter()
+            .zip(&data)
+            .enumerate()
+            .map(|(i, (zone, points))| {
+                Dataset::default()
+                    .name(zone.name.clone())
+                    .marker(Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(COLORS[i % COLORS.len()]))
+                    .data(points)
+            })
+            .collect();
+
+        let chart = Chart::new(datasets)
+            .block(Block::bordered().title(" CPU temperature "))
+            .x_axis(Axis::default().bounds([start, now.max(start + 1.0)]).labels([
+                format!("-{}", interval_label(Duration::from_secs_f64(now - start))),
+                "now".to_string(),
+            ]))
+            .y_axis(Axis::default().bounds([low, high]).labels([
+                format!("{:.0} {}", low, self.unit.symbol()),
+                format!("{:.0} {}", high, self.unit.symbol()),
+            ]));
+        frame.render_widget(chart, area);
+    }
+
+    fn draw_table(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let value = |celsius: f64| format!("{:.1} {}", self.unit.convert(celsius), self.unit.symbol());
+        let rows = self.zones.iter().enumerate().map(|(i, zone)| {
+            let current = zone.points.back().map_or_else(String::new, |&(_, celsius)| value(celsius));
+            Row::new([zone.name.clone(), current, value(zone.min), value(zone.max)]).style(Style::default().fg(COLORS[i % COLORS.len()]))
+        });
+
+        let table = Table::new(rows, [Constraint::Fill(1), Constraint::Length(12), Constraint::Length(12), Constraint::Length(12)])
+            .header(Row::new(["Zone", "Now", "Min", "Max"]).bold())
+            .block(Block::bordered());
+        frame.render_widget(table, area);
+    }
+}
+
+/// Formats `interval` as `250ms`, `2s` or `5m`.
+fn interval_label(interval: Duration) -> String {
+    let millis = interval.as_millis();
+    if millis < 1000 {
+        format!("{}ms", millis)
+    } else if millis < 60_000 || !millis.is_multiple_of(60_000) {
+        format!("{}s", interval.as_secs())
+    } else {
+        format!("{}m", interval.as_secs() / 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tunjukin_suhu_cpu_windows::CpuTemperature;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn zone(name: Option<&str>, celsius: f64) -> ThermalZoneReading {
+        ThermalZoneReading::new(name.map(str::to_string), CpuTemperature::from_celsius(celsius))
+    }
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_record_tracks_zones() {
+        let mut dashboard = Dashboard::new(Unit::Celsius, Duration::from_secs(1));
+
+        dashboard.record(Duration::from_secs(0), Ok(vec![zone(Some("TZ00"), 40.0), zone(None, 50.0)]));
+        dashboard.record(Duration::from_secs(1), Err(TemperatureError::NoSensors));
+        assert!(dashboard.error.is_some());
+        dashboard.record(Duration::from_secs(400), Ok(vec![zone(Some("TZ00"), 60.0), zone(None, 45.0)]));
+
+        assert_eq!(dashboard.error, None);
+        assert_eq!(dashboard.zones.len(), 2);
+        assert_eq!(dashboard.zones[1].name, "zone1");
+        assert_eq!((dashboard.zones[0].min, dashboard.zones[0].max), (40.0, 60.0));
+        // The first point is older than the chart shows.
+        assert_eq!(dashboard.zones[0].points, [(400.0, 60.0)]);
+    }
+
+    #[test]
+    fn test_keys() {
+        let mut dashboard = Dashboard::new(Unit::Celsius, Duration::from_millis(500));
+
+        assert_eq!(dashboard.key(press(KeyCode::Char('f'))), Some(Action::Interval));
+        assert_eq!(dashboard.interval, MIN_INTERVAL);
+        assert_eq!(dashboard.key(press(KeyCode::Char('f'))), None);
+        assert_eq!(dashboard.key(press(KeyCode::Char('s'))), Some(Action::Interval));
+        assert_eq!(dashboard.interval, Duration::from_millis(500));
+
+        dashboard.key(press(KeyCode::Char('u')));
+        assert_eq!(dashboard.unit, Unit::Fahrenheit);
+
+        assert_eq!(dashboard.key(press(KeyCode::Char('q'))), Some(Action::Quit));
+        assert_eq!(dashboard.key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)), Some(Action::Quit));
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut dashboard = Dashboard::new(Unit::Celsius, Duration::from_secs(2));
+        dashboard.record(Duration::from_secs(0), Ok(vec![zone(Some("TZ00"), 41.5)]));
+        dashboard.record(Duration::from_secs(2), Ok(vec![zone(Some("TZ00"), 43.0)]));
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+        terminal.draw(|frame| dashboard.draw(frame)).unwrap();
+        let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+
+        assert!(screen.contains("every 2s"));
+        assert!(screen.contains("TZ00"));
+        assert!(screen.contains("43.0 °C"));
+        assert!(screen.contains("41.5 °C"));
+    }
+
+    #[test]
+    fn test_interval_label() {
+        assert_eq!(interval_label(Duration::from_millis(250)), "250ms");
+        assert_eq!(interval_label(Duration::from_secs(90)), "90s");
+        assert_eq!(interval_label(Duration::from_secs(300)), "5m");
+    }
+}
@@ -0,0 +1,227 @@
+//! The server side of RFC 6455 WebSockets, as far as pushing text goes.
+//!
+//! The server only ever sends: unmasked text frames, then a close frame.
+//! Frames from the client are read only to notice when it closes. The
+//! handshake needs SHA-1 and Base64, which are implemented here rather than
+//! pulled in as dependencies.
+
+use std::io::{self, Read, Write};
+
+use crate::http::Request;
+
+/// Appended to the client's key before hashing, as RFC 6455 specifies.
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Returns the client's `Sec-WebSocket-Key` if `request` is a valid
+/// WebSocket upgrade.
+pub fn upgrade_key(request: &Request) -> Option<&str> {
+    let upgrade = request.header("Upgrade")?;
+    let connection = request.header("Connection")?;
+    let valid = request.method == "GET"
+        && upgrade.eq_ignore_ascii_case("websocket")
+        && connection.split(',').any(|token| token.trim().eq_ignore_ascii_case("upgrade"))
+        && request.header("Sec-WebSocket-Version") == Some("13");
+
+    valid.then(|| request.header("Sec-WebSocket-Key")).flatten()
+}
+
+/// Writes the `101 Switching Protocols` response accepting `key`.
+pub fn accept(out: &mut impl Write, key: &str) -> io::Result<()> {
+    write!(
+        out,
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(key)
+    )?;
+    out.flush()
+}
+
+/// Sends `text` as one text frame.
+pub fn send_text(out: &mut impl Write, text: &str) -> io::Result<()> {
+    out.write_all(&frame(0x1, text.as_bytes()))?;
+    out.flush()
+}
+
+/// Sends a close frame without a status code.
+pub fn send_close(out: &mut impl Write) -> io::Result<()> {
+    out.write_all(&frame(0x8, &[]))?;
+    out.flush()
+}
+
+/// Reads and discards client frames until a close frame arrives or the
+/// connection ends.
+pub fn wait_for_close(input: &mut impl Read) {
+    while let Ok(opcode) = skip_frame(input) {
+        if opcode == 0x8 {
+            break;
+        }
+    }
+}
+
+/// Reads one frame, returning its opcode and discarding its payload.
+fn skip_frame(input: &mut impl Read) -> io::Result<u8> {
+    let mut header = [0u8; 2];
+    input.read_exact(&mut header)?;
+
+    let len = match header[1] & 0x7F {
+        126 => {
+            let mut len = [0u8; 2];
+            input.read_exact(&mut len)?;
+            u64::from(u16::from_be_bytes(len))
+        }
+        127 => {
+            let mut len = [0u8; 8];
+            input.read_exact(&mut len)?;
+            u64::from_be_bytes(len)
+        }
+        len => u64::from(len),
+    };
+    let mask = if header[1] & 0x80 != 0 { 4 } else { 0 };
+
+    let skipped = io::copy(&mut input.take(mask + len), &mut io::sink())?;
+    if skipped < mask + len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+
+    Ok(header[0] & 0x0F)
+}
+
+/// Encodes a final, unmasked frame.
+fn frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        len @ 0..=125 => frame.push(len as u8),
+        len @ 126..=0xFFFF => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+fn accept_key(key: &str) -> String {
+    base64(&sha1(format!("{}{}", key, HANDSHAKE_GUID).as_bytes()))
+}
+
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [0x6745_2301, 0xEFCD_AB89, 0x98BA_DCFE, 0x1032_5476, 0xC3D2_E1F0];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut words = [0u32; 80];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            words[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            words[i] = (words[i - 3] ^ words[i - 8] ^ words[i - 14] ^ words[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, word) in words.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A82_7999),
+                20..=39 => (b ^ c ^ d, 0x6ED9_EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1B_BCDC),
+                _ => (b ^ c ^ d, 0xCA62_C1D6),
+            };
+            let temp = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        for (value, add) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(add);
+        }
+    }
+
+    let mut digest = [0u8; 20];
+    for (chunk, value) in digest.chunks_exact_mut(4).zip(state) {
+        chunk.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::new();
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], chunk.get(1).copied().unwrap_or(0), chunk.get(2).copied().unwrap_or(0)];
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(bits >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upgrade_request(headers: &[(&str, &str)]) -> Request {
+        Request {
+            method: "GET".to_string(),
+            path: "/ws".to_string(),
+            headers: headers.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect(),
+        }
+    }
+
+    #[test]
+    fn test_accept_key() {
+        // The example handshake from RFC 6455, section 1.3.
+        assert_eq!(accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+        assert_eq!(base64(b"ab"), "YWI=");
+        assert_eq!(base64(b"a"), "YQ==");
+    }
+
+    #[test]
+    fn test_upgrade_key() {
+        let headers = [
+            ("Upgrade", "websocket"),
+            ("Connection", "keep-alive, Upgrade"),
+            ("Sec-WebSocket-Version", "13"),
+            ("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ=="),
+        ];
+
+        assert_eq!(upgrade_key(&upgrade_request(&headers)), Some("dGhlIHNhbXBsZSBub25jZQ=="));
+        assert_eq!(upgrade_key(&upgrade_request(&headers[1..])), None);
+    }
+
+    #[test]
+    fn test_wait_for_close() {
+        // A masked "hi" text frame, a masked close frame, then more data.
+        let mut input = io::Cursor::new(vec![0x81, 0x82, 1, 2, 3, 4, b'h' ^ 1, b'i' ^ 2, 0x88, 0x80, 1, 2, 3, 4, 0x81]);
+
+        wait_for_close(&mut input);
+        assert_eq!(input.position(), 14);
+
+        let mut truncated = io::Cursor::new(vec![0x81, 0x85, 1, 2]);
+        wait_for_close(&mut truncated);
+    }
+
+    #[test]
+    fn test_frame_lengths() {
+        assert_eq!(frame(0x1, b"hi"), [0x81, 2, b'h', b'i']);
+        assert_eq!(frame(0x8, &[]), [0x88, 0]);
+        assert_eq!(frame(0x1, &[b'x'; 300])[..4], [0x81, 126, 0x01, 0x2C]);
+        assert_eq!(frame(0x1, &vec![b'x'; 70_000])[..10], [0x81, 127, 0, 0, 0, 0, 0, 0x01, 0x11, 0x70]);
+    }
+}
@@ -0,0 +1,184 @@
+//! Readings bounded in time: a plain timeout and best-effort readings within
+//! a time budget.
+
+use std::time::{Duration, Instant};
+
+#[cfg(all(windows, feature = "native"))]
+use crate::NativeWmiProvider;
+use crate::{ACPI_QUERY, CpuTemperature, PerfCounterProvider, TemperatureError};
+
+/// One way of taking a reading, abandoned when `deadline` passes, if there
+/// is one.
+type Stage<'a> = &'a dyn Fn(Option<Instant>) -> Result<CpuTemperature, TemperatureError>;
+
+impl CpuTemperature {
+    /// Retrieves the CPU temperature like [`CpuTemperature::get`], but gives
+    /// up after `timeout`.
+    ///
+    /// A PowerShell query still running at the deadline is killed, so a hung
+    /// WMI service cannot block the caller forever. Only the ACPI thermal
+    /// zones are read: there is no fallback to other sensor classes, and with
+    /// the `native` feature COM failures are not retried through PowerShell.
+    /// Time spent waiting for the
+    /// [process-wide query limit](crate::set_max_concurrent) is not counted,
+    /// and a `timeout` too long to have a deadline, such as
+    /// [`Duration::MAX`], never expires.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`TemperatureError::Timeout`] if the query did not finish
+    /// in time, or with any error [`CpuTemperature::get`] can return.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tunjukin_suhu_cpu_windows::{CpuTemperature, TemperatureError};
+    ///
+    /// match CpuTemperature::get_with_timeout(Duration::from_secs(5)) {
+    ///     Ok(temp) => println!("{:.1}°C", temp.celsius()),
+    ///     Err(TemperatureError::Timeout) => eprintln!("WMI is not responding"),
+    ///     Err(e) => eprintln!("error: {}", e),
+    /// }
+    /// ```
+    pub fn get_with_timeout(timeout: Duration) -> Result<Self, TemperatureError> {
+        let deadline = Instant::now().checked_add(timeout);
+
+        #[cfg(all(windows, feature = "native"))]
+        return NativeWmiProvider.read_one_until(deadline);
+
+        #[cfg(not(all(windows, feature = "native")))]
+        Self::read_with(|| Self::run_powershell_within(ACPI_QUERY, deadline))
+    }
+
+    /// Returns the most accurate reading that can be obtained within
+    /// `budget`.
+    ///
+    /// Backends are tried from fastest to most accurate, each one only while
+    /// budget remains:
+    ///
+    /// 1. With the `native` feature, the ACPI thermal zones through COM (see
+    ///    `NativeWmiProvider`).
+    /// 2. The ACPI thermal zones through PowerShell.
+    /// 3. The high-precision thermal zone performance counters through
+    ///    PowerShell (see [`PerfCounterProvider`]).
+    ///
+    /// Each successful backend supersedes the previous result, so the value
+    /// returned is from the last backend that finished in time. A backend
+    /// still running when the budget runs out is killed and the best value
+    /// obtained so far is returned. The call therefore returns shortly after
+    /// `budget` at the latest, apart from time spent waiting for the
+    /// [process-wide query limit](crate::set_max_concurrent). A `budget` too
+    /// long to have a deadline, such as [`Duration::MAX`], runs every
+    /// backend to completion.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the last backend's error if no backend produced a reading
+    /// in time.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// let temp = CpuTemperature::get_within(Duration::from_millis(800))?;
+    /// println!("{:.1}°C", temp.celsius());
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    pub fn get_within(budget: Duration) -> Result<Self, TemperatureError> {
+        let acpi = |deadline| Self::run_powershell_within(ACPI_QUERY, deadline).and_then(|output| Self::read_with(|| Ok(output)));
+        let precise = |deadline| PerfCounterProvider.read_precise_until(deadline);
+
+        #[cfg(all(windows, feature = "native"))]
+        let stages: &[Stage<'_>] = &[&|deadline| NativeWmiProvider.read_one_until(deadline), &acpi, &precise];
+        #[cfg(not(all(windows, feature = "native")))]
+        let stages: &[Stage<'_>] = &[&acpi, &precise];
+
+        best_within(stages, Instant::now().checked_add(budget))
+    }
+}
+
+/// Runs `stages` in order until `deadline`, or all of them without one,
+/// keeping the last successful reading.
+fn best_within(stages: &[Stage<'_>], deadline: Option<Instant>) -> Result<CpuTemperature, TemperatureError> {
+    let mut best = None;
+    let mut last_error = None;
+
+    for stage in stages {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break;
+        }
+        match stage(deadline) {
+            Ok(reading) => best = Some(reading),
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    best.ok_or_else(|| last_error.unwrap_or(TemperatureError::Timeout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::thread;
+
+    fn fixed(celsius: f64) -> impl Fn(Option<Instant>) -> Result<CpuTemperature, TemperatureError> {
+        move |_| Ok(CpuTemperature::from_celsius(celsius))
+    }
+
+    fn failing(error: &'static str) -> impl Fn(Option<Instant>) -> Result<CpuTemperature, TemperatureError> {
+        move |_| Err(error.into())
+    }
+
+    #[test]
+    fn test_later_stage_supersedes_earlier() {
+        let deadline = Some(Instant::now() + Duration::from_secs(60));
+
+        assert_eq!(best_within(&[&fixed(40.0), &fixed(40.3)], deadline), Ok(CpuTemperature::from_celsius(40.3)));
+        assert_eq!(best_within(&[&fixed(40.0), &failing("no counters")], deadline), Ok(CpuTemperature::from_celsius(40.0)));
+        assert_eq!(best_within(&[&failing("busy"), &failing("no counters")], deadline), Err("no counters".into()));
+    }
+
+    #[test]
+    fn test_stops_when_budget_is_exhausted() {
+        let deadline = Some(Instant::now() + Duration::from_millis(20));
+        let slow = |deadline: Option<Instant>| {
+            thread::sleep(deadline.unwrap().saturating_duration_since(Instant::now()));
+            Ok(CpuTemperature::from_celsius(41.0))
+        };
+        let second_ran = Cell::new(false);
+        let second = |_| {
+            second_ran.set(true);
+            Ok(CpuTemperature::from_celsius(42.0))
+        };
+
+        assert_eq!(best_within(&[&slow, &second], deadline), Ok(CpuTemperature::from_celsius(41.0)));
+        assert!(!second_ran.get());
+    }
+
+    #[test]
+    fn test_zero_budget_tries_nothing() {
+        let result = best_within(&[&fixed(40.0)], Some(Instant::now()));
+        assert_eq!(result, Err(TemperatureError::Timeout));
+    }
+
+    #[test]
+    fn test_no_deadline_runs_every_stage() {
+        assert_eq!(best_within(&[&fixed(40.0), &fixed(40.3)], None), Ok(CpuTemperature::from_celsius(40.3)));
+    }
+
+    #[test]
+    fn test_unbounded_timeout() {
+        // Only checks that a deadline past what `Instant` can hold does not
+        // panic; the reading itself depends on the machine.
+        let _ = CpuTemperature::get_with_timeout(Duration::MAX);
+    }
+
+    #[test]
+    fn test_unbounded_budget() {
+        let _ = CpuTemperature::get_within(Duration::MAX);
+    }
+}
@@ -0,0 +1,269 @@
+//! Time-to-live caching of temperature readings.
+
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::{CpuTemperature, TemperatureError, TemperatureProvider, WmiProvider};
+
+/// A reader that reuses the last successful reading for a fixed time-to-live.
+///
+/// Every call to [`CachedReader::get`] within `ttl` of the last query returns
+/// the cached reading instead of querying WMI again. [`CachedReader::refresh`]
+/// always queries, which is how an explicit user "refresh" should be honored.
+///
+/// The reader is `Sync` when its provider is, so one instance can be shared
+/// between threads (for example behind an `Arc`). Concurrent callers that find
+/// the cache expired wait for a single query rather than each issuing their
+/// own.
+///
+/// For bursty callers, such as several UI widgets refreshing in the same
+/// frame, [`CachedReader::coalesce_window`] makes every call that arrives
+/// while a query is in flight, or within the window after it completes, share
+/// that query's result. This works even with a zero TTL.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use tunjukin_suhu_cpu_windows::CachedReader;
+///
+/// let reader = CachedReader::new(Duration::from_secs(5));
+///
+/// let first = reader.get()?;   // queries WMI
+/// let second = reader.get()?;  // served from the cache
+/// let fresh = reader.refresh()?; // queries WMI again, ignoring the TTL
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug)]
+pub struct CachedReader<P = WmiProvider> {
+    provider: P,
+    ttl: Duration,
+    coalesce_window: Option<Duration>,
+    last: Mutex<Option<LastQuery>>,
+}
+
+/// The outcome of the most recent query.
+#[derive(Debug)]
+struct LastQuery {
+    completed_at: Instant,
+    result: Result<CpuTemperature, TemperatureError>,
+}
+
+impl CachedReader {
+    /// Creates a cached reader over the default WMI provider.
+    pub fn new(ttl: Duration) -> Self {
+        Self::with_provider(WmiProvider, ttl)
+    }
+}
+
+impl<P: TemperatureProvider> CachedReader<P> {
+    /// Creates a cached reader over `provider`.
+    pub fn with_provider(provider: P, ttl: Duration) -> Self {
+        CachedReader {
+            provider,
+            ttl,
+            coalesce_window: None,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Shares one query between all calls to [`CachedReader::get`] that
+    /// arrive before that query completes or within `window` after it.
+    ///
+    /// Unlike the TTL, coalescing also shares failed results, so a burst of
+    /// callers hitting a failing sensor triggers a single query.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tunjukin_suhu_cpu_windows::CachedReader;
+    ///
+    /// let reader = CachedReader::new(Duration::ZERO).coalesce_window(Duration::from_millis(5));
+    /// ```
+    pub fn coalesce_window(mut self, window: Duration) -> Self {
+        self.coalesce_window = Some(window);
+        self
+    }
+
+    /// Returns the cached reading if it is younger than the TTL, otherwise
+    /// queries the provider and caches the result.
+    ///
+    /// Failed queries are never cached, although they are shared with
+    /// coalesced callers when a coalesce window is set.
+    pub fn get(&self) -> Result<CpuTemperature, TemperatureError> {
+        let arrived_at = Instant::now();
+        let mut last = self.lock();
+
+        if let Some(previous) = last.as_ref() {
+            if let Ok(reading) = &previous.result {
+                if previous.completed_at.elapsed() < self.ttl {
+                    return Ok(reading.clone());
+                }
+            }
+
+            if let Some(window) = self.coalesce_window {
+                if previous.completed_at.checked_add(window).is_none_or(|end| arrived_at < end) {
+                    return previous.result.clone();
+                }
+            }
+        }
+
+        self.query(&mut last)
+    }
+
+    /// Queries the provider regardless of the TTL and updates the cache.
+    ///
+    /// Use this when the user explicitly asks for a new reading.
+    pub fn refresh(&self) -> Result<CpuTemperature, TemperatureError> {
+        let mut last = self.lock();
+        self.query(&mut last)
+    }
+
+    /// Returns the configured time-to-live.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Discards the cached reading so the next [`CachedReader::get`] queries.
+    pub fn invalidate(&self) {
+        *self.lock() = None;
+    }
+
+    fn query(&self, last: &mut Option<LastQuery>) -> Result<CpuTemperature, TemperatureError> {
+        let result = self.provider.read_one();
+        *last = Some(LastQuery {
+            completed_at: Instant::now(),
+            result: result.clone(),
+        });
+        result
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<LastQuery>> {
+        self.last.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockProvider, ThermalZoneReading};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    /// A provider that takes a while to answer, like a real WMI query.
+    struct SlowProvider {
+        calls: AtomicUsize,
+    }
+
+    impl TemperatureProvider for SlowProvider {
+        fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(50));
+            Ok(vec![ThermalZoneReading::new(None, CpuTemperature::from_celsius(45.0))])
+        }
+    }
+
+    fn scripted(values: &[f64]) -> MockProvider {
+        MockProvider::sequence(values.iter().map(|&c| Ok(CpuTemperature::from_celsius(c))))
+    }
+
+    #[test]
+    fn test_get_serves_cache_within_ttl() {
+        let provider = scripted(&[40.0, 41.0]);
+        let reader = CachedReader::with_provider(&provider, Duration::from_secs(60));
+
+        assert_eq!(reader.get().unwrap().celsius(), 40.0);
+        assert_eq!(reader.get().unwrap().celsius(), 40.0);
+        assert_eq!(provider.call_count(), 1);
+    }
+
+    #[test]
+    fn test_refresh_requeries_within_ttl() {
+        let provider = scripted(&[40.0, 41.0]);
+        let reader = CachedReader::with_provider(&provider, Duration::from_secs(60));
+
+        reader.get().unwrap();
+        assert_eq!(reader.refresh().unwrap().celsius(), 41.0);
+        assert_eq!(provider.call_count(), 2);
+
+        // The refreshed value replaces the cached one.
+        assert_eq!(reader.get().unwrap().celsius(), 41.0);
+        assert_eq!(provider.call_count(), 2);
+    }
+
+    #[test]
+    fn test_expired_cache_requeries() {
+        let provider = scripted(&[40.0, 41.0]);
+        let reader = CachedReader::with_provider(&provider, Duration::ZERO);
+
+        reader.get().unwrap();
+        assert_eq!(reader.get().unwrap().celsius(), 41.0);
+        assert_eq!(provider.call_count(), 2);
+    }
+
+    #[test]
+    fn test_errors_are_not_cached() {
+        let provider = MockProvider::sequence(vec![
+            Err("busy".into()),
+            Ok(CpuTemperature::from_celsius(40.0)),
+        ]);
+        let reader = CachedReader::with_provider(&provider, Duration::from_secs(60));
+
+        assert!(reader.get().is_err());
+        assert_eq!(reader.get().unwrap().celsius(), 40.0);
+    }
+
+    #[test]
+    fn test_coalesce_window_shares_one_query() {
+        let provider = SlowProvider { calls: AtomicUsize::new(0) };
+        let reader = Arc::new(
+            CachedReader::with_provider(provider, Duration::ZERO).coalesce_window(Duration::from_millis(10)),
+        );
+        let barrier = Arc::new(Barrier::new(16));
+
+        let handles: Vec<_> = (0..16)
+            .map(|_| {
+                let reader = Arc::clone(&reader);
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    barrier.wait();
+                    reader.get()
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap().unwrap().celsius(), 45.0);
+        }
+        assert_eq!(reader.provider.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_coalesce_window_shares_errors_but_expires() {
+        let provider = MockProvider::sequence(vec![
+            Err("busy".into()),
+            Ok(CpuTemperature::from_celsius(40.0)),
+        ]);
+        let reader = CachedReader::with_provider(&provider, Duration::ZERO).coalesce_window(Duration::from_secs(60));
+
+        assert!(reader.get().is_err());
+        assert!(reader.get().is_err());
+        assert_eq!(provider.call_count(), 1);
+
+        let reader = CachedReader::with_provider(&provider, Duration::ZERO).coalesce_window(Duration::ZERO);
+        assert_eq!(reader.get().unwrap().celsius(), 40.0);
+        assert_eq!(provider.call_count(), 2);
+    }
+
+    #[test]
+    fn test_unbounded_coalesce_window() {
+        let provider = scripted(&[40.0, 41.0]);
+        let reader = CachedReader::with_provider(&provider, Duration::ZERO).coalesce_window(Duration::MAX);
+
+        assert_eq!(reader.get().unwrap().celsius(), 40.0);
+        assert_eq!(reader.get().unwrap().celsius(), 40.0);
+        assert_eq!(provider.call_count(), 1);
+    }
+}
@@ -0,0 +1,152 @@
+//! Calibration offsets for sensors that read too high or too low.
+
+use std::collections::BTreeMap;
+
+use crate::{CpuTemperature, TemperatureError, TemperatureProvider, ThermalZoneReading, WmiProvider};
+
+/// Offsets added to readings, one for every zone and one per zone.
+///
+/// Boards often report some zones a few degrees off. Collecting the
+/// corrections in a `Calibration` keeps them in one place instead of every
+/// caller; wrap a provider in a [`CalibratedProvider`] to apply them to
+/// every read.
+///
+/// A zone offset applies to zones whose instance name equals its key, or
+/// whose last `\`-separated segment does with or without its `_0` style
+/// suffix, so `TZ01` matches `ACPI\ThermalZone\TZ01_0`. It is added on top of
+/// the global offset.
+///
+/// # Example
+///
+/// ```
+/// use tunjukin_suhu_cpu_windows::{Calibration, CpuTemperature, ThermalZoneReading};
+///
+/// let calibration = Calibration::new().global_offset(-1.0).zone_offset("TZ01", -8.0);
+///
+/// let zone = ThermalZoneReading::new(Some(r"ACPI\ThermalZone\TZ01_0".to_string()), CpuTemperature::from_celsius(70.0));
+/// assert_eq!(calibration.apply(zone).temperature.celsius(), 61.0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct Calibration {
+    global: f64,
+    zones: BTreeMap<String, f64>,
+}
+
+impl Calibration {
+    /// Creates a calibration without any offset.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `celsius` to every reading.
+    pub fn global_offset(mut self, celsius: f64) -> Self {
+        self.global = celsius;
+        self
+    }
+
+    /// Adds `celsius` to readings of the zone `zone`, such as `TZ01` or
+    /// `ACPI\ThermalZone\TZ01_0`, replacing any earlier offset for it.
+    pub fn zone_offset(mut self, zone: impl Into<String>, celsius: f64) -> Self {
+        self.zones.insert(zone.into(), celsius);
+        self
+    }
+
+    /// Returns the total offset for the zone `instance_name`, in degrees
+    /// Celsius.
+    pub fn offset_for(&self, instance_name: Option<&str>) -> f64 {
+        let zone = instance_name.map_or(0.0, |name| {
+            self.zones
+                .iter()
+                .filter(|(key, _)| zone_matches(key, name))
+                .map(|(_, offset)| *offset)
+                .sum()
+        });
+        self.global + zone
+    }
+
+    /// Returns `true` if no offset is set.
+    pub fn is_empty(&self) -> bool {
+        self.global == 0.0 && self.zones.values().all(|offset| *offset == 0.0)
+    }
+
+    /// Applies the offsets of `zone` to its reading.
+    pub fn apply(&self, zone: ThermalZoneReading) -> ThermalZoneReading {
+        let offset = self.offset_for(zone.instance_name.as_deref());
+        ThermalZoneReading { temperature: CpuTemperature::from_celsius(zone.temperature.celsius() + offset), ..zone }
+    }
+}
+
+/// Returns `true` if the calibration key `key` names the zone `name`.
+pub(crate) fn zone_matches(key: &str, name: &str) -> bool {
+    let segment = name.rsplit('\\').next().unwrap_or(name);
+    name == key || segment == key || segment.rsplit_once('_').is_some_and(|(zone, _)| zone == key)
+}
+
+/// A provider whose readings are corrected by a [`Calibration`].
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::{CalibratedProvider, Calibration, TemperatureProvider};
+///
+/// let provider = CalibratedProvider::new(Calibration::new().zone_offset("TZ01", -8.0));
+/// println!("{:.1}°C", provider.read_one()?.celsius());
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CalibratedProvider<P = WmiProvider> {
+    provider: P,
+    calibration: Calibration,
+}
+
+impl CalibratedProvider {
+    /// Creates a provider applying `calibration` to the default WMI provider.
+    pub fn new(calibration: Calibration) -> Self {
+        Self::with_provider(WmiProvider, calibration)
+    }
+}
+
+impl<P> CalibratedProvider<P> {
+    /// Creates a provider applying `calibration` to `provider`.
+    pub fn with_provider(provider: P, calibration: Calibration) -> Self {
+        CalibratedProvider { provider, calibration }
+    }
+
+    /// Returns the calibration applied to readings.
+    pub fn calibration(&self) -> &Calibration {
+        &self.calibration
+    }
+}
+
+impl<P: TemperatureProvider> TemperatureProvider for CalibratedProvider<P> {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        Ok(self.provider.read()?.into_iter().map(|zone| self.calibration.apply(zone)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockProvider;
+
+    #[test]
+    fn test_offset_for() {
+        let calibration = Calibration::new().global_offset(0.5).zone_offset("TZ01", -8.0).zone_offset("package", 2.0);
+
+        assert_eq!(calibration.offset_for(Some(r"ACPI\ThermalZone\TZ01_0")), -7.5);
+        assert_eq!(calibration.offset_for(Some("TZ01")), -7.5);
+        assert_eq!(calibration.offset_for(Some(r"ACPI\ThermalZone\TZ010_0")), 0.5);
+        assert_eq!(calibration.offset_for(Some("package")), 2.5);
+        assert_eq!(calibration.offset_for(None), 0.5);
+        assert!(!calibration.is_empty());
+        assert!(Calibration::new().is_empty());
+    }
+
+    #[test]
+    fn test_calibrated_provider() {
+        let provider = CalibratedProvider::with_provider(MockProvider::fixed(CpuTemperature::from_celsius(60.0)), Calibration::new().global_offset(-3.0));
+
+        assert_eq!(provider.read_one(), Ok(CpuTemperature::from_celsius(57.0)));
+    }
+}
@@ -0,0 +1,164 @@
+//! Trying several providers in a configurable order.
+
+use std::fmt;
+use std::time::Instant;
+
+use crate::{BoxedProvider, ProviderKind, TemperatureError, TemperatureProvider, ThermalZoneReading};
+
+/// The order of [`ProviderChain::default`], from the most to the least
+/// preferred backend.
+pub const DEFAULT_CHAIN: [ProviderKind; 6] = [
+    ProviderKind::Native,
+    ProviderKind::Acpi,
+    ProviderKind::Probe,
+    ProviderKind::LibreHardwareMonitor,
+    ProviderKind::OpenHardwareMonitor,
+    ProviderKind::CoreTemp,
+];
+
+/// A provider that tries other providers in order and returns the first
+/// successful read.
+///
+/// [`WmiProvider`](crate::WmiProvider) already falls back from the ACPI
+/// thermal zones to a fixed list of other backends, but only when the zones
+/// yield nothing. A chain uses any order, including backends such as Core
+/// Temp, and moves on after every error, so a missing WMI class or a denied
+/// query does not fail the read while another backend works.
+///
+/// If every provider fails, the error of the first one is returned, since it
+/// is the backend the chain was meant to use.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::{ProviderChain, ProviderKind, TemperatureProvider};
+///
+/// // Prefer HWiNFO, then the ACPI zones.
+/// let chain = ProviderChain::from_kinds(&[ProviderKind::HwInfo, ProviderKind::Acpi])?;
+/// println!("{:.1}°C", chain.read_one()?.celsius());
+///
+/// // Or the default order: native WMI, ACPI, Win32_TemperatureProbe,
+/// // LibreHardwareMonitor, OpenHardwareMonitor and Core Temp.
+/// let chain = ProviderChain::default();
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+pub struct ProviderChain {
+    providers: Vec<BoxedProvider>,
+}
+
+impl ProviderChain {
+    /// Creates a chain without providers, whose reads fail with
+    /// [`TemperatureError::NoSensors`] until providers are added.
+    pub fn new() -> Self {
+        ProviderChain { providers: Vec::new() }
+    }
+
+    /// Creates a chain of the providers of `kinds`, in order.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`TemperatureError::InvalidArgument`] if a kind is not
+    /// available in this build.
+    pub fn from_kinds(kinds: &[ProviderKind]) -> Result<Self, TemperatureError> {
+        Ok(ProviderChain { providers: kinds.iter().map(|kind| kind.provider()).collect::<Result<_, _>>()? })
+    }
+
+    /// Appends `provider`, tried after every provider already in the chain.
+    pub fn then(mut self, provider: impl TemperatureProvider + Send + Sync + 'static) -> Self {
+        self.providers.push(Box::new(provider));
+        self
+    }
+
+    /// Returns the number of providers in the chain.
+    pub fn len(&self) -> usize {
+        self.providers.len()
+    }
+
+    /// Returns `true` if the chain has no providers.
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+}
+
+/// Creates a chain in the order of [`DEFAULT_CHAIN`], leaving out backends
+/// missing from this build. With the `native` feature, the ACPI provider is
+/// left out too, since it reads the same zones as the native one.
+impl Default for ProviderChain {
+    fn default() -> Self {
+        let native = ProviderKind::Native.provider().is_ok();
+        let providers = DEFAULT_CHAIN
+            .into_iter()
+            .filter(|kind| !(native && *kind == ProviderKind::Acpi))
+            .filter_map(|kind| kind.provider().ok())
+            .collect();
+        ProviderChain { providers }
+    }
+}
+
+impl TemperatureProvider for ProviderChain {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        self.read_first(|provider| provider.read())
+    }
+
+    /// Tries each provider in turn until one succeeds, bounding every
+    /// attempt by the same `deadline`.
+    fn read_until(&self, deadline: Instant) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        self.read_first(|provider| provider.read_until(deadline))
+    }
+}
+
+impl ProviderChain {
+    /// Returns the first successful `read` of a provider, or the first
+    /// error if every provider fails.
+    fn read_first(&self, read: impl Fn(&BoxedProvider) -> Result<Vec<ThermalZoneReading>, TemperatureError>) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        let mut first_error = None;
+        for provider in &self.providers {
+            match read(provider) {
+                Ok(zones) => return Ok(zones),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        Err(first_error.unwrap_or(TemperatureError::NoSensors))
+    }
+}
+
+impl fmt::Debug for ProviderChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProviderChain").field("len", &self.providers.len()).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CpuTemperature, MockProvider};
+
+    #[test]
+    fn test_returns_first_success() {
+        let chain = ProviderChain::new()
+            .then(MockProvider::failing(TemperatureError::AccessDenied { details: "Access denied".to_string() }))
+            .then(MockProvider::fixed(CpuTemperature::from_celsius(55.0)))
+            .then(MockProvider::fixed(CpuTemperature::from_celsius(70.0)));
+
+        assert_eq!(chain.len(), 3);
+        assert_eq!(chain.read_one(), Ok(CpuTemperature::from_celsius(55.0)));
+    }
+
+    #[test]
+    fn test_returns_first_error() {
+        let chain = ProviderChain::new().then(MockProvider::failing(TemperatureError::Timeout)).then(MockProvider::failing(TemperatureError::NoSensors));
+
+        assert_eq!(chain.read(), Err(TemperatureError::Timeout));
+        assert_eq!(ProviderChain::new().read(), Err(TemperatureError::NoSensors));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_default_leaves_out_missing_backends() {
+        // Native WMI and Core Temp need Windows.
+        assert_eq!(ProviderChain::default().len(), 4);
+        assert!(ProviderChain::from_kinds(&[ProviderKind::Acpi, ProviderKind::CoreTemp]).is_err());
+    }
+}
@@ -0,0 +1,143 @@
+//! CPU clock speed, to tell thermal throttling from an idle CPU.
+//!
+//! `Win32_Processor` reports `MaxClockSpeed`, which is the base clock, and
+//! `CurrentClockSpeed`, which many systems never update from the base clock.
+//! The "Processor Information" performance counters add
+//! `PercentProcessorPerformance`, the effective speed as a percentage of the
+//! base clock, which exceeds 100 while boosting and drops while throttling.
+
+use std::thread;
+
+use crate::{parse_format_list, record_value, CpuTemperature, TemperatureError};
+
+/// The query printing the clock speeds as one `Format-List`-style record.
+const CLOCK_QUERY: &str = "$cpu = Get-CimInstance -ClassName Win32_Processor | Select-Object -First 1; \
+    $perf = Get-CimInstance -ClassName Win32_PerfFormattedData_Counters_ProcessorInformation -Filter \"Name='_Total'\"; \
+    \"CurrentClockSpeed : $($cpu.CurrentClockSpeed)\"; \"MaxClockSpeed : $($cpu.MaxClockSpeed)\"; \
+    \"PercentProcessorPerformance : $($perf.PercentProcessorPerformance)\"";
+
+/// The clock speed of the CPU.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CpuClock {
+    /// The base clock in MHz, from `Win32_Processor.MaxClockSpeed`.
+    pub base_mhz: f64,
+    /// The clock in MHz from `Win32_Processor.CurrentClockSpeed`, which many
+    /// systems leave at the base clock.
+    pub current_mhz: f64,
+    /// The effective speed as a percentage of the base clock, or `None` if
+    /// the performance counter is unavailable.
+    pub performance_percent: Option<f64>,
+}
+
+impl CpuClock {
+    /// Reads the clock speed of the first CPU.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the query fails or reports no base clock.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tunjukin_suhu_cpu_windows::CpuClock;
+    ///
+    /// let clock = CpuClock::get()?;
+    /// println!("{:.0} MHz of {:.0} MHz base", clock.effective_mhz(), clock.base_mhz);
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    pub fn get() -> Result<Self, TemperatureError> {
+        parse_clock(&CpuTemperature::run_powershell(CLOCK_QUERY)?)
+    }
+
+    /// Returns the effective clock in MHz: the base clock scaled by
+    /// [`performance_percent`](CpuClock::performance_percent) when known,
+    /// otherwise [`current_mhz`](CpuClock::current_mhz).
+    pub fn effective_mhz(&self) -> f64 {
+        match self.performance_percent {
+            Some(percent) => self.base_mhz * percent / 100.0,
+            None => self.current_mhz,
+        }
+    }
+}
+
+/// A temperature and the clock speed it was measured at.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClockedTemperature {
+    /// The CPU temperature, as [`CpuTemperature::get`] reads it.
+    pub temperature: CpuTemperature,
+    /// The CPU clock speed.
+    pub clock: CpuClock,
+}
+
+impl ClockedTemperature {
+    /// Reads the temperature and the clock speed together.
+    ///
+    /// Both queries run at the same time, so this takes about as long as
+    /// [`CpuTemperature::get`] alone. A hot CPU whose effective clock is
+    /// below its base clock is likely throttling.
+    ///
+    /// # Errors
+    ///
+    /// Fails if either reading fails, with the temperature's error first.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tunjukin_suhu_cpu_windows::ClockedTemperature;
+    ///
+    /// let reading = ClockedTemperature::get()?;
+    /// if reading.temperature.celsius() > 90.0 && reading.clock.effective_mhz() < reading.clock.base_mhz {
+    ///     println!("Throttling at {:.0} MHz", reading.clock.effective_mhz());
+    /// }
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    pub fn get() -> Result<Self, TemperatureError> {
+        let clock = thread::spawn(CpuClock::get);
+        let temperature = CpuTemperature::get();
+        let clock = clock.join().unwrap_or_else(|_| Err(TemperatureError::Other("The clock query panicked".to_string())));
+
+        Ok(ClockedTemperature { temperature: temperature?, clock: clock? })
+    }
+}
+
+/// Parses the clock query output.
+fn parse_clock(output: &str) -> Result<CpuClock, TemperatureError> {
+    let records = parse_format_list(output);
+    let record = records.first().ok_or(TemperatureError::NoSensors)?;
+    let value = |name: &str| record_value(record, name).and_then(|value| value.parse::<f64>().ok());
+
+    let base_mhz = value("MaxClockSpeed")
+        .filter(|&mhz| mhz > 0.0)
+        .ok_or_else(|| TemperatureError::ParseError("Win32_Processor reported no MaxClockSpeed".to_string()))?;
+    Ok(CpuClock {
+        base_mhz,
+        current_mhz: value("CurrentClockSpeed").unwrap_or(base_mhz),
+        performance_percent: value("PercentProcessorPerformance").filter(|&percent| percent > 0.0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_clock() {
+        let output = "CurrentClockSpeed : 3600\r\nMaxClockSpeed : 3600\r\nPercentProcessorPerformance : 125\r\n";
+
+        let clock = parse_clock(output).unwrap();
+        assert_eq!(clock, CpuClock { base_mhz: 3600.0, current_mhz: 3600.0, performance_percent: Some(125.0) });
+        assert_eq!(clock.effective_mhz(), 4500.0);
+
+        let without_counter = parse_clock("CurrentClockSpeed : 2100\nMaxClockSpeed : 3600\nPercentProcessorPerformance : \n").unwrap();
+        assert_eq!(without_counter.performance_percent, None);
+        assert_eq!(without_counter.effective_mhz(), 2100.0);
+    }
+
+    #[test]
+    fn test_parse_clock_errors() {
+        assert_eq!(parse_clock(""), Err(TemperatureError::NoSensors));
+        assert!(matches!(parse_clock("CurrentClockSpeed : 3600\n"), Err(TemperatureError::ParseError(_))));
+    }
+}
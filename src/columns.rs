@@ -0,0 +1,124 @@
+//! Column-oriented view of all thermal zones.
+
+use crate::{is_plausible, parse_format_list, record_value, CpuTemperature, TemperatureError};
+
+/// Every thermal zone's reading, stored as one vector per field.
+///
+/// The three vectors are index-aligned: `names[i]`, `celsius[i]` and `raw[i]`
+/// all describe the same zone, and all three always have the same length.
+/// Zones appear in WMI order.
+///
+/// This layout suits bulk numeric processing: `celsius` is a contiguous
+/// `[f64]` that can be handed straight to vectorized code or serialized
+/// without walking a list of structs.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZoneColumns {
+    /// Each zone's WMI `InstanceName`, or `None` if WMI did not report one.
+    pub names: Vec<Option<String>>,
+    /// Each zone's temperature in degrees Celsius.
+    pub celsius: Vec<f64>,
+    /// Each zone's raw `CurrentTemperature`, in tenths of a Kelvin.
+    pub raw: Vec<u32>,
+}
+
+impl ZoneColumns {
+    /// Returns the number of zones.
+    pub fn len(&self) -> usize {
+        self.celsius.len()
+    }
+
+    /// Returns `true` if there are no zones.
+    pub fn is_empty(&self) -> bool {
+        self.celsius.is_empty()
+    }
+
+    /// Appends one zone to all three columns.
+    fn push(&mut self, name: Option<String>, raw: u32) {
+        self.names.push(name);
+        self.celsius.push(CpuTemperature::from_decikelvin(f64::from(raw)).celsius());
+        self.raw.push(raw);
+    }
+}
+
+impl CpuTemperature {
+    /// Reads every thermal zone with a valid reading into index-aligned
+    /// columns.
+    ///
+    /// Zones whose reading is missing or outside the plausible range are
+    /// left out of all three columns, so the columns never disagree.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the WMI query fails or no zone has a valid reading.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// let columns = CpuTemperature::get_columns()?;
+    /// let mean = columns.celsius.iter().sum::<f64>() / columns.len() as f64;
+    /// println!("{} zones, mean {:.1}°C", columns.len(), mean);
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    pub fn get_columns() -> Result<ZoneColumns, TemperatureError> {
+        let output = Self::run_wmi_query()?;
+        let columns = parse_columns(&output);
+
+        if columns.is_empty() {
+            return Err(Self::no_readings_error(&output));
+        }
+
+        Ok(columns)
+    }
+}
+
+/// Parses every plausible zone in `Format-List` output into columns.
+fn parse_columns(output: &str) -> ZoneColumns {
+    let mut columns = ZoneColumns::default();
+
+    for record in parse_format_list(output) {
+        let Some(raw) = record_value(&record, "CurrentTemperature").and_then(|value| value.parse::<u32>().ok()) else {
+            continue;
+        };
+        if !is_plausible(CpuTemperature::from_decikelvin(f64::from(raw)).celsius()) {
+            continue;
+        }
+
+        columns.push(record_value(&record, "InstanceName").map(str::to_string), raw);
+    }
+
+    columns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_columns_are_index_aligned() {
+        let output = "\r\n\
+            CurrentTemperature : 3132\r\n\
+            InstanceName       : ACPI\\ThermalZone\\TZ00_0\r\n\
+            \r\n\
+            CurrentTemperature : 0\r\n\
+            InstanceName       : ACPI\\ThermalZone\\TZ01_0\r\n\
+            \r\n\
+            CurrentTemperature : 3232\r\n\
+            \r\n";
+
+        let columns = parse_columns(output);
+
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns.names, vec![Some(r"ACPI\ThermalZone\TZ00_0".to_string()), None]);
+        assert_eq!(columns.raw, vec![3132, 3232]);
+        assert!((columns.celsius[0] - 40.05).abs() < 0.01);
+        assert!((columns.celsius[1] - 50.05).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_columns_empty_output() {
+        assert!(parse_columns("").is_empty());
+    }
+}
@@ -0,0 +1,185 @@
+//! Readings annotated with how far they can be trusted.
+
+use std::time::{Instant, SystemTime};
+
+use crate::{is_plausible, CpuTemperature, PerfCounterProvider, PerfCounterZone, ProviderKind, TemperatureError};
+
+/// Raw values, in tenths of a Kelvin, that firmware commonly reports when it
+/// has no real sensor behind a thermal zone: 0 °C, 25 °C, 300 K and 301 K
+/// (27.85 °C, the most common stuck ACPI value).
+const PLACEHOLDER_DECIKELVIN: &[i64] = &[2732, 2982, 3000, 3010];
+
+/// Readings outside this range, in Celsius, are implausible for a running
+/// CPU even though they pass the basic sanity check.
+const TYPICAL_RANGE: (f64, f64) = (15.0, 105.0);
+
+/// Readings outside this range, in Celsius, are possible but close enough to
+/// ambient or to throttling limits to deserve a second look.
+const COMFORTABLE_RANGE: (f64, f64) = (25.0, 95.0);
+
+/// How much a reading can be trusted.
+///
+/// Variants are ordered from least to most trustworthy, so
+/// `confidence >= Confidence::Medium` reads naturally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Confidence {
+    /// The value looks like a firmware placeholder or is implausible for a
+    /// running CPU. Show it, if at all, as unreliable.
+    Low,
+    /// The value is believable but came from a coarse or fallback backend,
+    /// or sits near the edge of the typical range.
+    Medium,
+    /// A fine-grained reading well within the typical range.
+    High,
+}
+
+/// A reading together with its [`Confidence`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConfidentReading {
+    /// The temperature reading.
+    pub reading: CpuTemperature,
+    /// How much `reading` can be trusted.
+    pub confidence: Confidence,
+    /// When the reading was taken. Not serialized; deserialized readings
+    /// are stamped with the moment they were deserialized.
+    #[cfg_attr(feature = "serde", serde(skip, default = "Instant::now"))]
+    pub at: Instant,
+    /// When the reading was taken, on the system clock.
+    pub time: SystemTime,
+}
+
+impl ConfidentReading {
+    /// Rates `reading`, just taken, capping it at [`Confidence::Medium`] if
+    /// `secondary` is set because it came from a coarse or fallback backend.
+    fn assess(reading: CpuTemperature, secondary: bool) -> Self {
+        let decikelvin = ((reading.celsius() + 273.15) * 10.0).round() as i64;
+        let celsius = reading.celsius();
+
+        let confidence = if PLACEHOLDER_DECIKELVIN.contains(&decikelvin) || !(TYPICAL_RANGE.0..=TYPICAL_RANGE.1).contains(&celsius) {
+            Confidence::Low
+        } else if secondary || !(COMFORTABLE_RANGE.0..=COMFORTABLE_RANGE.1).contains(&celsius) {
+            Confidence::Medium
+        } else {
+            Confidence::High
+        };
+
+        ConfidentReading { reading, confidence, at: Instant::now(), time: SystemTime::now() }
+    }
+}
+
+impl CpuTemperature {
+    /// Takes a reading and rates how trustworthy it is.
+    ///
+    /// The reading is taken like [`CpuTemperature::get`], from the ACPI
+    /// thermal zones or, when they have no usable reading, from its fallback
+    /// backends. If that fails, the thermal zone performance counters are
+    /// used instead (see [`PerfCounterProvider`]).
+    ///
+    /// The confidence is derived from:
+    ///
+    /// - **Placeholder values.** 0 °C, 25 °C, 26.85 °C and 27.85 °C are what
+    ///   firmware typically reports for a zone without a real sensor. Such
+    ///   readings are [`Confidence::Low`].
+    /// - **Sanity margin.** Readings below 15 °C or above 105 °C pass the
+    ///   basic sanity check but are implausible for a running CPU and are
+    ///   [`Confidence::Low`]. Readings below 25 °C or above 95 °C are at most
+    ///   [`Confidence::Medium`].
+    /// - **Backend.** Readings from a fallback of [`CpuTemperature::get`],
+    ///   such as `Win32_TemperatureProbe` or a hardware monitoring tool, and
+    ///   performance counter readings without the high-precision counter,
+    ///   which have whole-Kelvin resolution, are at most
+    ///   [`Confidence::Medium`].
+    ///
+    /// # Errors
+    ///
+    /// Fails with the ACPI error if neither backend produced a reading.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tunjukin_suhu_cpu_windows::{Confidence, CpuTemperature};
+    ///
+    /// let confident = CpuTemperature::get_confident()?;
+    /// let marker = if confident.confidence == Confidence::High { "" } else { " (?)" };
+    /// println!("{:.1}°C{}", confident.reading.celsius(), marker);
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    pub fn get_confident() -> Result<ConfidentReading, TemperatureError> {
+        rate_traced(Self::get_traced(), || PerfCounterProvider.read_zones())
+    }
+}
+
+/// Rates a result of [`CpuTemperature::get_traced`], or the first plausible
+/// zone of `counters` if it failed.
+fn rate_traced(
+    traced: Result<(CpuTemperature, Option<ProviderKind>), TemperatureError>,
+    counters: impl FnOnce() -> Result<Vec<PerfCounterZone>, TemperatureError>,
+) -> Result<ConfidentReading, TemperatureError> {
+    let acpi_error = match traced {
+        Ok((reading, fallback)) => return Ok(ConfidentReading::assess(reading, fallback.is_some())),
+        Err(e) => e,
+    };
+
+    counters()
+        .ok()
+        .and_then(|zones| {
+            zones
+                .into_iter()
+                .find(|zone| is_plausible(zone.best().celsius()))
+                .map(|zone| match zone.high_precision {
+                    Some(precise) => ConfidentReading::assess(precise, false),
+                    None => ConfidentReading::assess(zone.temperature, true),
+                })
+        })
+        .ok_or(acpi_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fallback, MockProvider, TemperatureProvider};
+
+    fn rate(celsius: f64, coarse: bool) -> Confidence {
+        ConfidentReading::assess(CpuTemperature::from_celsius(celsius), coarse).confidence
+    }
+
+    #[test]
+    fn test_placeholders_are_low() {
+        for &raw in PLACEHOLDER_DECIKELVIN {
+            let reading = CpuTemperature::from_decikelvin(raw as f64);
+            assert_eq!(ConfidentReading::assess(reading, false).confidence, Confidence::Low, "raw {}", raw);
+        }
+    }
+
+    #[test]
+    fn test_sanity_margin() {
+        assert_eq!(rate(10.0, false), Confidence::Low);
+        assert_eq!(rate(110.0, false), Confidence::Low);
+        assert_eq!(rate(20.0, false), Confidence::Medium);
+        assert_eq!(rate(98.0, false), Confidence::Medium);
+        assert_eq!(rate(55.0, false), Confidence::High);
+    }
+
+    #[test]
+    fn test_fallback_backend_caps_at_medium() {
+        let reading = CpuTemperature::from_celsius(55.0);
+        let no_counters = || -> Result<Vec<PerfCounterZone>, TemperatureError> { panic!("the counters should not be read") };
+
+        let acpi = rate_traced(Ok((reading.clone(), None)), no_counters).unwrap();
+        assert_eq!(acpi.confidence, Confidence::High);
+
+        let monitor = MockProvider::fixed(reading);
+        let fallbacks: [(ProviderKind, &dyn TemperatureProvider); 1] = [(ProviderKind::LibreHardwareMonitor, &monitor)];
+        let traced = fallback::recover_with(Err(TemperatureError::NoSensors), &fallbacks, |p| p.read_one());
+        let fallback = rate_traced(traced, no_counters).unwrap();
+        assert_eq!(fallback.confidence, Confidence::Medium);
+    }
+
+    #[test]
+    fn test_coarse_backend_caps_at_medium() {
+        assert_eq!(rate(55.0, true), Confidence::Medium);
+        assert!(Confidence::High > Confidence::Medium && Confidence::Medium > Confidence::Low);
+    }
+}
@@ -0,0 +1,155 @@
+//! Per-core temperatures from Core Temp's shared memory.
+//!
+//! While it runs, Core Temp publishes its readings in the
+//! `CoreTempMappingObject` file mapping as a `CORE_TEMP_SHARED_DATA`
+//! structure:
+//!
+//! | Offset | Field            | Type          |
+//! |--------|------------------|---------------|
+//! | 0      | `uiLoad`         | `u32` × 256   |
+//! | 1024   | `uiTjMax`        | `u32` × 128   |
+//! | 1536   | `uiCoreCnt`      | `u32`         |
+//! | 1540   | `uiCPUCnt`       | `u32`         |
+//! | 1544   | `fTemp`          | `f32` × 256   |
+//! | 2684   | `ucFahrenheit`   | `u8`          |
+//! | 2685   | `ucDeltaToTjMax` | `u8`          |
+//!
+//! `fTemp` holds `uiCoreCnt` cores for each of the `uiCPUCnt` CPUs, in the
+//! unit Core Temp displays, and as the distance to `TjMax` if
+//! `ucDeltaToTjMax` is set.
+//!
+//! Only available on Windows with the `shared-memory` feature.
+
+use crate::shared_memory::{self, f32_at, u32_at};
+use crate::{is_plausible, CpuTemperature, TemperatureError, TemperatureProvider, ThermalZoneReading};
+
+/// The name of Core Temp's file mapping.
+const MAPPING_NAME: &str = "CoreTempMappingObject";
+
+const TJ_MAX_OFFSET: usize = 1024;
+const CORE_COUNT_OFFSET: usize = 1536;
+const CPU_COUNT_OFFSET: usize = 1540;
+const TEMPERATURE_OFFSET: usize = 1544;
+const FAHRENHEIT_OFFSET: usize = 2684;
+const DELTA_TO_TJ_MAX_OFFSET: usize = 2685;
+
+/// The number of entries in `fTemp`.
+const MAX_TEMPERATURES: usize = 256;
+
+/// Reads per-core temperatures from Core Temp while it is running.
+///
+/// Core Temp reads every core's Digital Thermal Sensor with its own driver,
+/// so this gives accurate per-core values without loading one here. Each
+/// [`ThermalZoneReading`] is named `Core #0`, `Core #1` and so on, prefixed
+/// with `CPU 1`, `CPU 2`, ... for cores of the second CPU onwards on
+/// multi-socket machines.
+///
+/// # Errors
+///
+/// Reads fail with [`TemperatureError::NoSensors`] while Core Temp is not
+/// running.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::{CoreTempProvider, TemperatureProvider};
+///
+/// for core in CoreTempProvider.read()? {
+///     println!("{}: {:.0}°C", core.instance_name.unwrap_or_default(), core.temperature.celsius());
+/// }
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CoreTempProvider;
+
+impl TemperatureProvider for CoreTempProvider {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        cores_from(&shared_memory::read(MAPPING_NAME)?)
+    }
+}
+
+/// Parses a `CORE_TEMP_SHARED_DATA` block, failing if it holds no plausible
+/// temperature.
+fn cores_from(data: &[u8]) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+    let truncated = || TemperatureError::ParseError("the Core Temp shared memory is truncated".to_string());
+    let core_count = u32_at(data, CORE_COUNT_OFFSET).ok_or_else(truncated)? as usize;
+    let cpu_count = u32_at(data, CPU_COUNT_OFFSET).ok_or_else(truncated)? as usize;
+    let fahrenheit = *data.get(FAHRENHEIT_OFFSET).ok_or_else(truncated)? != 0;
+    let delta_to_tj_max = *data.get(DELTA_TO_TJ_MAX_OFFSET).ok_or_else(truncated)? != 0;
+
+    let mut cores = Vec::new();
+    for cpu in 0..cpu_count {
+        let tj_max = u32_at(data, TJ_MAX_OFFSET + cpu * 4).unwrap_or(0) as f32;
+        for core in 0..core_count {
+            let index = cpu * core_count + core;
+            if index >= MAX_TEMPERATURES {
+                break;
+            }
+            let Some(mut value) = f32_at(data, TEMPERATURE_OFFSET + index * 4) else {
+                break;
+            };
+            if delta_to_tj_max {
+                value = tj_max - value;
+            }
+            let value = f64::from(value);
+            let temperature = match fahrenheit {
+                true => CpuTemperature::from_celsius((value - 32.0) * 5.0 / 9.0),
+                false => CpuTemperature::from_celsius(value),
+            };
+            if !is_plausible(temperature.celsius()) {
+                continue;
+            }
+
+            let name = match cpu {
+                0 => format!("Core #{}", core),
+                _ => format!("CPU {} Core #{}", cpu, core),
+            };
+            cores.push(ThermalZoneReading::new(Some(name), temperature));
+        }
+    }
+    if cores.is_empty() {
+        return Err(TemperatureError::NoValidReadings);
+    }
+
+    Ok(cores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a shared memory block of one CPU with `temperatures`.
+    fn block(temperatures: &[f32], tj_max: u32, fahrenheit: bool, delta_to_tj_max: bool) -> Vec<u8> {
+        let mut data = vec![0u8; 2686];
+        data[TJ_MAX_OFFSET..TJ_MAX_OFFSET + 4].copy_from_slice(&tj_max.to_le_bytes());
+        data[CORE_COUNT_OFFSET..CORE_COUNT_OFFSET + 4].copy_from_slice(&(temperatures.len() as u32).to_le_bytes());
+        data[CPU_COUNT_OFFSET..CPU_COUNT_OFFSET + 4].copy_from_slice(&1u32.to_le_bytes());
+        for (i, temperature) in temperatures.iter().enumerate() {
+            let offset = TEMPERATURE_OFFSET + i * 4;
+            data[offset..offset + 4].copy_from_slice(&temperature.to_le_bytes());
+        }
+        data[FAHRENHEIT_OFFSET] = fahrenheit as u8;
+        data[DELTA_TO_TJ_MAX_OFFSET] = delta_to_tj_max as u8;
+        data
+    }
+
+    #[test]
+    fn test_parse_cores() {
+        let cores = cores_from(&block(&[45.0, 47.0], 100, false, false)).unwrap();
+        assert_eq!(cores.len(), 2);
+        assert_eq!(cores[1].instance_name.as_deref(), Some("Core #1"));
+        assert_eq!(cores[1].temperature.celsius(), 47.0);
+
+        let delta = cores_from(&block(&[55.0], 100, false, true)).unwrap();
+        assert_eq!(delta[0].temperature.celsius(), 45.0);
+
+        let fahrenheit = cores_from(&block(&[113.0], 100, true, false)).unwrap();
+        assert!((fahrenheit[0].temperature.celsius() - 45.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(cores_from(&[0; 16]), Err(TemperatureError::ParseError(_))));
+        assert_eq!(cores_from(&block(&[], 100, false, false)), Err(TemperatureError::NoValidReadings));
+    }
+}
@@ -0,0 +1,210 @@
+//! Finding out which backends work on the current machine.
+
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{is_plausible, ProviderChain, ProviderKind, TemperatureError, DEFAULT_CHAIN};
+
+/// The backends probed by [`Providers::detect`], in order of preference:
+/// [`DEFAULT_CHAIN`] followed by the other named backends, except
+/// [`ProviderKind::Msr`], which loads a kernel driver and is only probed
+/// when asked for.
+const PROBED: [ProviderKind; 9] = [
+    DEFAULT_CHAIN[0],
+    DEFAULT_CHAIN[1],
+    DEFAULT_CHAIN[2],
+    DEFAULT_CHAIN[3],
+    DEFAULT_CHAIN[4],
+    DEFAULT_CHAIN[5],
+    ProviderKind::HwInfo,
+    ProviderKind::Aida64,
+    ProviderKind::PerfCounter,
+];
+
+/// How long a probe may take before its backend counts as not working.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+static DETECTED: OnceLock<Providers> = OnceLock::new();
+
+/// How one backend fared when it was probed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderStatus {
+    /// The backend.
+    pub kind: ProviderKind,
+    /// The number of plausible readings it returned, or why it failed.
+    /// Backends missing from this build fail with
+    /// [`TemperatureError::InvalidArgument`] without being read.
+    pub result: Result<usize, TemperatureError>,
+    /// How long the read took.
+    pub elapsed: Duration,
+}
+
+impl ProviderStatus {
+    /// Returns `true` if the backend returned at least one plausible reading.
+    pub fn is_working(&self) -> bool {
+        matches!(self.result, Ok(zones) if zones > 0)
+    }
+}
+
+/// The backends that work on this machine, found by reading each once.
+///
+/// Probing takes as long as the slowest backend, since every backend is
+/// read on its own thread, so [`detect`](Providers::detect) does it once per
+/// process and caches the result. A backend still reading after ten seconds
+/// fails with [`TemperatureError::Timeout`] and is abandoned, so a hung WMI
+/// class cannot block detection. A [`chain`](Providers::chain) of the
+/// working backends then reads without waiting on the ones that fail.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::{Providers, TemperatureProvider};
+///
+/// let providers = Providers::detect();
+/// match providers.preferred() {
+///     Some(kind) => println!("reading from {}", kind),
+///     None => println!("no working temperature source"),
+/// }
+///
+/// let chain = providers.chain();
+/// println!("{:.1}°C", chain.read_one()?.celsius());
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Providers {
+    statuses: Vec<ProviderStatus>,
+}
+
+impl Providers {
+    /// Probes every backend the first time it is called, and returns the
+    /// cached result afterwards.
+    ///
+    /// Use [`probe`](Providers::probe) to probe again, such as after starting
+    /// a hardware monitoring tool. [`ProviderKind::Msr`] is not probed here,
+    /// since reading it loads `WinRing0x64.dll` and its kernel driver; pass
+    /// it to [`probe`](Providers::probe) to include it.
+    pub fn detect() -> &'static Providers {
+        DETECTED.get_or_init(|| Providers::probe(&PROBED))
+    }
+
+    /// Probes the backends of `kinds` now, without touching the cache.
+    pub fn probe(kinds: &[ProviderKind]) -> Providers {
+        Providers { statuses: probe_within(kinds, PROBE_TIMEOUT, probe) }
+    }
+
+    /// Returns how every probed backend fared, in order of preference.
+    pub fn statuses(&self) -> &[ProviderStatus] {
+        &self.statuses
+    }
+
+    /// Returns the working backends, in order of preference.
+    pub fn working(&self) -> Vec<ProviderKind> {
+        self.statuses.iter().filter(|status| status.is_working()).map(|status| status.kind).collect()
+    }
+
+    /// Returns the most preferred working backend, which a
+    /// [`chain`](Providers::chain) reads from while it keeps working.
+    pub fn preferred(&self) -> Option<ProviderKind> {
+        self.working().into_iter().next()
+    }
+
+    /// Returns a chain of the working backends, in order of preference.
+    pub fn chain(&self) -> ProviderChain {
+        ProviderChain::from_kinds(&self.working()).unwrap_or_default()
+    }
+}
+
+/// Runs `probe` for every backend of `kinds` on its own thread, giving up
+/// on those still running after `timeout`.
+fn probe_within(kinds: &[ProviderKind], timeout: Duration, probe: fn(ProviderKind, Instant) -> ProviderStatus) -> Vec<ProviderStatus> {
+    let started = Instant::now();
+    let deadline = started + timeout;
+    let probes: Vec<_> = kinds
+        .iter()
+        .map(|&kind| {
+            let (sender, receiver) = mpsc::channel();
+            thread::spawn(move || {
+                let _ = sender.send(probe(kind, deadline));
+            });
+            receiver
+        })
+        .collect();
+
+    probes
+        .into_iter()
+        .zip(kinds)
+        .map(|(receiver, &kind)| match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(status) => status,
+            Err(RecvTimeoutError::Timeout) => ProviderStatus { kind, result: Err(TemperatureError::Timeout), elapsed: started.elapsed() },
+            Err(RecvTimeoutError::Disconnected) => panicked(kind),
+        })
+        .collect()
+}
+
+/// Reads `kind` once, giving up at `deadline`.
+fn probe(kind: ProviderKind, deadline: Instant) -> ProviderStatus {
+    let started = Instant::now();
+    let result = kind
+        .provider()
+        .and_then(|provider| provider.read_until(deadline))
+        .map(|zones| zones.iter().filter(|zone| is_plausible(zone.temperature.celsius())).count());
+    ProviderStatus { kind, result, elapsed: started.elapsed() }
+}
+
+/// The status of a backend whose probe panicked.
+fn panicked(kind: ProviderKind) -> ProviderStatus {
+    ProviderStatus { kind, result: Err(TemperatureError::Other(format!("probing {} panicked", kind))), elapsed: Duration::ZERO }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(kind: ProviderKind, result: Result<usize, TemperatureError>) -> ProviderStatus {
+        ProviderStatus { kind, result, elapsed: Duration::ZERO }
+    }
+
+    #[test]
+    fn test_working_keeps_order() {
+        let providers = Providers {
+            statuses: vec![
+                status(ProviderKind::Acpi, Err(TemperatureError::NoSensors)),
+                status(ProviderKind::Probe, Ok(0)),
+                status(ProviderKind::LibreHardwareMonitor, Ok(3)),
+                status(ProviderKind::CoreTemp, Ok(8)),
+            ],
+        };
+
+        assert_eq!(providers.working(), [ProviderKind::LibreHardwareMonitor, ProviderKind::CoreTemp]);
+        assert_eq!(providers.preferred(), Some(ProviderKind::LibreHardwareMonitor));
+    }
+
+    #[test]
+    fn test_hung_probe_times_out() {
+        let statuses = probe_within(&[ProviderKind::Acpi, ProviderKind::Probe], Duration::from_millis(20), |kind, _| {
+            if kind == ProviderKind::Probe {
+                thread::sleep(Duration::from_secs(5));
+            }
+            status(kind, Ok(2))
+        });
+
+        assert_eq!(statuses[0].result, Ok(2));
+        assert_eq!(statuses[1].result, Err(TemperatureError::Timeout));
+    }
+
+    #[test]
+    fn test_msr_is_not_probed_by_default() {
+        assert!(!PROBED.contains(&ProviderKind::Msr));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_missing_backend_is_not_read() {
+        let providers = Providers::probe(&[ProviderKind::CoreTemp]);
+
+        assert!(matches!(providers.statuses()[0].result, Err(TemperatureError::InvalidArgument(_))));
+        assert!(providers.chain().is_empty());
+    }
+}
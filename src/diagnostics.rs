@@ -0,0 +1,199 @@
+//! Environment and query diagnostics for bug reports.
+
+use std::fmt;
+
+use crate::{is_plausible, parse_format_list, record_value, ACPI_QUERY, CpuTemperature, TemperatureError};
+
+/// Collects version and elevation details from Windows PowerShell.
+const ENVIRONMENT_PROBE: &str = "\
+$principal = New-Object Security.Principal.WindowsPrincipal([Security.Principal.WindowsIdentity]::GetCurrent()); \
+'OS=' + [Environment]::OSVersion.VersionString; \
+'PS=' + $PSVersionTable.PSVersion.ToString(); \
+'ELEVATED=' + $principal.IsInRole([Security.Principal.WindowsBuiltInRole]::Administrator)";
+
+/// Reports the PowerShell 7 version.
+const PWSH_PROBE: &str = "$PSVersionTable.PSVersion.ToString()";
+
+impl CpuTemperature {
+    /// Gathers a plain-text diagnostic report for bug reports.
+    ///
+    /// The report contains the crate version, target platform, Windows
+    /// version, Windows PowerShell and PowerShell 7 (`pwsh`) availability and
+    /// versions, whether the process is elevated, the exact query command, the
+    /// number of sensor records found, every reading rejected during parsing
+    /// (with the reason), and the raw query output. Nothing is redacted; the
+    /// report only describes local thermal sensors and the shell environment.
+    ///
+    /// This never fails: problems while gathering a detail are written into
+    /// the report in place of that detail.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// println!("{}", CpuTemperature::diagnostics());
+    /// ```
+    pub fn diagnostics() -> String {
+        let environment = Self::run_powershell(ENVIRONMENT_PROBE);
+        let probe_field = |key: &str| match &environment {
+            Ok(output) => output
+                .lines()
+                .find_map(|line| line.trim().strip_prefix(key)?.strip_prefix('='))
+                .map(|value| value.trim().to_string())
+                .ok_or_else(|| TemperatureError::Other("not reported".to_string())),
+            Err(e) => Err(e.clone()),
+        };
+
+        Report {
+            os_version: probe_field("OS"),
+            powershell: probe_field("PS"),
+            pwsh: Self::run_shell("pwsh", PWSH_PROBE).map(|version| version.trim().to_string()),
+            elevated: probe_field("ELEVATED"),
+            command: format!("powershell -NoProfile -Command \"{}\"", ACPI_QUERY),
+            output: Self::run_wmi_query(),
+        }
+        .to_string()
+    }
+}
+
+/// Everything gathered for a diagnostic report.
+struct Report {
+    os_version: Result<String, TemperatureError>,
+    powershell: Result<String, TemperatureError>,
+    pwsh: Result<String, TemperatureError>,
+    elevated: Result<String, TemperatureError>,
+    command: String,
+    output: Result<String, TemperatureError>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let detail = |result: &Result<String, TemperatureError>| match result {
+            Ok(value) => value.clone(),
+            Err(e) => format!("unavailable ({})", e),
+        };
+
+        writeln!(f, "tunjukin_suhu_cpu_windows diagnostics")?;
+        writeln!(f, "=====================================")?;
+        writeln!(f, "Crate version:       {}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(f, "Target:              {} {}", std::env::consts::OS, std::env::consts::ARCH)?;
+        writeln!(f, "OS version:          {}", detail(&self.os_version))?;
+        writeln!(f, "Windows PowerShell:  {}", detail(&self.powershell))?;
+        writeln!(f, "PowerShell 7 (pwsh): {}", detail(&self.pwsh))?;
+        writeln!(f, "Elevated:            {}", detail(&self.elevated))?;
+        writeln!(f, "Query command:       {}", self.command)?;
+
+        match &self.output {
+            Ok(output) => {
+                let scan = scan_readings(output);
+                writeln!(f, "Sensor records:      {}", scan.records)?;
+                writeln!(f, "Accepted readings:   {}", scan.accepted.len())?;
+                for (name, celsius) in &scan.accepted {
+                    writeln!(f, "  - {}: {:.2} °C", name, celsius)?;
+                }
+                writeln!(f, "Rejected readings:   {}", scan.rejected.len())?;
+                for rejection in &scan.rejected {
+                    writeln!(f, "  - {}", rejection)?;
+                }
+                writeln!(f)?;
+                writeln!(f, "Raw query output:")?;
+                writeln!(f, "-----------------")?;
+                writeln!(f, "{}", output.trim_end())?;
+            }
+            Err(e) => {
+                writeln!(f, "Query error:         {}", e)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The outcome of checking every record in the query output.
+struct Scan {
+    records: usize,
+    accepted: Vec<(String, f64)>,
+    rejected: Vec<String>,
+}
+
+/// Classifies every thermal zone record as accepted or rejected.
+fn scan_readings(output: &str) -> Scan {
+    let records = parse_format_list(output);
+    let mut scan = Scan {
+        records: records.len(),
+        accepted: Vec::new(),
+        rejected: Vec::new(),
+    };
+
+    for (index, record) in records.iter().enumerate() {
+        let name = record_value(record, "InstanceName")
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("record {}", index + 1));
+
+        match record_value(record, "CurrentTemperature") {
+            None => scan.rejected.push(format!("{}: no CurrentTemperature value", name)),
+            Some(raw) => match raw.parse::<f64>() {
+                Err(_) => scan.rejected.push(format!("{}: unparsable CurrentTemperature '{}'", name, raw)),
+                Ok(value) => {
+                    let celsius = CpuTemperature::from_decikelvin(value).celsius();
+                    if is_plausible(celsius) {
+                        scan.accepted.push((name, celsius));
+                    } else {
+                        scan.rejected.push(format!(
+                            "{}: raw value {} ({:.2} °C) is outside the plausible range",
+                            name, raw, celsius
+                        ));
+                    }
+                }
+            },
+        }
+    }
+
+    scan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_OUTPUT: &str = r#"
+CurrentTemperature : 3132
+InstanceName       : ACPI\ThermalZone\TZ00_0
+
+CurrentTemperature : 0
+InstanceName       : ACPI\ThermalZone\TZ01_0
+
+InstanceName       : ACPI\ThermalZone\TZ02_0
+"#;
+
+    #[test]
+    fn test_scan_readings_reports_rejections() {
+        let scan = scan_readings(SAMPLE_OUTPUT);
+
+        assert_eq!(scan.records, 3);
+        assert_eq!(scan.accepted.len(), 1);
+        assert_eq!(scan.rejected.len(), 2);
+        assert!(scan.rejected[0].contains("TZ01_0") && scan.rejected[0].contains("plausible range"));
+        assert!(scan.rejected[1].contains("no CurrentTemperature"));
+    }
+
+    #[test]
+    fn test_render_includes_details_and_errors() {
+        let report = Report {
+            os_version: Ok("Microsoft Windows NT 10.0.22631.0".to_string()),
+            powershell: Ok("5.1.22621.2506".to_string()),
+            pwsh: Err("not found".into()),
+            elevated: Ok("False".to_string()),
+            command: "powershell -NoProfile -Command \"...\"".to_string(),
+            output: Ok(SAMPLE_OUTPUT.to_string()),
+        }
+        .to_string();
+
+        assert!(report.contains("OS version:          Microsoft Windows NT 10.0.22631.0"));
+        assert!(report.contains("PowerShell 7 (pwsh): unavailable (not found)"));
+        assert!(report.contains("Sensor records:      3"));
+        assert!(report.contains("Rejected readings:   2"));
+        assert!(report.contains(r"InstanceName       : ACPI\ThermalZone\TZ02_0"));
+    }
+}
@@ -0,0 +1,216 @@
+//! Drive temperatures from S.M.A.R.T. data and the storage counters.
+//!
+//! `MSStorageDriver_ATAPISmartData` in the `root/wmi` namespace returns each
+//! ATA drive's raw S.M.A.R.T. attribute table as `VendorSpecific`: a two-byte
+//! version followed by 30 entries of 12 bytes, each starting with the
+//! attribute ID. Attribute 194 holds the drive temperature, and attribute 190
+//! the airflow temperature some drives report instead, with the degrees
+//! Celsius in the first byte of the raw value. Reading it requires
+//! administrator rights.
+//!
+//! NVMe drives have no ATA attribute table. Their composite temperature is
+//! read from `MSFT_StorageReliabilityCounter` in the
+//! `root/microsoft/windows/storage` namespace, associated with each drive's
+//! `MSFT_PhysicalDisk`, in whole degrees Celsius.
+
+use crate::sensor::read_tagged;
+use crate::{
+    is_plausible, parse_records, record_value, CpuTemperature, SensorKind, SensorProvider, SensorReading, TemperatureError, TemperatureProvider,
+    ThermalZoneReading,
+};
+
+/// The query printing every drive's attribute table as `Format-List`-style
+/// records. The table is joined by hand because `Format-List` truncates
+/// arrays.
+const SMART_QUERY: &str = "Get-CimInstance -Namespace root/wmi -ClassName MSStorageDriver_ATAPISmartData | \
+    ForEach-Object { \"InstanceName : $($_.InstanceName)\"; \"VendorSpecific : $($_.VendorSpecific -join ' ')\"; '' }";
+
+/// The query printing the model, disk number and temperature of every NVMe
+/// drive. Bus type 17 is NVMe.
+const NVME_QUERY: &str = "Get-CimInstance -Namespace root/microsoft/windows/storage -ClassName MSFT_PhysicalDisk -Filter 'BusType = 17' | \
+    ForEach-Object { $counter = $_ | Get-CimAssociatedInstance -ResultClassName MSFT_StorageReliabilityCounter; \
+    \"Model : $($_.Model)\"; \"DeviceId : $($_.DeviceId)\"; \"Temperature : $($counter.Temperature)\"; '' }";
+
+/// The S.M.A.R.T. attribute holding the drive temperature.
+const TEMPERATURE_ATTRIBUTE: u8 = 194;
+/// The S.M.A.R.T. attribute holding the airflow temperature.
+const AIRFLOW_TEMPERATURE_ATTRIBUTE: u8 = 190;
+
+/// The length of the version that precedes the attribute entries.
+const TABLE_HEADER_LEN: usize = 2;
+/// The length of one attribute entry.
+const ATTRIBUTE_LEN: usize = 12;
+/// The offset of the raw value within an attribute entry.
+const RAW_VALUE_OFFSET: usize = 5;
+
+/// Reads drive temperatures from their S.M.A.R.T. data.
+///
+/// Each drive that reports a temperature is named by its WMI instance name,
+/// such as `IDE\DiskSamsung_SSD_870_EVO_1TB___SVT01B6Q\5&1b9a0b1&0&1.0.0_0`.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::{DiskTemperature, TemperatureProvider};
+///
+/// for drive in DiskTemperature.read()? {
+///     println!("{}: {:.0}°C", drive.instance_name.unwrap_or_default(), drive.temperature.celsius());
+/// }
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiskTemperature;
+
+impl TemperatureProvider for DiskTemperature {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        drives_from(&CpuTemperature::run_powershell(SMART_QUERY)?)
+    }
+}
+
+impl SensorProvider for DiskTemperature {
+    fn read_sensors(&self) -> Result<Vec<SensorReading>, TemperatureError> {
+        read_tagged(self, SensorKind::Disk)
+    }
+}
+
+/// Reads the composite temperature of NVMe drives from the storage
+/// reliability counters.
+///
+/// S.M.A.R.T. over ATA does not cover NVMe drives, so
+/// [`DiskTemperature`] never lists them. Each NVMe drive that reports a
+/// temperature is named by its model and disk number, such as
+/// `Samsung SSD 980 PRO 1TB (disk 0)`. Reading the counters requires
+/// administrator rights.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::{NvmeTemperature, TemperatureProvider};
+///
+/// for drive in NvmeTemperature.read()? {
+///     println!("{}: {:.0}°C", drive.instance_name.unwrap_or_default(), drive.temperature.celsius());
+/// }
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NvmeTemperature;
+
+impl TemperatureProvider for NvmeTemperature {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        nvme_drives_from(&CpuTemperature::run_powershell(NVME_QUERY)?)
+    }
+}
+
+impl SensorProvider for NvmeTemperature {
+    fn read_sensors(&self) -> Result<Vec<SensorReading>, TemperatureError> {
+        read_tagged(self, SensorKind::Disk)
+    }
+}
+
+/// Parses the query output, failing if it lists no drive with a temperature.
+fn drives_from(output: &str) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+    parse_records(output, |record| {
+        let table: Vec<u8> = record_value(record, "VendorSpecific")?
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .ok()?;
+
+        Some(ThermalZoneReading::new(
+            record_value(record, "InstanceName").map(str::to_string),
+            CpuTemperature::from_celsius(smart_temperature(&table)?),
+        ))
+    })
+}
+
+/// Parses the NVMe query output, failing if it lists no drive with a
+/// temperature.
+fn nvme_drives_from(output: &str) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+    parse_records(output, |record| {
+        // Drives that do not report a temperature leave it empty or 0.
+        let celsius: f64 = record_value(record, "Temperature")?.parse().ok()?;
+        let name = drive_name(record_value(record, "Model"), record_value(record, "DeviceId"));
+        (celsius > 0.0 && is_plausible(celsius)).then(|| ThermalZoneReading::new(Some(name), CpuTemperature::from_celsius(celsius)))
+    })
+}
+
+/// Names a drive by its model and disk number.
+fn drive_name(model: Option<&str>, device_id: Option<&str>) -> String {
+    let model = model.unwrap_or("NVMe drive");
+    match device_id {
+        Some(device_id) => format!("{} (disk {})", model, device_id),
+        None => model.to_string(),
+    }
+}
+
+/// Returns the temperature in a S.M.A.R.T. attribute table, preferring the
+/// drive temperature over the airflow temperature.
+fn smart_temperature(table: &[u8]) -> Option<f64> {
+    let raw_value = |id: u8| {
+        table
+            .get(TABLE_HEADER_LEN..)?
+            .chunks_exact(ATTRIBUTE_LEN)
+            .find(|attribute| attribute[0] == id)
+            .map(|attribute| f64::from(attribute[RAW_VALUE_OFFSET]))
+            .filter(|&celsius| celsius > 0.0 && is_plausible(celsius))
+    };
+
+    raw_value(TEMPERATURE_ATTRIBUTE).or_else(|| raw_value(AIRFLOW_TEMPERATURE_ATTRIBUTE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds an attribute table holding `attributes` as `(id, first raw byte)`.
+    fn table(attributes: &[(u8, u8)]) -> String {
+        let mut bytes = vec![0u8; TABLE_HEADER_LEN + 30 * ATTRIBUTE_LEN];
+        bytes[0] = 16;
+        for (i, &(id, raw)) in attributes.iter().enumerate() {
+            let entry = TABLE_HEADER_LEN + i * ATTRIBUTE_LEN;
+            bytes[entry] = id;
+            bytes[entry + 3] = 100;
+            bytes[entry + RAW_VALUE_OFFSET] = raw;
+        }
+        bytes.iter().map(u8::to_string).collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn test_parse_drives() {
+        let output = format!(
+            "InstanceName : IDE\\DiskSSD_0\r\nVendorSpecific : {}\r\n\r\n\
+             InstanceName : IDE\\DiskHDD_1\r\nVendorSpecific : {}\r\n\r\n\
+             InstanceName : IDE\\DiskOld_2\r\nVendorSpecific : {}\r\n\r\n",
+            table(&[(9, 200), (194, 38)]),
+            table(&[(190, 41)]),
+            table(&[(9, 200)])
+        );
+
+        let drives = drives_from(&output).unwrap();
+        assert_eq!(drives.len(), 2);
+        assert_eq!(drives[0].instance_name.as_deref(), Some(r"IDE\DiskSSD_0"));
+        assert_eq!(drives[0].temperature.celsius(), 38.0);
+        assert_eq!(drives[1].temperature.celsius(), 41.0);
+    }
+
+    #[test]
+    fn test_parse_nvme_drives() {
+        let output = "Model : Samsung SSD 980 PRO 1TB\r\nDeviceId : 0\r\nTemperature : 44\r\n\r\n\
+                      Model : WD_BLACK SN850X 2000GB\r\nDeviceId : 1\r\nTemperature : \r\n\r\n\
+                      Model : KINGSTON SNV2S500G\r\nDeviceId : 2\r\nTemperature : 0\r\n\r\n";
+
+        let drives = nvme_drives_from(output).unwrap();
+        assert_eq!(drives.len(), 1);
+        assert_eq!(drives[0].instance_name.as_deref(), Some("Samsung SSD 980 PRO 1TB (disk 0)"));
+        assert_eq!(drives[0].temperature.celsius(), 44.0);
+
+        assert_eq!(nvme_drives_from(""), Err(TemperatureError::NoSensors));
+        assert_eq!(drive_name(None, None), "NVMe drive");
+    }
+
+    #[test]
+    fn test_drive_errors() {
+        assert_eq!(drives_from(""), Err(TemperatureError::NoSensors));
+        assert_eq!(drives_from("InstanceName : IDE\\Disk\nVendorSpecific : 16 0\n"), Err(TemperatureError::NoValidReadings));
+    }
+}
@@ -0,0 +1,56 @@
+//! Durations written for people, as in configuration files and options.
+
+use std::time::Duration;
+
+use crate::TemperatureError;
+
+/// Parses a duration such as `2s`, `500ms`, `1.5m` or `1h`. A bare number is
+/// taken as seconds.
+///
+/// # Errors
+///
+/// Fails with [`TemperatureError::InvalidArgument`] if `text` is not a
+/// number followed by one of the units above.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use tunjukin_suhu_cpu_windows::parse_duration;
+///
+/// assert_eq!(parse_duration("1.5m")?, Duration::from_secs(90));
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+pub fn parse_duration(text: &str) -> Result<Duration, TemperatureError> {
+    let text = text.trim();
+    let invalid = || TemperatureError::InvalidArgument(format!("invalid duration '{}'", text));
+    let split = text.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+
+    let value: f64 = number.parse().map_err(|_| invalid())?;
+    let seconds = match unit.trim() {
+        "ms" => value / 1000.0,
+        "" | "s" => value,
+        "m" => value * 60.0,
+        "h" => value * 3600.0,
+        other => return Err(TemperatureError::InvalidArgument(format!("unknown duration unit '{}' (use ms, s, m or h)", other))),
+    };
+
+    Duration::try_from_secs_f64(seconds).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("2s"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("1.5m"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("1h"), Ok(Duration::from_secs(3600)));
+        assert_eq!(parse_duration("3"), Ok(Duration::from_secs(3)));
+        assert!(parse_duration("2 days").is_err());
+        assert!(parse_duration("s").is_err());
+    }
+}
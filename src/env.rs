@@ -0,0 +1,226 @@
+//! Overrides taken from `SUHU_*` environment variables, so containers and
+//! scripts can adjust a deployment without editing its configuration.
+
+use std::env;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[cfg(feature = "config")]
+use crate::Config;
+use crate::{parse_duration, ProviderChain, ProviderKind, TemperatureError, TemperatureReaderBuilder, TemperatureUnit};
+
+/// The settings given by environment variables.
+#[derive(Debug, Default, PartialEq)]
+struct Overrides {
+    providers: Option<Vec<ProviderKind>>,
+    timeout: Option<Duration>,
+    unit: Option<TemperatureUnit>,
+    offset: Option<f64>,
+    interval: Option<Duration>,
+    csv_dir: Option<PathBuf>,
+}
+
+impl Overrides {
+    /// Reads the overrides from the process environment.
+    fn from_env() -> Result<Self, TemperatureError> {
+        Self::from_vars(|name| match env::var(name) {
+            Ok(value) => Ok(Some(value)),
+            Err(env::VarError::NotPresent) => Ok(None),
+            Err(env::VarError::NotUnicode(_)) => Err(TemperatureError::InvalidArgument(format!("{} is not valid Unicode", name))),
+        })
+    }
+
+    /// Reads the overrides from the variables returned by `var`.
+    fn from_vars(var: impl Fn(&str) -> Result<Option<String>, TemperatureError>) -> Result<Self, TemperatureError> {
+        let value = |name: &str| -> Result<Option<String>, TemperatureError> {
+            Ok(var(name)?.map(|value| value.trim().to_string()).filter(|value| !value.is_empty()))
+        };
+
+        Ok(Overrides {
+            providers: value("SUHU_PROVIDER")?
+                .map(|text| text.split(',').map(ProviderKind::from_str).collect::<Result<_, _>>())
+                .transpose()?,
+            timeout: number("SUHU_TIMEOUT_MS", value("SUHU_TIMEOUT_MS")?)?.map(Duration::from_millis),
+            unit: value("SUHU_UNIT")?.map(|text| text.parse()).transpose()?,
+            offset: number("SUHU_OFFSET", value("SUHU_OFFSET")?)?,
+            interval: value("SUHU_INTERVAL")?.map(|text| parse_duration(&text)).transpose()?,
+            csv_dir: value("SUHU_CSV_DIR")?.map(PathBuf::from),
+        })
+    }
+}
+
+/// Parses the number `text` of the variable `name`, if it is set.
+fn number<T: FromStr>(name: &str, text: Option<String>) -> Result<Option<T>, TemperatureError>
+where
+    T::Err: fmt::Display,
+{
+    text.map(|text| text.parse().map_err(|e| TemperatureError::InvalidArgument(format!("invalid {} '{}': {}", name, text, e))))
+        .transpose()
+}
+
+impl TemperatureReaderBuilder {
+    /// Replaces the provider, timeout, unit and global calibration offset
+    /// with those set by `SUHU_*` environment variables. Unset or empty
+    /// variables leave the setting alone:
+    ///
+    /// | Variable          | Setting                                          |
+    /// |-------------------|--------------------------------------------------|
+    /// | `SUHU_PROVIDER`   | comma-separated providers, such as `acpi,hwinfo` |
+    /// | `SUHU_TIMEOUT_MS` | read timeout in milliseconds                     |
+    /// | `SUHU_UNIT`       | `celsius`, `fahrenheit` or `kelvin`              |
+    /// | `SUHU_OFFSET`     | global calibration offset in degrees Celsius     |
+    ///
+    /// Providers are named as parsed by [`ProviderKind`]. Call this last, so
+    /// the environment wins over the code.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`TemperatureError::InvalidArgument`] if a variable does not
+    /// parse, or names a provider missing from this build.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// // SUHU_TIMEOUT_MS=500 shortens the timeout without a rebuild.
+    /// let reader = CpuTemperature::builder().timeout(Duration::from_secs(2)).env_overrides()?.build();
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    pub fn env_overrides(self) -> Result<Self, TemperatureError> {
+        Overrides::from_env()?.apply_to_builder(self)
+    }
+}
+
+impl Overrides {
+    fn apply_to_builder(self, mut builder: TemperatureReaderBuilder) -> Result<TemperatureReaderBuilder, TemperatureError> {
+        if let Some(providers) = &self.providers {
+            builder = builder.provider(ProviderChain::from_kinds(providers)?);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(unit) = self.unit {
+            builder = builder.unit(unit);
+        }
+        if let Some(offset) = self.offset {
+            builder.calibration = std::mem::take(&mut builder.calibration).global_offset(offset);
+        }
+        Ok(builder)
+    }
+
+    #[cfg(feature = "config")]
+    fn apply_to_config(self, config: &mut Config) {
+        if let Some(providers) = self.providers {
+            config.providers = providers;
+        }
+        config.timeout = self.timeout.or(config.timeout);
+        config.unit = self.unit.unwrap_or(config.unit);
+        if let Some(offset) = self.offset {
+            config.offsets = std::mem::take(&mut config.offsets).global_offset(offset);
+        }
+        config.interval = self.interval.or(config.interval);
+        config.sinks.csv = self.csv_dir.or(config.sinks.csv.take());
+    }
+}
+
+#[cfg(feature = "config")]
+impl Config {
+    /// Replaces settings with those set by `SUHU_*` environment variables, so
+    /// the environment wins over the file.
+    ///
+    /// Besides the variables of
+    /// [`TemperatureReaderBuilder::env_overrides`], `SUHU_INTERVAL` sets the
+    /// sampling interval, such as `2s`, and `SUHU_CSV_DIR` the directory of
+    /// the CSV sink.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`TemperatureError::InvalidArgument`] if a variable does not
+    /// parse.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tunjukin_suhu_cpu_windows::Config;
+    ///
+    /// let config = Config::from_file("suhu.toml")?.env_overrides()?;
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    pub fn env_overrides(mut self) -> Result<Self, TemperatureError> {
+        Overrides::from_env()?.apply_to_config(&mut self);
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Calibration, CpuTemperature};
+
+    fn overrides(vars: &[(&str, &str)]) -> Result<Overrides, TemperatureError> {
+        Overrides::from_vars(|name| Ok(vars.iter().find(|(var, _)| *var == name).map(|(_, value)| value.to_string())))
+    }
+
+    #[test]
+    fn test_parse_overrides() {
+        let parsed = overrides(&[
+            ("SUHU_PROVIDER", "acpi, core-temp"),
+            ("SUHU_TIMEOUT_MS", "750"),
+            ("SUHU_UNIT", "kelvin"),
+            ("SUHU_OFFSET", "-2.5"),
+            ("SUHU_INTERVAL", "2s"),
+            ("SUHU_CSV_DIR", ""),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            parsed,
+            Overrides {
+                providers: Some(vec![ProviderKind::Acpi, ProviderKind::CoreTemp]),
+                timeout: Some(Duration::from_millis(750)),
+                unit: Some(TemperatureUnit::Kelvin),
+                offset: Some(-2.5),
+                interval: Some(Duration::from_secs(2)),
+                csv_dir: None,
+            }
+        );
+        assert_eq!(overrides(&[]).unwrap(), Overrides::default());
+    }
+
+    #[test]
+    fn test_rejects_invalid_overrides() {
+        let Err(TemperatureError::InvalidArgument(message)) = overrides(&[("SUHU_TIMEOUT_MS", "2s")]) else {
+            panic!("accepted an invalid timeout");
+        };
+        assert!(message.starts_with("invalid SUHU_TIMEOUT_MS '2s': "));
+        assert!(overrides(&[("SUHU_PROVIDER", "acpi,,probe")]).is_err());
+        assert!(overrides(&[("SUHU_UNIT", "rankine")]).is_err());
+    }
+
+    #[test]
+    fn test_apply_to_builder() {
+        let builder = CpuTemperature::builder().calibration(Calibration::new().zone_offset("TZ01", -8.0));
+        let reader = Overrides { timeout: Some(Duration::from_millis(750)), offset: Some(1.0), ..Overrides::default() }
+            .apply_to_builder(builder)
+            .unwrap()
+            .build();
+
+        assert_eq!(reader.timeout(), Some(Duration::from_millis(750)));
+        assert_eq!(reader.calibration(), &Calibration::new().global_offset(1.0).zone_offset("TZ01", -8.0));
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_apply_to_config() {
+        let mut config: Config = "interval = \"5s\"\nunit = \"fahrenheit\"\n[sinks]\ncsv = 'C:\\Logs'".parse().unwrap();
+        Overrides { interval: Some(Duration::from_secs(1)), ..Overrides::default() }.apply_to_config(&mut config);
+
+        assert_eq!(config.interval, Some(Duration::from_secs(1)));
+        assert_eq!(config.unit, TemperatureUnit::Fahrenheit);
+        assert_eq!(config.sinks.csv, Some(PathBuf::from(r"C:\Logs")));
+    }
+}
@@ -0,0 +1,227 @@
+//! The error type returned by every fallible operation in this crate.
+
+use std::error::Error;
+use std::fmt;
+
+/// Why a temperature could not be read.
+///
+/// Match on the variant to react to specific failures, for example to ask
+/// the user to elevate on [`TemperatureError::AccessDenied`] or to hide the
+/// temperature widget on [`TemperatureError::NoSensors`]. The `Display`
+/// output is a human-readable message suitable for logs.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::{CpuTemperature, TemperatureError};
+///
+/// match CpuTemperature::get() {
+///     Ok(temp) => println!("{:.1}°C", temp.celsius()),
+///     Err(TemperatureError::NoSensors | TemperatureError::NoValidReadings) => println!("no sensor"),
+///     Err(e) => eprintln!("error: {}", e),
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TemperatureError {
+    /// Neither Windows PowerShell nor PowerShell 7 could be started, usually
+    /// because neither is installed or in `PATH`.
+    PowerShellNotFound {
+        /// The operating system's reason for the failure.
+        message: String,
+    },
+    /// The query ran but failed.
+    QueryFailed {
+        /// PowerShell's exit code, or `None` if it was terminated without one.
+        exit_code: Option<i32>,
+        /// What PowerShell wrote to standard error.
+        stderr: String,
+    },
+    /// WMI refused the query. Running as administrator usually helps.
+    AccessDenied {
+        /// The error reported by PowerShell or COM.
+        details: String,
+    },
+    /// The query returned no thermal zones at all.
+    NoSensors,
+    /// Thermal zones were found, but none reported a valid reading.
+    NoValidReadings,
+    /// Every reading was a temperature outside the
+    /// [valid range](crate::set_valid_range).
+    OutOfRange {
+        /// The rejected readings and the accepted range.
+        details: String,
+    },
+    /// The query output could not be understood.
+    ParseError(String),
+    /// The query did not finish within the allowed time.
+    Timeout,
+    /// A COM call of the native WMI backend failed.
+    Com {
+        /// The failing `HRESULT`.
+        code: i32,
+        /// The system's description of `code`.
+        message: String,
+    },
+    /// An argument was rejected, such as an invalid WMI property name.
+    InvalidArgument(String),
+    /// Any other failure, such as one reported by a custom
+    /// [`TemperatureProvider`](crate::TemperatureProvider).
+    Other(String),
+}
+
+impl TemperatureError {
+    /// Returns `true` if the error is a temporary WMI contention failure
+    /// that is worth retrying, such as `WBEM_E_SERVER_TOO_BUSY` or
+    /// `RPC_E_SERVERCALL_RETRYLATER`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tunjukin_suhu_cpu_windows::TemperatureError;
+    ///
+    /// let busy = TemperatureError::QueryFailed {
+    ///     exit_code: Some(1),
+    ///     stderr: "Server too busy (Exception from HRESULT: 0x80041045)".to_string(),
+    /// };
+    /// assert!(busy.is_transient());
+    /// assert!(!TemperatureError::NoSensors.is_transient());
+    /// ```
+    pub fn is_transient(&self) -> bool {
+        match self {
+            TemperatureError::QueryFailed { stderr: text, .. } | TemperatureError::Other(text) => mentions_transient(text),
+            TemperatureError::Com { code, message } => mentions_transient(&format!("0x{:08x} {}", code, message)),
+            _ => false,
+        }
+    }
+
+    /// Classifies an unsuccessful PowerShell run.
+    pub(crate) fn query_failed(exit_code: Option<i32>, stderr: &str) -> Self {
+        let stderr = stderr.trim().to_string();
+        if mentions_access_denied(&stderr) {
+            TemperatureError::AccessDenied { details: stderr }
+        } else {
+            TemperatureError::QueryFailed { exit_code, stderr }
+        }
+    }
+}
+
+impl fmt::Display for TemperatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemperatureError::PowerShellNotFound { message } => {
+                write!(f, "Failed to execute PowerShell: {}. Ensure PowerShell is installed and accessible.", message)
+            }
+            TemperatureError::QueryFailed { exit_code, stderr } => {
+                write!(f, "WMI query failed with exit code: ")?;
+                match exit_code {
+                    Some(code) => write!(f, "{}", code)?,
+                    None => write!(f, "unknown")?,
+                }
+                write!(f, ". You may need to run as administrator. PowerShell reported: {}", stderr)
+            }
+            TemperatureError::AccessDenied { details } => {
+                write!(f, "Access to WMI was denied. Try running as administrator. Reported error: {}", details)
+            }
+            TemperatureError::NoSensors => {
+                write!(f, "No temperature data received from WMI query. Check if thermal sensors are available.")
+            }
+            TemperatureError::NoValidReadings => write!(
+                f,
+                "No valid temperature readings found in WMI output. The thermal zone sensors may not be accessible."
+            ),
+            TemperatureError::OutOfRange { details } => write!(
+                f,
+                "Every temperature reading was outside the accepted range: {}. Widen it with set_valid_range if the values are genuine.",
+                details
+            ),
+            TemperatureError::ParseError(message) => write!(f, "Could not parse WMI output: {}", message),
+            TemperatureError::Timeout => write!(f, "WMI query timed out."),
+            TemperatureError::Com { code, message } => {
+                write!(f, "Native WMI query failed: {} (HRESULT 0x{:08X}).", message, code)
+            }
+            TemperatureError::InvalidArgument(message) | TemperatureError::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl Error for TemperatureError {}
+
+impl From<String> for TemperatureError {
+    fn from(message: String) -> Self {
+        TemperatureError::Other(message)
+    }
+}
+
+impl From<&str> for TemperatureError {
+    fn from(message: &str) -> Self {
+        TemperatureError::Other(message.to_string())
+    }
+}
+
+/// Error signatures WMI uses when it is temporarily overloaded.
+const TRANSIENT_ERROR_SIGNATURES: &[&str] = &[
+    "0x80041045",
+    "wbem_e_server_too_busy",
+    "server too busy",
+    "0x8001010a",
+    "rpc_e_servercall_retrylater",
+    "application is busy",
+    "provider is busy",
+];
+
+/// Error signatures of WMI and COM access checks.
+const ACCESS_DENIED_SIGNATURES: &[&str] = &["access denied", "access is denied", "0x80041003", "0x80070005"];
+
+/// Returns `true` if `text` describes a temporary WMI contention failure.
+fn mentions_transient(text: &str) -> bool {
+    contains_any(text, TRANSIENT_ERROR_SIGNATURES)
+}
+
+/// Returns `true` if `text` describes a failed access check.
+fn mentions_access_denied(text: &str) -> bool {
+    contains_any(text, ACCESS_DENIED_SIGNATURES)
+}
+
+/// Returns `true` if `text` contains any of the lowercase `signatures`,
+/// ignoring ASCII case.
+fn contains_any(text: &str, signatures: &[&str]) -> bool {
+    let text = text.to_ascii_lowercase();
+    signatures.iter().any(|signature| text.contains(signature))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed(stderr: &str) -> TemperatureError {
+        TemperatureError::query_failed(Some(1), stderr)
+    }
+
+    #[test]
+    fn test_transient_error_classification() {
+        assert!(failed("Get-WmiObject : Server too busy (Exception from HRESULT: 0x80041045)").is_transient());
+        assert!(failed("Call was rejected by callee. (Exception from HRESULT: 0x8001010A (RPC_E_SERVERCALL_RETRYLATER))").is_transient());
+        assert!(TemperatureError::Com { code: 0x8001_010Au32 as i32, message: "Call was rejected by callee.".to_string() }.is_transient());
+        assert!(!failed("Get-WmiObject : Invalid namespace (0x8004100E)").is_transient());
+        assert!(!TemperatureError::NoValidReadings.is_transient());
+    }
+
+    #[test]
+    fn test_access_denied_classification() {
+        assert_eq!(
+            failed("Get-WmiObject : Access denied \r\n"),
+            TemperatureError::AccessDenied { details: "Get-WmiObject : Access denied".to_string() }
+        );
+        assert!(matches!(failed("Invalid class"), TemperatureError::QueryFailed { exit_code: Some(1), .. }));
+    }
+
+    #[test]
+    fn test_display_keeps_query_details() {
+        assert_eq!(
+            TemperatureError::QueryFailed { exit_code: None, stderr: "boom".to_string() }.to_string(),
+            "WMI query failed with exit code: unknown. You may need to run as administrator. PowerShell reported: boom"
+        );
+        assert_eq!(TemperatureError::from("custom source failed").to_string(), "custom source failed");
+    }
+}
@@ -0,0 +1,201 @@
+//! Alerts and readings written to the Windows Event Log.
+//!
+//! Entries are written through .NET's `System.Diagnostics.EventLog` from
+//! PowerShell, so no extra crates are needed.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{powershell_quote, Alert, AlertMonitor, CpuTemperature, Crossing, SamplerHandle, TemperatureError};
+
+/// The event ID of periodic readings.
+const READING_EVENT_ID: u16 = 100;
+/// The event ID of a raised threshold.
+const RAISED_EVENT_ID: u16 = 200;
+/// The event ID of a cleared threshold.
+const CLEARED_EVENT_ID: u16 = 201;
+
+/// Writes alerts and readings to the Windows Event Log under a dedicated
+/// source, for monitoring systems that already collect the event log.
+///
+/// Entries use fixed event IDs, so collection rules can match on them:
+///
+/// | ID  | Type        | Written for                  |
+/// |-----|-------------|------------------------------|
+/// | 100 | Information | a periodic reading           |
+/// | 200 | Warning     | a threshold that was reached |
+/// | 201 | Information | a threshold that was cleared |
+///
+/// The source must be registered once, from an elevated process such as an
+/// installer, with [`register`](EventLogSink::register). Writing afterwards
+/// needs no special rights.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use tunjukin_suhu_cpu_windows::{AlertMonitor, EventLogSink, TemperatureSampler};
+///
+/// let sink = EventLogSink::new("CPU Temperature");
+/// sink.register()?;
+///
+/// let sampler = TemperatureSampler::new(Duration::from_secs(5)).spawn();
+/// AlertMonitor::new()
+///     .threshold("critical", 95.0)
+///     .event_log(sink.clone())
+///     .attach(&sampler);
+///
+/// // Also record a reading every 15 minutes.
+/// sink.attach(&sampler, Duration::from_secs(15 * 60));
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventLogSink {
+    source: String,
+    log: String,
+}
+
+impl EventLogSink {
+    /// Creates a sink writing to the `Application` log under `source`.
+    pub fn new(source: impl Into<String>) -> Self {
+        EventLogSink { source: source.into(), log: "Application".to_string() }
+    }
+
+    /// Sets the log [`register`](EventLogSink::register) adds the source to.
+    /// The default is `Application`.
+    pub fn log(mut self, log: impl Into<String>) -> Self {
+        self.log = log.into();
+        self
+    }
+
+    /// Registers the source with the event log, unless it already exists.
+    ///
+    /// # Errors
+    ///
+    /// Fails if PowerShell cannot be started or the process is not elevated
+    /// and the source does not exist yet.
+    pub fn register(&self) -> Result<(), TemperatureError> {
+        let script = format!(
+            "if (-not [System.Diagnostics.EventLog]::SourceExists({source})) {{ [System.Diagnostics.EventLog]::CreateEventSource({source}, {log}) }}",
+            source = powershell_quote(&self.source),
+            log = powershell_quote(&self.log)
+        );
+        CpuTemperature::run_powershell(&script).map(|_| ())
+    }
+
+    /// Writes an entry for `alert`: a warning when a threshold is reached,
+    /// information when it is cleared.
+    ///
+    /// # Errors
+    ///
+    /// Fails if PowerShell cannot be started or the source is not registered.
+    pub fn write_alert(&self, alert: &Alert) -> Result<(), TemperatureError> {
+        CpuTemperature::run_powershell(&alert_script(&self.source, alert)).map(|_| ())
+    }
+
+    /// Writes an information entry for `reading`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if PowerShell cannot be started or the source is not registered.
+    pub fn write_reading(&self, reading: &CpuTemperature) -> Result<(), TemperatureError> {
+        CpuTemperature::run_powershell(&reading_script(&self.source, reading)).map(|_| ())
+    }
+
+    /// Writes a successful sample from `sampler` at most once every `every`.
+    ///
+    /// Entries are written on their own threads so the sampler is not
+    /// delayed, and entries that cannot be written are dropped.
+    pub fn attach(self, sampler: &SamplerHandle, every: Duration) {
+        let mut last_written: Option<Instant> = None;
+        sampler.subscribe_with(move |sample| {
+            let Ok(reading) = sample else {
+                return;
+            };
+            if last_written.is_some_and(|at| at.elapsed() < every) {
+                return;
+            }
+            last_written = Some(Instant::now());
+
+            let script = reading_script(&self.source, reading);
+            thread::spawn(move || CpuTemperature::run_powershell(&script));
+        });
+    }
+}
+
+impl AlertMonitor {
+    /// Writes every alert to the event log through `sink`.
+    ///
+    /// Entries are written on their own threads so the readings are not
+    /// delayed, and entries that cannot be written are dropped.
+    pub fn event_log(self, sink: EventLogSink) -> Self {
+        self.on_alert(move |alert| {
+            let script = alert_script(&sink.source, alert);
+            thread::spawn(move || CpuTemperature::run_powershell(&script));
+        })
+    }
+}
+
+/// Builds the script writing an entry for `alert` under `source`.
+fn alert_script(source: &str, alert: &Alert) -> String {
+    let (entry_type, event_id, verb) = match alert.crossing {
+        Crossing::Raised => ("Warning", RAISED_EVENT_ID, "reached"),
+        Crossing::Cleared => ("Information", CLEARED_EVENT_ID, "fell below"),
+    };
+    let message = format!(
+        "CPU temperature {:.1} °C {} the {} threshold of {:.1} °C.",
+        alert.temperature.celsius(), verb, alert.name, alert.threshold
+    );
+    write_script(source, entry_type, event_id, &message)
+}
+
+/// Builds the script writing an entry for `reading` under `source`.
+fn reading_script(source: &str, reading: &CpuTemperature) -> String {
+    let message = format!("CPU temperature {:.1} °C ({:.1} °F).", reading.celsius(), reading.fahrenheit());
+    write_script(source, "Information", READING_EVENT_ID, &message)
+}
+
+/// Builds the script writing one entry with `message` under `source`.
+fn write_script(source: &str, entry_type: &str, event_id: u16, message: &str) -> String {
+    format!(
+        "[System.Diagnostics.EventLog]::WriteEntry({}, {}, [System.Diagnostics.EventLogEntryType]::{}, {})",
+        powershell_quote(source),
+        powershell_quote(message),
+        entry_type,
+        event_id
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alert_script() {
+        let alert = Alert {
+            name: "critical".to_string(),
+            threshold: 95.0,
+            crossing: Crossing::Raised,
+            temperature: CpuTemperature::from_celsius(96.2),
+        };
+
+        assert_eq!(
+            alert_script("Bob's CPU", &alert),
+            "[System.Diagnostics.EventLog]::WriteEntry('Bob''s CPU', \
+             'CPU temperature 96.2 °C reached the critical threshold of 95.0 °C.', \
+             [System.Diagnostics.EventLogEntryType]::Warning, 200)"
+        );
+
+        let cleared = Alert { crossing: Crossing::Cleared, ..alert };
+        assert!(alert_script("CPU", &cleared).ends_with("fell below the critical threshold of 95.0 °C.', [System.Diagnostics.EventLogEntryType]::Information, 201)"));
+    }
+
+    #[test]
+    fn test_reading_script() {
+        assert_eq!(
+            reading_script("CPU", &CpuTemperature::from_celsius(45.0)),
+            "[System.Diagnostics.EventLog]::WriteEntry('CPU', 'CPU temperature 45.0 °C (113.0 °F).', \
+             [System.Diagnostics.EventLogEntryType]::Information, 100)"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! The chain of providers tried when the ACPI thermal zones yield nothing.
+
+use crate::{
+    LibreHardwareMonitorProvider, OpenHardwareMonitorProvider, ProviderKind, TemperatureError, TemperatureProbeProvider, TemperatureProvider,
+};
+
+/// Providers tried, in order, after the ACPI thermal zones.
+const FALLBACKS: &[(ProviderKind, &dyn TemperatureProvider)] = &[
+    (ProviderKind::Probe, &TemperatureProbeProvider),
+    (ProviderKind::LibreHardwareMonitor, &LibreHardwareMonitorProvider),
+    (ProviderKind::OpenHardwareMonitor, &OpenHardwareMonitorProvider),
+];
+
+/// Replaces a "nothing to read" `primary` result with the first successful
+/// fallback read.
+///
+/// Other errors, such as access or PowerShell failures, are returned as they
+/// are, since the fallbacks would fail the same way. If every fallback fails,
+/// the primary error is returned.
+pub(crate) fn recover<T>(
+    primary: Result<T, TemperatureError>,
+    read: impl Fn(&dyn TemperatureProvider) -> Result<T, TemperatureError>,
+) -> Result<T, TemperatureError> {
+    recover_with(primary, FALLBACKS, read).map(|(value, _)| value)
+}
+
+/// Like [`recover`], but also returns the fallback that produced the value,
+/// or `None` if it is the `primary` one.
+pub(crate) fn recover_traced<T>(
+    primary: Result<T, TemperatureError>,
+    read: impl Fn(&dyn TemperatureProvider) -> Result<T, TemperatureError>,
+) -> Result<(T, Option<ProviderKind>), TemperatureError> {
+    recover_with(primary, FALLBACKS, read)
+}
+
+/// Like [`recover_traced`], trying `fallbacks` instead of the built-in ones.
+pub(crate) fn recover_with<T>(
+    primary: Result<T, TemperatureError>,
+    fallbacks: &[(ProviderKind, &dyn TemperatureProvider)],
+    read: impl Fn(&dyn TemperatureProvider) -> Result<T, TemperatureError>,
+) -> Result<(T, Option<ProviderKind>), TemperatureError> {
+    match primary {
+        Err(e @ (TemperatureError::NoSensors | TemperatureError::NoValidReadings | TemperatureError::OutOfRange { .. })) => {
+            fallbacks.iter().find_map(|&(kind, provider)| Some((read(provider).ok()?, Some(kind)))).ok_or(e)
+        }
+        result => result.map(|value| (value, None)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CpuTemperature, MockProvider};
+
+    #[test]
+    fn test_recovers_from_missing_sensors() {
+        let broken = MockProvider::failing(TemperatureError::NoSensors);
+        let working = MockProvider::fixed(CpuTemperature::from_celsius(52.0));
+
+        let fallbacks: [(ProviderKind, &dyn TemperatureProvider); 2] = [(ProviderKind::Probe, &broken), (ProviderKind::LibreHardwareMonitor, &working)];
+        let reading = recover_with(Err(TemperatureError::NoValidReadings), &fallbacks, |p| p.read_one());
+
+        assert_eq!(reading, Ok((CpuTemperature::from_celsius(52.0), Some(ProviderKind::LibreHardwareMonitor))));
+        assert_eq!(broken.call_count(), 1);
+    }
+
+    #[test]
+    fn test_keeps_primary_result_and_error() {
+        let working = MockProvider::fixed(CpuTemperature::from_celsius(52.0));
+        let denied = TemperatureError::AccessDenied { details: "Access denied".to_string() };
+
+        let fallbacks: [(ProviderKind, &dyn TemperatureProvider); 1] = [(ProviderKind::Probe, &working)];
+        assert_eq!(recover_with(Err(denied.clone()), &fallbacks, |p| p.read_one()), Err(denied));
+        assert_eq!(
+            recover_with(Ok(CpuTemperature::from_celsius(40.0)), &fallbacks, |p| p.read_one()),
+            Ok((CpuTemperature::from_celsius(40.0), None))
+        );
+        assert_eq!(working.call_count(), 0);
+
+        let broken = MockProvider::failing("namespace missing");
+        let fallbacks: [(ProviderKind, &dyn TemperatureProvider); 1] = [(ProviderKind::Probe, &broken)];
+        assert_eq!(recover_with(Err(TemperatureError::NoSensors), &fallbacks, |p| p.read_one()), Err(TemperatureError::NoSensors));
+    }
+}
@@ -0,0 +1,97 @@
+//! Fan speeds, to correlate with temperatures.
+//!
+//! Fan speeds come from the `Fan` sensors LibreHardwareMonitor and
+//! OpenHardwareMonitor publish while they run, in revolutions per minute.
+//! Without either tool, `Win32_Fan` is used, but most firmware leaves it
+//! empty and its `DesiredSpeed` is the speed the fan is driven towards, not
+//! a measurement.
+
+use crate::hardware_monitor::{sensor_query, sensor_value, LIBRE_HARDWARE_MONITOR_NAMESPACE, OPEN_HARDWARE_MONITOR_NAMESPACE};
+use crate::{parse_records, record_value, CpuTemperature, SensorKind, SensorReading, TemperatureError};
+
+/// The query listing the fans the firmware describes.
+const WIN32_FAN_QUERY: &str = "Get-CimInstance -ClassName Win32_Fan | Format-List Name,DesiredSpeed";
+
+/// The speed of one fan.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FanSpeed {
+    /// The fan's name as its source reports it, such as `Fan #1` or
+    /// `GPU Fan`, or `None` if it has none.
+    pub name: Option<String>,
+    /// The speed in revolutions per minute.
+    pub rpm: f64,
+}
+
+impl FanSpeed {
+    /// Reads every fan, from LibreHardwareMonitor, OpenHardwareMonitor or
+    /// `Win32_Fan`, whichever answers first.
+    ///
+    /// A fan at 0 RPM is listed, since a fan that stopped while the CPU is
+    /// hot is exactly what callers look for.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the `Win32_Fan` error if no source lists a fan.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tunjukin_suhu_cpu_windows::{CpuTemperature, FanSpeed};
+    ///
+    /// let cpu = CpuTemperature::get()?;
+    /// let fans = FanSpeed::get_all()?;
+    /// if cpu.celsius() > 80.0 && fans.iter().all(|fan| fan.rpm == 0.0) {
+    ///     eprintln!("The CPU is hot and no fan is spinning");
+    /// }
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    pub fn get_all() -> Result<Vec<FanSpeed>, TemperatureError> {
+        for namespace in [LIBRE_HARDWARE_MONITOR_NAMESPACE, OPEN_HARDWARE_MONITOR_NAMESPACE] {
+            if let Ok(fans) = CpuTemperature::run_powershell(&sensor_query(namespace, "Fan")).and_then(|output| fans_from(&output, "Value")) {
+                return Ok(fans);
+            }
+        }
+
+        fans_from(&CpuTemperature::run_powershell(WIN32_FAN_QUERY)?, "DesiredSpeed")
+    }
+}
+
+impl From<FanSpeed> for SensorReading {
+    fn from(fan: FanSpeed) -> Self {
+        SensorReading::new(SensorKind::Fan, fan.name, fan.rpm)
+    }
+}
+
+/// Parses query output listing each fan's speed as `property`, failing if it
+/// lists no fan with a speed.
+fn fans_from(output: &str, property: &str) -> Result<Vec<FanSpeed>, TemperatureError> {
+    parse_records(output, |record| {
+        let rpm = sensor_value(record, property)?;
+        (rpm >= 0.0).then(|| FanSpeed { name: record_value(record, "Name").map(str::to_string), rpm })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fans() {
+        let output = "Identifier : /lpc/nct6798d/fan/0\r\nName : Fan #1\r\nValue : 1187.5\r\n\r\n\
+                      Identifier : /lpc/nct6798d/fan/1\r\nName : Fan #2\r\nValue : 0\r\n\r\n";
+
+        let fans = fans_from(output, "Value").unwrap();
+        assert_eq!(fans, [FanSpeed { name: Some("Fan #1".to_string()), rpm: 1187.5 }, FanSpeed { name: Some("Fan #2".to_string()), rpm: 0.0 }]);
+
+        let reading = SensorReading::from(fans[0].clone());
+        assert_eq!(reading.kind, SensorKind::Fan);
+        assert_eq!(reading.temperature(), None);
+    }
+
+    #[test]
+    fn test_fan_errors() {
+        assert_eq!(fans_from("", "DesiredSpeed"), Err(TemperatureError::NoSensors));
+        assert_eq!(fans_from("Name : Cooling Device\nDesiredSpeed :\n", "DesiredSpeed"), Err(TemperatureError::NoValidReadings));
+    }
+}
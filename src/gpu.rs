@@ -0,0 +1,156 @@
+//! NVIDIA GPU temperatures from `nvidia-smi`.
+//!
+//! The NVIDIA driver installs `nvidia-smi` on the `PATH`, so asking it for
+//! `temperature.gpu` needs neither PowerShell nor an extra crate. Values are
+//! reported in whole degrees Celsius.
+
+use std::ffi::OsString;
+use std::io::ErrorKind;
+use std::process::Command;
+
+use crate::sensor::read_tagged;
+use crate::{is_plausible, CpuTemperature, SensorKind, SensorProvider, SensorReading, TemperatureError, TemperatureProvider, ThermalZoneReading};
+
+/// The arguments asking `nvidia-smi` for one `index, name, temperature` line
+/// per GPU.
+const QUERY_ARGUMENTS: [&str; 2] = ["--query-gpu=index,name,temperature.gpu", "--format=csv,noheader,nounits"];
+
+/// Reads NVIDIA GPU temperatures by running `nvidia-smi`.
+///
+/// Each GPU is named like `GPU 0: NVIDIA GeForce RTX 3080`, and GPUs that
+/// report no temperature are skipped. As a [`TemperatureProvider`], GPU
+/// temperatures can go through the same samplers, loggers and alerts as CPU
+/// ones.
+///
+/// # Errors
+///
+/// Reads fail with [`TemperatureError::NoSensors`] if `nvidia-smi` is not
+/// installed or lists no GPUs, and with [`TemperatureError::QueryFailed`] if
+/// it cannot talk to the driver.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::{GpuTemperature, TemperatureProvider};
+///
+/// let gpu = GpuTemperature::new().read_one()?;
+/// println!("GPU: {:.0}°C", gpu.celsius());
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuTemperature {
+    executable: OsString,
+}
+
+impl GpuTemperature {
+    /// Creates a reader running the `nvidia-smi` found on the `PATH`.
+    pub fn new() -> Self {
+        GpuTemperature { executable: "nvidia-smi".into() }
+    }
+
+    /// Runs `nvidia-smi` from `path` instead, for drivers that do not add it
+    /// to the `PATH`.
+    pub fn executable(mut self, path: impl Into<OsString>) -> Self {
+        self.executable = path.into();
+        self
+    }
+
+    /// Runs the query and returns its standard output.
+    fn query(&self) -> Result<String, TemperatureError> {
+        let mut command = Command::new(&self.executable);
+        command.args(QUERY_ARGUMENTS);
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+
+            const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+            command.creation_flags(CREATE_NO_WINDOW);
+        }
+
+        let output = command.output().map_err(|e| match e.kind() {
+            ErrorKind::NotFound => TemperatureError::NoSensors,
+            _ => TemperatureError::Other(format!("Failed to run nvidia-smi: {}", e)),
+        })?;
+        if !output.status.success() {
+            // nvidia-smi reports driver problems on standard output.
+            let message = [output.stderr, output.stdout].concat();
+            return Err(TemperatureError::query_failed(output.status.code(), &String::from_utf8_lossy(&message)));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+impl Default for GpuTemperature {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TemperatureProvider for GpuTemperature {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        gpus_from(&self.query()?)
+    }
+}
+
+impl SensorProvider for GpuTemperature {
+    fn read_sensors(&self) -> Result<Vec<SensorReading>, TemperatureError> {
+        read_tagged(self, SensorKind::Gpu)
+    }
+}
+
+/// Parses the query output, failing if it lists no GPU with a temperature.
+fn gpus_from(output: &str) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+    let lines: Vec<&str> = output.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    if lines.is_empty() {
+        return Err(TemperatureError::NoSensors);
+    }
+
+    let gpus: Vec<ThermalZoneReading> = lines.into_iter().filter_map(parse_gpu).collect();
+    if gpus.is_empty() {
+        return Err(TemperatureError::NoValidReadings);
+    }
+
+    Ok(gpus)
+}
+
+/// Parses one `index, name, temperature` line, or `None` if the GPU
+/// reported no usable temperature, such as `[N/A]`.
+fn parse_gpu(line: &str) -> Option<ThermalZoneReading> {
+    // Names may contain commas; the index comes first and the temperature last.
+    let (index, rest) = line.split_once(',')?;
+    let (name, celsius) = rest.rsplit_once(',')?;
+    let celsius: f64 = celsius.trim().parse().ok().filter(|&celsius| is_plausible(celsius))?;
+
+    Some(ThermalZoneReading::new(Some(format!("GPU {}: {}", index.trim(), name.trim())), CpuTemperature::from_celsius(celsius)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gpus() {
+        let output = "0, NVIDIA GeForce RTX 3080, 45\r\n1, Tesla T4, [N/A]\r\n2, NVIDIA RTX A2000, Laptop, 61\r\n\r\n";
+
+        let gpus = gpus_from(output).unwrap();
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(gpus[0].instance_name.as_deref(), Some("GPU 0: NVIDIA GeForce RTX 3080"));
+        assert_eq!(gpus[0].temperature.celsius(), 45.0);
+        assert_eq!(gpus[1].instance_name.as_deref(), Some("GPU 2: NVIDIA RTX A2000, Laptop"));
+        assert_eq!(gpus[1].temperature.celsius(), 61.0);
+    }
+
+    #[test]
+    fn test_gpus_from_errors() {
+        assert_eq!(gpus_from("\n"), Err(TemperatureError::NoSensors));
+        assert_eq!(gpus_from("0, Tesla T4, [N/A]\n"), Err(TemperatureError::NoValidReadings));
+    }
+
+    #[test]
+    fn test_missing_executable() {
+        let gpu = GpuTemperature::new().executable("definitely-not-nvidia-smi");
+        assert_eq!(gpu.read(), Err(TemperatureError::NoSensors));
+    }
+}
@@ -0,0 +1,247 @@
+//! Backends for the WMI providers of hardware monitoring tools.
+//!
+//! Many desktop boards expose no ACPI thermal zones at all, so
+//! `MSAcpi_ThermalZoneTemperature` comes back empty. LibreHardwareMonitor
+//! and its predecessor OpenHardwareMonitor read the CPU's own digital
+//! thermal sensors instead and, while they are running, publish every sensor
+//! as a `Sensor` instance in the `root\LibreHardwareMonitor` or
+//! `root\OpenHardwareMonitor` WMI namespace. Both use the same schema.
+//!
+//! Sensor values are reported in degrees Celsius, not tenths of a Kelvin.
+//! They are floats, which `Format-List` would print in the current culture,
+//! with a decimal comma or digit grouping in many locales, so the sensor
+//! query formats them in the invariant culture instead.
+
+use std::time::Instant;
+
+use crate::sensor::read_tagged;
+use crate::{
+    is_plausible, parse_records, record_value, CpuTemperature, SensorKind, SensorProvider, SensorReading, TemperatureError, TemperatureProvider,
+    ThermalZoneReading,
+};
+
+/// The LibreHardwareMonitor WMI namespace.
+pub(crate) const LIBRE_HARDWARE_MONITOR_NAMESPACE: &str = "root/LibreHardwareMonitor";
+
+/// The OpenHardwareMonitor WMI namespace.
+pub(crate) const OPEN_HARDWARE_MONITOR_NAMESPACE: &str = "root/OpenHardwareMonitor";
+
+/// Identifier prefixes of the hardware nodes that represent a CPU.
+const CPU_IDENTIFIER_PREFIXES: &[&str] = &["/intelcpu/", "/amdcpu/"];
+
+/// Identifier prefixes of the hardware nodes that represent an AMD GPU, in
+/// current LibreHardwareMonitor, older LibreHardwareMonitor and
+/// OpenHardwareMonitor.
+const AMD_GPU_IDENTIFIER_PREFIXES: &[&str] = &["/gpu-amd/", "/amdgpu/", "/atigpu/"];
+
+/// Sensor names that describe the whole CPU rather than a single core.
+const PACKAGE_SENSOR_NAMES: &[&str] = &["CPU Package", "Core (Tctl/Tdie)", "Core (Tctl)", "Core (Tdie)"];
+
+/// The name of the sensor that describes the GPU die as a whole.
+const GPU_CORE_SENSOR_NAME: &str = "GPU Core";
+
+/// Reads the CPU temperature sensors published by LibreHardwareMonitor.
+///
+/// LibreHardwareMonitor must be running, usually as administrator, for its
+/// namespace to exist. Each [`ThermalZoneReading`] carries the sensor's name,
+/// such as `CPU Package` or `Core #1`, as its instance name. Whole-package
+/// sensors are listed before per-core ones, so
+/// [`read_one`](TemperatureProvider::read_one) returns the package
+/// temperature when there is one.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::{LibreHardwareMonitorProvider, TemperatureProvider};
+///
+/// for sensor in LibreHardwareMonitorProvider.read()? {
+///     println!("{}: {:.1}°C", sensor.instance_name.unwrap_or_default(), sensor.temperature.celsius());
+/// }
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LibreHardwareMonitorProvider;
+
+impl TemperatureProvider for LibreHardwareMonitorProvider {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        read_cpu_sensors(LIBRE_HARDWARE_MONITOR_NAMESPACE, None)
+    }
+
+    fn read_until(&self, deadline: Instant) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        read_cpu_sensors(LIBRE_HARDWARE_MONITOR_NAMESPACE, Some(deadline))
+    }
+}
+
+/// Reads the CPU temperature sensors published by OpenHardwareMonitor.
+///
+/// Behaves exactly like [`LibreHardwareMonitorProvider`], for machines that
+/// still run the older tool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpenHardwareMonitorProvider;
+
+impl TemperatureProvider for OpenHardwareMonitorProvider {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        read_cpu_sensors(OPEN_HARDWARE_MONITOR_NAMESPACE, None)
+    }
+
+    fn read_until(&self, deadline: Instant) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        read_cpu_sensors(OPEN_HARDWARE_MONITOR_NAMESPACE, Some(deadline))
+    }
+}
+
+/// Reads the AMD GPU temperature sensors published by LibreHardwareMonitor,
+/// or by OpenHardwareMonitor if LibreHardwareMonitor is not running.
+///
+/// AMD's own ADLX SDK is a C++ library and its drivers publish no
+/// temperature in WMI, so this relies on one of those tools reading the GPU.
+/// Each reading carries the sensor's name, such as `GPU Core` or
+/// `GPU Hot Spot`, with the core temperature first.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::{AmdGpuProvider, TemperatureProvider};
+///
+/// println!("GPU: {:.0}°C", AmdGpuProvider.read_one()?.celsius());
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AmdGpuProvider;
+
+impl TemperatureProvider for AmdGpuProvider {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        read_amd_gpu_sensors(LIBRE_HARDWARE_MONITOR_NAMESPACE).or_else(|_| read_amd_gpu_sensors(OPEN_HARDWARE_MONITOR_NAMESPACE))
+    }
+}
+
+impl SensorProvider for AmdGpuProvider {
+    fn read_sensors(&self) -> Result<Vec<SensorReading>, TemperatureError> {
+        read_tagged(self, SensorKind::Gpu)
+    }
+}
+
+/// Queries the temperature sensors in `namespace` and keeps the CPU ones,
+/// killing PowerShell if it is still running at `deadline`.
+fn read_cpu_sensors(namespace: &str, deadline: Option<Instant>) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+    let query = sensor_query(namespace, "Temperature");
+    cpu_sensors_from(&CpuTemperature::run_powershell_within(&query, deadline)?)
+}
+
+/// Queries the temperature sensors in `namespace` and keeps the AMD GPU ones.
+fn read_amd_gpu_sensors(namespace: &str) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+    let output = CpuTemperature::run_powershell(&sensor_query(namespace, "Temperature"))?;
+    amd_gpu_sensors_from(&output)
+}
+
+/// Builds the query listing every sensor of `sensor_type`, such as
+/// `Temperature` or `Fan`, in `namespace`, with each value formatted in the
+/// invariant culture so [`sensor_value`] can parse it.
+pub(crate) fn sensor_query(namespace: &str, sensor_type: &str) -> String {
+    format!(
+        "Get-CimInstance -Namespace '{}' -ClassName Sensor -Filter \"SensorType='{}'\" | \
+         Format-List Identifier,Name,@{{Name='Value';Expression={{$_.Value.ToString([cultureinfo]::InvariantCulture)}}}}",
+        namespace, sensor_type
+    )
+}
+
+/// Parses the number `property` of a record, as printed in the invariant
+/// culture by [`sensor_query`] or for the integer properties of the
+/// firmware's own classes.
+pub(crate) fn sensor_value(record: &[(String, String)], property: &str) -> Option<f64> {
+    record_value(record, property)?.parse().ok()
+}
+
+/// Parses the sensor query output, failing if it lists no usable CPU sensor.
+fn cpu_sensors_from(output: &str) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+    let mut sensors = sensors_from(output, CPU_IDENTIFIER_PREFIXES)?;
+    sensors.sort_by_key(|sensor| !sensor.instance_name.as_deref().is_some_and(|name| PACKAGE_SENSOR_NAMES.contains(&name)));
+    Ok(sensors)
+}
+
+/// Parses the sensor query output, failing if it lists no usable AMD GPU
+/// sensor.
+fn amd_gpu_sensors_from(output: &str) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+    let mut sensors = sensors_from(output, AMD_GPU_IDENTIFIER_PREFIXES)?;
+    sensors.sort_by_key(|sensor| sensor.instance_name.as_deref() != Some(GPU_CORE_SENSOR_NAME));
+    Ok(sensors)
+}
+
+/// Parses the sensor query output, keeping the sensors of hardware whose
+/// identifier starts with one of `prefixes`.
+fn sensors_from(output: &str, prefixes: &[&str]) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+    parse_records(output, |record| {
+        record_value(record, "Identifier").filter(|identifier| prefixes.iter().any(|prefix| identifier.starts_with(prefix)))?;
+        let celsius = sensor_value(record, "Value")?;
+        is_plausible(celsius).then(|| ThermalZoneReading::new(
+            record_value(record, "Name").map(str::to_string),
+            CpuTemperature::from_celsius(celsius),
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_OUTPUT: &str = "\r\n\
+        Identifier : /intelcpu/0/temperature/0\r\n\
+        Name       : Core #1\r\n\
+        Value      : 51\r\n\
+        \r\n\
+        Identifier : /nvidiagpu/0/temperature/0\r\n\
+        Name       : GPU Core\r\n\
+        Value      : 38\r\n\
+        \r\n\
+        Identifier : /intelcpu/0/temperature/4\r\n\
+        Name       : CPU Package\r\n\
+        Value      : 55.5\r\n\
+        \r\n";
+
+    #[test]
+    fn test_keeps_cpu_sensors_package_first() {
+        let sensors = cpu_sensors_from(SAMPLE_OUTPUT).unwrap();
+
+        let names: Vec<_> = sensors.iter().map(|sensor| sensor.instance_name.as_deref().unwrap()).collect();
+        assert_eq!(names, vec!["CPU Package", "Core #1"]);
+        assert_eq!(sensors[0].temperature.celsius(), 55.5);
+        assert_eq!(sensors[1].temperature.celsius(), 51.0);
+    }
+
+    #[test]
+    fn test_sensor_errors() {
+        assert_eq!(cpu_sensors_from(""), Err(TemperatureError::NoSensors));
+
+        let gpu_only = "Identifier : /nvidiagpu/0/temperature/0\nName : GPU Core\nValue : 38\n";
+        assert_eq!(cpu_sensors_from(gpu_only), Err(TemperatureError::NoValidReadings));
+    }
+
+    #[test]
+    fn test_keeps_amd_gpu_sensors_core_first() {
+        let output = "Identifier : /gpu-amd/0/temperature/2\nName : GPU Hot Spot\nValue : 71\n\n\
+                      Identifier : /gpu-amd/0/temperature/0\nName : GPU Core\nValue : 58\n\n\
+                      Identifier : /nvidiagpu/0/temperature/0\nName : GPU Core\nValue : 38\n";
+
+        let sensors = amd_gpu_sensors_from(output).unwrap();
+        let names: Vec<_> = sensors.iter().map(|sensor| sensor.instance_name.as_deref().unwrap()).collect();
+        assert_eq!(names, vec!["GPU Core", "GPU Hot Spot"]);
+        assert_eq!(sensors[0].temperature.celsius(), 58.0);
+
+        assert_eq!(amd_gpu_sensors_from(SAMPLE_OUTPUT), Err(TemperatureError::NoValidReadings));
+    }
+
+    #[test]
+    fn test_sensor_query_targets_namespace() {
+        let query = sensor_query(LIBRE_HARDWARE_MONITOR_NAMESPACE, "Temperature");
+        assert!(query.contains("-Namespace 'root/LibreHardwareMonitor'"));
+        assert!(query.contains("SensorType='Temperature'"));
+        assert!(sensor_query(OPEN_HARDWARE_MONITOR_NAMESPACE, "Fan").contains("-Namespace 'root/OpenHardwareMonitor'"));
+        assert!(query.contains("[cultureinfo]::InvariantCulture"));
+    }
+
+    #[test]
+    fn test_sensor_value_is_invariant() {
+        let records = crate::parse_format_list("Value : 1234.5\n\nValue : 1,234\n\nValue :\n");
+        let values: Vec<_> = records.iter().map(|record| sensor_value(record, "Value")).collect();
+        assert_eq!(values, [Some(1234.5), None, None]);
+    }
+}
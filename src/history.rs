@@ -0,0 +1,518 @@
+//! Buffered, timestamped readings for trend analysis.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::CpuTemperature;
+
+/// A temperature reading paired with the moment it was taken.
+///
+/// The moment is kept twice: `at` on the monotonic clock, for rates of change
+/// that must not jump when the system clock is adjusted, and `time` on the
+/// wall clock, for logs and charts.
+///
+/// Zones, sensor readings and the readings of
+/// [`ConfidentReading`](crate::ConfidentReading) and
+/// [`LastKnownReading`](crate::LastKnownReading) carry the same two fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedReading {
+    /// The temperature reading
+    pub reading: CpuTemperature,
+    /// When the reading was taken
+    pub at: Instant,
+    /// When the reading was taken, on the system clock
+    pub time: SystemTime,
+}
+
+impl TimedReading {
+    /// Pairs `reading` with the timestamp `at`, and the system time that
+    /// was as far from now as `at` is.
+    pub fn new(reading: CpuTemperature, at: Instant) -> Self {
+        let now = Instant::now();
+        let time = match at.checked_duration_since(now) {
+            Some(ahead) => SystemTime::now() + ahead,
+            None => SystemTime::now().checked_sub(now - at).unwrap_or(UNIX_EPOCH),
+        };
+        TimedReading { reading, at, time }
+    }
+
+    /// Pairs `reading` with the current instant and system time.
+    pub fn now(reading: CpuTemperature) -> Self {
+        TimedReading { reading, at: Instant::now(), time: SystemTime::now() }
+    }
+}
+
+/// A fixed-capacity buffer of the most recent timed readings.
+///
+/// Once the buffer is full, pushing a new reading evicts the oldest one.
+///
+/// # Example
+///
+/// ```
+/// use tunjukin_suhu_cpu_windows::{CpuTemperature, TempHistory};
+///
+/// let mut history = TempHistory::new(60);
+/// history.push(CpuTemperature::from_celsius(40.0));
+/// history.push(CpuTemperature::from_celsius(41.0));
+///
+/// assert_eq!(history.len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TempHistory {
+    readings: VecDeque<TimedReading>,
+    capacity: usize,
+    last_change: Option<Instant>,
+}
+
+impl TempHistory {
+    /// Creates an empty history holding at most `capacity` readings.
+    ///
+    /// A `capacity` of `0` is treated as `1`.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        TempHistory {
+            readings: VecDeque::with_capacity(capacity),
+            capacity,
+            last_change: None,
+        }
+    }
+
+    /// Records `reading`, timestamped with the current instant.
+    pub fn push(&mut self, reading: CpuTemperature) {
+        self.push_timed(TimedReading::now(reading));
+    }
+
+    /// Records a reading that already carries its own timestamp.
+    pub fn push_timed(&mut self, reading: TimedReading) {
+        if let Some(previous) = self.readings.back() {
+            if previous.reading.celsius() != reading.reading.celsius() {
+                self.last_change = Some(reading.at);
+            }
+        }
+
+        if self.readings.len() == self.capacity {
+            self.readings.pop_front();
+        }
+        self.readings.push_back(reading);
+    }
+
+    /// Returns the number of buffered readings.
+    pub fn len(&self) -> usize {
+        self.readings.len()
+    }
+
+    /// Returns `true` if no readings are buffered.
+    pub fn is_empty(&self) -> bool {
+        self.readings.is_empty()
+    }
+
+    /// Returns the maximum number of readings the history holds.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the most recently pushed reading.
+    pub fn latest(&self) -> Option<&TimedReading> {
+        self.readings.back()
+    }
+
+    /// Iterates over the buffered readings from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = &TimedReading> {
+        self.readings.iter()
+    }
+
+    /// Returns how long ago the Celsius value last changed.
+    ///
+    /// The change is timestamped with the first reading that differed from
+    /// the reading pushed before it, and is tracked even after that reading
+    /// has been evicted from the buffer. A duration that keeps growing while
+    /// readings are still being pushed points to a sluggish or frozen sensor.
+    ///
+    /// Returns `None` until two distinct consecutive readings have been
+    /// pushed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tunjukin_suhu_cpu_windows::{CpuTemperature, TempHistory};
+    ///
+    /// let mut history = TempHistory::new(10);
+    /// history.push(CpuTemperature::from_celsius(40.0));
+    /// assert_eq!(history.time_since_change(), None);
+    ///
+    /// history.push(CpuTemperature::from_celsius(41.0));
+    /// assert!(history.time_since_change().is_some());
+    /// ```
+    pub fn time_since_change(&self) -> Option<Duration> {
+        self.last_change.map(|changed_at| Instant::now().saturating_duration_since(changed_at))
+    }
+
+    /// Returns the temperature trend in degrees Celsius per second.
+    ///
+    /// The slope is the least-squares linear regression of Celsius against the
+    /// sample timestamps over every buffered reading, so single noisy samples
+    /// have limited influence. A positive value means the temperature is
+    /// rising.
+    ///
+    /// Returns `None` with fewer than two readings, or when every reading
+    /// shares the same timestamp.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    /// use tunjukin_suhu_cpu_windows::{CpuTemperature, TempHistory, TimedReading};
+    ///
+    /// let start = Instant::now();
+    /// let mut history = TempHistory::new(10);
+    /// for i in 0..5 {
+    ///     let reading = CpuTemperature::from_celsius(40.0 + i as f64 * 0.5);
+    ///     history.push_timed(TimedReading::new(reading, start + Duration::from_secs(i)));
+    /// }
+    ///
+    /// assert!((history.slope().unwrap() - 0.5).abs() < 1e-9);
+    /// ```
+    pub fn slope(&self) -> Option<f64> {
+        if self.readings.len() < 2 {
+            return None;
+        }
+
+        let origin = self.readings.front()?.at;
+        let points: Vec<(f64, f64)> = self
+            .readings
+            .iter()
+            .map(|r| (r.at.duration_since(origin).as_secs_f64(), r.reading.celsius()))
+            .collect();
+
+        let n = points.len() as f64;
+        let mean_t = points.iter().map(|(t, _)| t).sum::<f64>() / n;
+        let mean_c = points.iter().map(|(_, c)| c).sum::<f64>() / n;
+
+        let covariance: f64 = points.iter().map(|(t, c)| (t - mean_t) * (c - mean_c)).sum();
+        let variance: f64 = points.iter().map(|(t, _)| (t - mean_t).powi(2)).sum();
+
+        if variance == 0.0 {
+            None
+        } else {
+            Some(covariance / variance)
+        }
+    }
+}
+
+/// Reduces a timed series to roughly `target_points` readings for plotting.
+///
+/// Uses the largest-triangle-three-buckets (LTTB) algorithm, which keeps the
+/// first and last readings and, from each bucket in between, the reading that
+/// best preserves the visual shape of the series. Peaks and dips therefore
+/// survive downsampling, unlike with plain averaging. The returned readings
+/// are original samples, in their original order.
+///
+/// `samples` is expected to be in chronological order. When `target_points`
+/// is at least `samples.len()` the input is returned unchanged; a target of 1
+/// or 2 keeps only the first, or first and last, readings.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use tunjukin_suhu_cpu_windows::{downsample, CpuTemperature, TimedReading};
+///
+/// let start = Instant::now();
+/// let samples: Vec<TimedReading> = (0..1000)
+///     .map(|i| TimedReading::new(
+///         CpuTemperature::from_celsius(40.0 + (i % 50) as f64 * 0.1),
+///         start + Duration::from_secs(i),
+///     ))
+///     .collect();
+///
+/// assert_eq!(downsample(&samples, 100).len(), 100);
+/// ```
+pub fn downsample(samples: &[TimedReading], target_points: usize) -> Vec<TimedReading> {
+    let n = samples.len();
+    if target_points >= n {
+        return samples.to_vec();
+    }
+    match target_points {
+        0 => return Vec::new(),
+        1 => return vec![samples[0].clone()],
+        2 => return vec![samples[0].clone(), samples[n - 1].clone()],
+        _ => {}
+    }
+
+    let origin = samples[0].at;
+    let point = |i: usize| {
+        (
+            samples[i].at.duration_since(origin).as_secs_f64(),
+            samples[i].reading.celsius(),
+        )
+    };
+
+    let bucket_size = (n - 2) as f64 / (target_points - 2) as f64;
+    let mut sampled = Vec::with_capacity(target_points);
+    sampled.push(samples[0].clone());
+    let mut anchor = 0;
+
+    for bucket in 0..target_points - 2 {
+        // Average of the next bucket, used as the third triangle vertex.
+        let next_start = ((bucket + 1) as f64 * bucket_size) as usize + 1;
+        let next_end = (((bucket + 2) as f64 * bucket_size) as usize + 1).min(n);
+        let next_len = (next_end - next_start) as f64;
+        let (avg_t, avg_c) = (next_start..next_end)
+            .map(point)
+            .fold((0.0, 0.0), |(t, c), (pt, pc)| (t + pt, c + pc));
+        let (avg_t, avg_c) = (avg_t / next_len, avg_c / next_len);
+
+        let start = (bucket as f64 * bucket_size) as usize + 1;
+        let end = next_start;
+        let (anchor_t, anchor_c) = point(anchor);
+
+        let mut best = start;
+        let mut best_area = -1.0;
+        for i in start..end {
+            let (t, c) = point(i);
+            let area = ((anchor_t - avg_t) * (c - anchor_c) - (anchor_t - t) * (avg_c - anchor_c)).abs();
+            if area > best_area {
+                best_area = area;
+                best = i;
+            }
+        }
+
+        sampled.push(samples[best].clone());
+        anchor = best;
+    }
+
+    sampled.push(samples[n - 1].clone());
+    sampled
+}
+
+/// Minimum coefficient of determination for [`thermal_time_constant`] to
+/// accept its exponential fit.
+const MIN_FIT_R_SQUARED: f64 = 0.9;
+
+/// Estimates the thermal time constant τ, in seconds, of a cooldown curve.
+///
+/// Fits Newton's law of cooling, `T(t) = ambient + ΔT·e^(-t/τ)`, to the
+/// samples. Taking the logarithm turns the model into the straight line
+/// `ln(T - ambient) = ln ΔT - t/τ`, which is fitted with least squares; τ is
+/// the negative reciprocal of the fitted slope. After one τ the excess over
+/// ambient has dropped to about 37%, and after three τ to about 5%.
+///
+/// `ambient` is the Celsius temperature the cooler is settling towards, and
+/// the samples are expected to cover a cooldown in chronological order.
+///
+/// Returns `None` when there are fewer than three samples, when any sample is
+/// at or below `ambient`, when the curve is not decaying, or when the fit is
+/// poor (a coefficient of determination below 0.9).
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use tunjukin_suhu_cpu_windows::{thermal_time_constant, CpuTemperature, TimedReading};
+///
+/// let start = Instant::now();
+/// let cooldown: Vec<TimedReading> = (0..10)
+///     .map(|i| {
+///         let t = i as f64 * 10.0;
+///         let celsius = 30.0 + 50.0 * (-t / 45.0).exp();
+///         TimedReading::new(CpuTemperature::from_celsius(celsius), start + Duration::from_secs_f64(t))
+///     })
+///     .collect();
+///
+/// let tau = thermal_time_constant(&cooldown, 30.0).unwrap();
+/// assert!((tau - 45.0).abs() < 0.01);
+/// ```
+pub fn thermal_time_constant(samples: &[TimedReading], ambient: f64) -> Option<f64> {
+    if samples.len() < 3 {
+        return None;
+    }
+
+    let origin = samples[0].at;
+    let points = samples
+        .iter()
+        .map(|s| {
+            let excess = s.reading.celsius() - ambient;
+            (excess > 0.0).then(|| (s.at.duration_since(origin).as_secs_f64(), excess.ln()))
+        })
+        .collect::<Option<Vec<(f64, f64)>>>()?;
+
+    let n = points.len() as f64;
+    let mean_t = points.iter().map(|(t, _)| t).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let sxy: f64 = points.iter().map(|(t, y)| (t - mean_t) * (y - mean_y)).sum();
+    let sxx: f64 = points.iter().map(|(t, _)| (t - mean_t).powi(2)).sum();
+    let syy: f64 = points.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+
+    if sxx == 0.0 || syy == 0.0 {
+        return None;
+    }
+
+    let slope = sxy / sxx;
+    let r_squared = sxy * sxy / (sxx * syy);
+    if slope >= 0.0 || r_squared < MIN_FIT_R_SQUARED {
+        return None;
+    }
+
+    Some(-1.0 / slope)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history_from(points: &[(u64, f64)]) -> TempHistory {
+        let start = Instant::now();
+        let mut history = TempHistory::new(points.len().max(1));
+        for &(secs, celsius) in points {
+            history.push_timed(TimedReading::new(
+                CpuTemperature::from_celsius(celsius),
+                start + Duration::from_secs(secs),
+            ));
+        }
+        history
+    }
+
+    #[test]
+    fn test_new_derives_system_time() {
+        let before = SystemTime::now();
+        let reading = TimedReading::new(CpuTemperature::from_celsius(40.0), Instant::now() - Duration::from_secs(60));
+
+        let age = before.duration_since(reading.time).unwrap();
+        assert!(age > Duration::from_secs(59) && age < Duration::from_secs(61), "{:?}", age);
+    }
+
+    #[test]
+    fn test_history_evicts_oldest() {
+        let mut history = TempHistory::new(2);
+        for celsius in [40.0, 41.0, 42.0] {
+            history.push(CpuTemperature::from_celsius(celsius));
+        }
+
+        let values: Vec<f64> = history.iter().map(|r| r.reading.celsius()).collect();
+        assert_eq!(values, vec![41.0, 42.0]);
+        assert_eq!(history.latest().unwrap().reading.celsius(), 42.0);
+    }
+
+    #[test]
+    fn test_slope_of_noisy_rise() {
+        let history = history_from(&[(0, 40.0), (1, 41.5), (2, 41.0), (3, 43.5)]);
+        let slope = history.slope().unwrap();
+
+        assert!((slope - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_slope_falling() {
+        let history = history_from(&[(0, 60.0), (10, 50.0)]);
+        assert!((history.slope().unwrap() + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_slope_needs_two_distinct_times() {
+        assert_eq!(history_from(&[]).slope(), None);
+        assert_eq!(history_from(&[(0, 40.0)]).slope(), None);
+        assert_eq!(history_from(&[(5, 40.0), (5, 45.0)]).slope(), None);
+    }
+
+    fn series(values: &[f64]) -> Vec<TimedReading> {
+        let start = Instant::now();
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| TimedReading::new(CpuTemperature::from_celsius(c), start + Duration::from_secs(i as u64)))
+            .collect()
+    }
+
+    #[test]
+    fn test_downsample_keeps_endpoints_and_count() {
+        let values: Vec<f64> = (0..100).map(|i| 40.0 + i as f64 * 0.1).collect();
+        let samples = series(&values);
+        let reduced = downsample(&samples, 10);
+
+        assert_eq!(reduced.len(), 10);
+        assert_eq!(reduced.first(), samples.first());
+        assert_eq!(reduced.last(), samples.last());
+        assert!(reduced.windows(2).all(|w| w[0].at < w[1].at));
+    }
+
+    #[test]
+    fn test_downsample_preserves_spike() {
+        let mut values = vec![40.0; 200];
+        values[123] = 95.0;
+        let reduced = downsample(&series(&values), 20);
+
+        assert!(reduced.iter().any(|r| r.reading.celsius() == 95.0));
+    }
+
+    #[test]
+    fn test_downsample_small_targets() {
+        let samples = series(&[40.0, 41.0, 42.0, 43.0]);
+
+        assert_eq!(downsample(&samples, 4), samples);
+        assert_eq!(downsample(&samples, 10), samples);
+        assert!(downsample(&samples, 0).is_empty());
+        assert_eq!(downsample(&samples, 1), vec![samples[0].clone()]);
+        assert_eq!(downsample(&samples, 2), vec![samples[0].clone(), samples[3].clone()]);
+    }
+
+    #[test]
+    fn test_time_since_change_tracks_last_distinct_value() {
+        let start = Instant::now().checked_sub(Duration::from_secs(30)).unwrap();
+        let mut history = TempHistory::new(1);
+        let push = |history: &mut TempHistory, secs: u64, celsius: f64| {
+            history.push_timed(TimedReading::new(
+                CpuTemperature::from_celsius(celsius),
+                start + Duration::from_secs(secs),
+            ));
+        };
+
+        push(&mut history, 0, 40.0);
+        push(&mut history, 5, 40.0);
+        assert_eq!(history.time_since_change(), None);
+
+        push(&mut history, 10, 42.0);
+        push(&mut history, 20, 42.0);
+        let elapsed = history.time_since_change().unwrap();
+
+        // Changed at start + 10s, which is roughly 20 seconds ago.
+        assert!(elapsed >= Duration::from_secs(20) && elapsed < Duration::from_secs(25));
+    }
+
+    fn curve(points: &[(f64, f64)]) -> Vec<TimedReading> {
+        let start = Instant::now();
+        points
+            .iter()
+            .map(|&(t, c)| TimedReading::new(CpuTemperature::from_celsius(c), start + Duration::from_secs_f64(t)))
+            .collect()
+    }
+
+    #[test]
+    fn test_time_constant_of_exponential_cooldown() {
+        let points: Vec<(f64, f64)> = (0..20)
+            .map(|i| {
+                let t = i as f64 * 5.0;
+                (t, 25.0 + 60.0 * (-t / 60.0).exp())
+            })
+            .collect();
+
+        let tau = thermal_time_constant(&curve(&points), 25.0).unwrap();
+        assert!((tau - 60.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_time_constant_rejects_bad_input() {
+        // Too few samples.
+        assert_eq!(thermal_time_constant(&curve(&[(0.0, 80.0), (10.0, 60.0)]), 25.0), None);
+        // A sample at ambient cannot be log-transformed.
+        assert_eq!(thermal_time_constant(&curve(&[(0.0, 80.0), (10.0, 60.0), (20.0, 25.0)]), 25.0), None);
+        // Heating up, not cooling down.
+        assert_eq!(thermal_time_constant(&curve(&[(0.0, 40.0), (10.0, 50.0), (20.0, 60.0)]), 25.0), None);
+    }
+
+    #[test]
+    fn test_time_constant_rejects_poor_fit() {
+        let points = [(0.0, 80.0), (10.0, 40.0), (20.0, 75.0), (30.0, 35.0), (40.0, 70.0), (50.0, 30.0)];
+        assert_eq!(thermal_time_constant(&curve(&points), 25.0), None);
+    }
+}
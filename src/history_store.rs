@@ -0,0 +1,313 @@
+//! Persistent reading history in an SQLite database.
+//!
+//! One row per reading is cheap in SQLite: a month of 1 Hz samples is about
+//! 2.6 million rows and a few hundred megabytes, and the index on the
+//! timestamp keeps range queries fast at that size.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use crate::{CpuTemperature, SamplerHandle, TemperatureError, ThermalZoneReading};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS readings (
+        taken_at INTEGER NOT NULL,
+        zone TEXT,
+        celsius REAL NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS readings_taken_at ON readings (taken_at);
+";
+
+/// A reading loaded from a [`HistoryStore`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StoredReading {
+    /// When the reading was taken, to the millisecond.
+    pub taken_at: SystemTime,
+    /// The thermal zone, if the reading was recorded with one.
+    pub zone: Option<String>,
+    /// The temperature.
+    pub temperature: CpuTemperature,
+}
+
+/// Stores every reading in an SQLite database and answers questions about
+/// time ranges.
+///
+/// Timestamps are stored as milliseconds since the Unix epoch. Ranges are
+/// inclusive at both ends.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::{Duration, SystemTime};
+/// use tunjukin_suhu_cpu_windows::{HistoryStore, TemperatureSampler};
+///
+/// let sampler = TemperatureSampler::new(Duration::from_secs(1)).spawn();
+/// HistoryStore::open("temperatures.db")?.attach(&sampler);
+///
+/// // Later, possibly from another process:
+/// let store = HistoryStore::open("temperatures.db")?;
+/// let day_ago = SystemTime::now() - Duration::from_secs(24 * 60 * 60);
+///
+/// if let Some(peak) = store.max_between(day_ago, SystemTime::now())? {
+///     println!("hottest in the last day: {:.1}°C", peak.temperature.celsius());
+/// }
+/// store.prune_older_than(Duration::from_secs(90 * 24 * 60 * 60))?;
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug)]
+pub struct HistoryStore {
+    connection: Connection,
+}
+
+impl HistoryStore {
+    /// Opens the database at `path`, creating it and its table if needed.
+    ///
+    /// File databases use write-ahead logging, so other processes can query
+    /// the history while a sampler is writing to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TemperatureError::Other`] if the database cannot be opened
+    /// or is not a history database.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, TemperatureError> {
+        let connection = Connection::open(path).map_err(store_error)?;
+        connection
+            .query_row("PRAGMA journal_mode = WAL", [], |_| Ok(()))
+            .map_err(store_error)?;
+        Self::with_connection(connection)
+    }
+
+    /// Opens a database that lives only as long as the store, for tests.
+    pub fn open_in_memory() -> Result<Self, TemperatureError> {
+        Self::with_connection(Connection::open_in_memory().map_err(store_error)?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, TemperatureError> {
+        connection.execute_batch(SCHEMA).map_err(store_error)?;
+        Ok(HistoryStore { connection })
+    }
+
+    /// Stores `reading` without a zone, stamped with the current time.
+    pub fn record(&self, reading: &CpuTemperature) -> Result<(), TemperatureError> {
+        self.record_at(SystemTime::now(), None, reading)
+    }
+
+    /// Stores every zone in `zones` in one transaction, all stamped with the
+    /// current time.
+    pub fn record_zones(&mut self, zones: &[ThermalZoneReading]) -> Result<(), TemperatureError> {
+        let taken_at = millis(SystemTime::now());
+        let transaction = self.connection.transaction().map_err(store_error)?;
+        for zone in zones {
+            transaction
+                .execute(
+                    "INSERT INTO readings (taken_at, zone, celsius) VALUES (?1, ?2, ?3)",
+                    params![taken_at, zone.instance_name, zone.temperature.celsius()],
+                )
+                .map_err(store_error)?;
+        }
+        transaction.commit().map_err(store_error)
+    }
+
+    /// Stores `reading` with an explicit timestamp and zone, for importing
+    /// readings taken elsewhere.
+    pub fn record_at(&self, taken_at: SystemTime, zone: Option<&str>, reading: &CpuTemperature) -> Result<(), TemperatureError> {
+        self.connection
+            .execute(
+                "INSERT INTO readings (taken_at, zone, celsius) VALUES (?1, ?2, ?3)",
+                params![millis(taken_at), zone, reading.celsius()],
+            )
+            .map(|_| ())
+            .map_err(store_error)
+    }
+
+    /// Returns every reading taken between `from` and `to`, oldest first.
+    pub fn readings_between(&self, from: SystemTime, to: SystemTime) -> Result<Vec<StoredReading>, TemperatureError> {
+        let mut statement = self
+            .connection
+            .prepare(
+                "SELECT taken_at, zone, celsius FROM readings
+                 WHERE taken_at BETWEEN ?1 AND ?2 ORDER BY taken_at, rowid",
+            )
+            .map_err(store_error)?;
+        let rows = statement
+            .query_map(params![millis(from), millis(to)], stored_reading)
+            .map_err(store_error)?;
+
+        rows.collect::<Result<_, _>>().map_err(store_error)
+    }
+
+    /// Returns the hottest reading taken between `from` and `to`, or `None`
+    /// if there are none. Ties go to the earliest reading.
+    pub fn max_between(&self, from: SystemTime, to: SystemTime) -> Result<Option<StoredReading>, TemperatureError> {
+        self.extreme_between(from, to, "DESC")
+    }
+
+    /// Returns the coolest reading taken between `from` and `to`, or `None`
+    /// if there are none. Ties go to the earliest reading.
+    pub fn min_between(&self, from: SystemTime, to: SystemTime) -> Result<Option<StoredReading>, TemperatureError> {
+        self.extreme_between(from, to, "ASC")
+    }
+
+    /// Returns the mean of the readings taken between `from` and `to`, or
+    /// `None` if there are none.
+    pub fn mean_between(&self, from: SystemTime, to: SystemTime) -> Result<Option<CpuTemperature>, TemperatureError> {
+        let mean: Option<f64> = self
+            .connection
+            .query_row(
+                "SELECT AVG(celsius) FROM readings WHERE taken_at BETWEEN ?1 AND ?2",
+                params![millis(from), millis(to)],
+                |row| row.get(0),
+            )
+            .map_err(store_error)?;
+
+        Ok(mean.map(CpuTemperature::from_celsius))
+    }
+
+    /// Returns the number of stored readings.
+    pub fn len(&self) -> Result<u64, TemperatureError> {
+        self.connection
+            .query_row("SELECT COUNT(*) FROM readings", [], |row| row.get(0))
+            .map_err(store_error)
+    }
+
+    /// Returns `true` if no readings are stored.
+    pub fn is_empty(&self) -> Result<bool, TemperatureError> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Deletes every reading taken before `cutoff` and returns how many were
+    /// deleted.
+    ///
+    /// The database file does not shrink; SQLite reuses the freed pages for
+    /// new readings.
+    pub fn prune_before(&self, cutoff: SystemTime) -> Result<usize, TemperatureError> {
+        self.connection
+            .execute("DELETE FROM readings WHERE taken_at < ?1", params![millis(cutoff)])
+            .map_err(store_error)
+    }
+
+    /// Deletes every reading older than `retention` and returns how many were
+    /// deleted.
+    pub fn prune_older_than(&self, retention: Duration) -> Result<usize, TemperatureError> {
+        let cutoff = SystemTime::now().checked_sub(retention).unwrap_or(UNIX_EPOCH);
+        self.prune_before(cutoff)
+    }
+
+    /// Stores every successful sample from `sampler` on the sampler's thread.
+    ///
+    /// Failed reads are not stored, and neither are readings that cannot be
+    /// written, for example while another process holds a write lock.
+    pub fn attach(self, sampler: &SamplerHandle) {
+        sampler.subscribe_with(move |sample| {
+            if let Ok(reading) = sample {
+                let _ = self.record(reading);
+            }
+        });
+    }
+
+    fn extreme_between(&self, from: SystemTime, to: SystemTime, order: &str) -> Result<Option<StoredReading>, TemperatureError> {
+        let sql = format!(
+            "SELECT taken_at, zone, celsius FROM readings
+             WHERE taken_at BETWEEN ?1 AND ?2 ORDER BY celsius {}, taken_at LIMIT 1",
+            order
+        );
+        self.connection
+            .query_row(&sql, params![millis(from), millis(to)], stored_reading)
+            .optional()
+            .map_err(store_error)
+    }
+}
+
+fn stored_reading(row: &Row<'_>) -> rusqlite::Result<StoredReading> {
+    let taken_at: i64 = row.get(0)?;
+    Ok(StoredReading {
+        taken_at: UNIX_EPOCH + Duration::from_millis(taken_at.max(0) as u64),
+        zone: row.get(1)?,
+        temperature: CpuTemperature::from_celsius(row.get(2)?),
+    })
+}
+
+/// Converts `time` to milliseconds since the Unix epoch, clamping earlier
+/// times to zero.
+fn millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|elapsed| i64::try_from(elapsed.as_millis()).unwrap_or(i64::MAX))
+        .unwrap_or(0)
+}
+
+fn store_error(error: rusqlite::Error) -> TemperatureError {
+    TemperatureError::Other(format!("History store error: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_760_400_000 + seconds)
+    }
+
+    fn filled_store() -> HistoryStore {
+        let store = HistoryStore::open_in_memory().unwrap();
+        for (seconds, celsius) in [(0, 40.0), (10, 70.0), (20, 55.0), (30, 70.0), (40, 45.0)] {
+            store.record_at(at(seconds), Some("TZ00"), &CpuTemperature::from_celsius(celsius)).unwrap();
+        }
+        store
+    }
+
+    #[test]
+    fn test_range_queries() {
+        let store = filled_store();
+
+        let peak = store.max_between(at(0), at(40)).unwrap().unwrap();
+        assert_eq!(peak.temperature.celsius(), 70.0);
+        assert_eq!(peak.taken_at, at(10));
+        assert_eq!(peak.zone.as_deref(), Some("TZ00"));
+
+        assert_eq!(store.min_between(at(5), at(35)).unwrap().unwrap().temperature.celsius(), 55.0);
+        assert_eq!(store.mean_between(at(20), at(40)).unwrap().unwrap().celsius(), 170.0 / 3.0);
+        assert_eq!(store.readings_between(at(10), at(20)).unwrap().len(), 2);
+
+        assert_eq!(store.max_between(at(100), at(200)).unwrap(), None);
+        assert_eq!(store.mean_between(at(100), at(200)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_prune_before() {
+        let store = filled_store();
+
+        assert_eq!(store.prune_before(at(20)).unwrap(), 2);
+        assert_eq!(store.len().unwrap(), 3);
+        assert_eq!(store.readings_between(at(0), at(40)).unwrap()[0].taken_at, at(20));
+    }
+
+    #[test]
+    fn test_record_zones_and_reopen() {
+        let path = std::env::temp_dir().join(format!("tunjukin_suhu_test_{}_history.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        {
+            let mut store = HistoryStore::open(&path).unwrap();
+            store
+                .record_zones(&[
+                    ThermalZoneReading::new(Some("TZ00".to_string()), CpuTemperature::from_celsius(40.0)),
+                    ThermalZoneReading::new(None, CpuTemperature::from_celsius(42.0)),
+                ])
+                .unwrap();
+        }
+
+        let store = HistoryStore::open(&path).unwrap();
+        let readings = store.readings_between(UNIX_EPOCH, SystemTime::now()).unwrap();
+        assert_eq!(readings.len(), 2);
+        assert_eq!(readings[1].zone, None);
+        assert!(!store.is_empty().unwrap());
+
+        drop(store);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+}
@@ -0,0 +1,295 @@
+//! Sensor readings from HWiNFO's shared memory.
+//!
+//! With "Shared Memory Support" enabled in its settings, HWiNFO publishes
+//! every sensor it reads in the `Global\HWiNFO_SENS_SM2` file mapping. The
+//! block starts with a header locating two arrays, one element per sensor
+//! (a chip, such as `CPU [#0]: Intel Core i7-12700K: DTS`) and one per
+//! reading of a sensor (such as `Core 0`):
+//!
+//! | Offset | Header field                 | Type  |
+//! |--------|------------------------------|-------|
+//! | 0      | `dwSignature`                | `"HWiS"`, or `"DEAD"` when stopped |
+//! | 20     | `dwOffsetOfSensorSection`    | `u32` |
+//! | 24     | `dwSizeOfSensorElement`      | `u32` |
+//! | 28     | `dwNumSensorElements`        | `u32` |
+//! | 32     | `dwOffsetOfReadingSection`   | `u32` |
+//! | 36     | `dwSizeOfReadingElement`     | `u32` |
+//! | 40     | `dwNumReadingElements`       | `u32` |
+//!
+//! A sensor element holds its user-facing name as 128 ANSI characters at
+//! offset 136. A reading element holds its type at 0, the index of its
+//! sensor at 4, its user-facing label at 140, its unit as 16 characters at
+//! 268 and its current value as an `f64` at 284, in the unit HWiNFO
+//! displays.
+//!
+//! Only available on Windows with the `shared-memory` feature.
+
+use crate::sensor::{SensorKind, SensorProvider, SensorReading};
+use crate::shared_memory::{self, f64_at, str_at, u32_at};
+use crate::{is_plausible, CpuTemperature, TemperatureError, TemperatureProvider, ThermalZoneReading};
+
+/// The name of HWiNFO's file mapping.
+const MAPPING_NAME: &str = "Global\\HWiNFO_SENS_SM2";
+
+const SIGNATURE: &[u8; 4] = b"HWiS";
+/// The signature HWiNFO leaves behind when it stops publishing.
+const STOPPED_SIGNATURE: &[u8; 4] = b"DEAD";
+
+const SENSOR_SECTION_OFFSET: usize = 20;
+const READING_SECTION_OFFSET: usize = 32;
+
+const SENSOR_NAME_OFFSET: usize = 136;
+const READING_TYPE_OFFSET: usize = 0;
+const READING_SENSOR_OFFSET: usize = 4;
+const READING_LABEL_OFFSET: usize = 140;
+const READING_UNIT_OFFSET: usize = 268;
+const READING_VALUE_OFFSET: usize = 284;
+const NAME_LENGTH: usize = 128;
+const UNIT_LENGTH: usize = 16;
+
+const TYPE_TEMPERATURE: u32 = 1;
+const TYPE_VOLTAGE: u32 = 2;
+const TYPE_FAN: u32 = 3;
+
+/// Reads CPU temperatures, and other sensors through [`SensorProvider`],
+/// from HWiNFO while it is running.
+///
+/// HWiNFO must run with "Shared Memory Support" enabled; its free version
+/// turns it off again after 12 hours.
+///
+/// As a [`TemperatureProvider`], this returns the temperature readings of
+/// HWiNFO's CPU sensors, such as `CPU Package` and `Core 0`, named by their
+/// label. A label reported by several CPU sensors is returned once. As a
+/// [`SensorProvider`], it also returns the temperatures of GPUs, drives and
+/// batteries and every fan speed and voltage, each named
+/// `sensor: label`.
+///
+/// # Errors
+///
+/// Reads fail with [`TemperatureError::NoSensors`] while HWiNFO is not
+/// publishing its shared memory.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::{HwInfoProvider, TemperatureProvider};
+///
+/// for reading in HwInfoProvider.read()? {
+///     println!("{}: {:.0}°C", reading.instance_name.unwrap_or_default(), reading.temperature.celsius());
+/// }
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HwInfoProvider;
+
+impl TemperatureProvider for HwInfoProvider {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        cpu_temperatures_from(&entries_from(&shared_memory::read(MAPPING_NAME)?)?)
+    }
+}
+
+impl SensorProvider for HwInfoProvider {
+    fn read_sensors(&self) -> Result<Vec<SensorReading>, TemperatureError> {
+        sensors_from(&entries_from(&shared_memory::read(MAPPING_NAME)?)?)
+    }
+}
+
+/// One reading element, joined with the name of its sensor.
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    reading_type: u32,
+    sensor: String,
+    label: String,
+    unit: String,
+    value: f64,
+}
+
+impl Entry {
+    /// Returns the value in degrees Celsius, for a temperature in °C or °F.
+    fn celsius(&self) -> f64 {
+        match self.unit.ends_with('F') {
+            true => (self.value - 32.0) * 5.0 / 9.0,
+            false => self.value,
+        }
+    }
+}
+
+/// Parses every reading element out of the shared memory block.
+fn entries_from(data: &[u8]) -> Result<Vec<Entry>, TemperatureError> {
+    let truncated = || TemperatureError::ParseError("the HWiNFO shared memory is truncated".to_string());
+    match data.get(..4) {
+        Some(signature) if signature == SIGNATURE => {}
+        Some(signature) if signature == STOPPED_SIGNATURE => return Err(TemperatureError::NoSensors),
+        Some(_) => return Err(TemperatureError::ParseError("the HWiNFO shared memory has an unknown signature".to_string())),
+        None => return Err(truncated()),
+    }
+    let section = |offset: usize| -> Option<(usize, usize, usize)> {
+        Some((u32_at(data, offset)? as usize, u32_at(data, offset + 4)? as usize, u32_at(data, offset + 8)? as usize))
+    };
+    let (sensors_at, sensor_size, sensor_count) = section(SENSOR_SECTION_OFFSET).ok_or_else(truncated)?;
+    let (readings_at, reading_size, reading_count) = section(READING_SECTION_OFFSET).ok_or_else(truncated)?;
+
+    let sensors: Vec<String> = (0..sensor_count)
+        .map_while(|index| str_at(data, sensors_at + index * sensor_size + SENSOR_NAME_OFFSET, NAME_LENGTH))
+        .collect();
+    let mut entries = Vec::new();
+    for index in 0..reading_count {
+        let element = readings_at + index * reading_size;
+        let (Some(reading_type), Some(sensor), Some(label), Some(unit), Some(value)) = (
+            u32_at(data, element + READING_TYPE_OFFSET),
+            u32_at(data, element + READING_SENSOR_OFFSET),
+            str_at(data, element + READING_LABEL_OFFSET, NAME_LENGTH),
+            str_at(data, element + READING_UNIT_OFFSET, UNIT_LENGTH),
+            f64_at(data, element + READING_VALUE_OFFSET),
+        ) else {
+            break;
+        };
+        let sensor = sensors.get(sensor as usize).cloned().unwrap_or_default();
+        entries.push(Entry { reading_type, sensor, label, unit, value });
+    }
+    if entries.is_empty() {
+        return Err(TemperatureError::NoSensors);
+    }
+
+    Ok(entries)
+}
+
+/// Keeps the plausible temperatures of CPU sensors, once per label.
+fn cpu_temperatures_from(entries: &[Entry]) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+    let mut readings: Vec<ThermalZoneReading> = Vec::new();
+    for entry in entries {
+        if entry.reading_type != TYPE_TEMPERATURE || temperature_kind(&entry.sensor) != Some(SensorKind::Cpu) {
+            continue;
+        }
+        if !is_plausible(entry.celsius()) || readings.iter().any(|reading| reading.instance_name.as_deref() == Some(&entry.label)) {
+            continue;
+        }
+        readings.push(ThermalZoneReading::new(Some(entry.label.clone()), CpuTemperature::from_celsius(entry.celsius())));
+    }
+    if readings.is_empty() {
+        return Err(TemperatureError::NoValidReadings);
+    }
+
+    Ok(readings)
+}
+
+/// Converts the temperatures, fan speeds and voltages into sensor readings.
+fn sensors_from(entries: &[Entry]) -> Result<Vec<SensorReading>, TemperatureError> {
+    let readings: Vec<SensorReading> = entries
+        .iter()
+        .filter_map(|entry| {
+            let (kind, value) = match entry.reading_type {
+                TYPE_TEMPERATURE => (temperature_kind(&entry.sensor)?, entry.celsius()),
+                TYPE_FAN => (SensorKind::Fan, entry.value),
+                TYPE_VOLTAGE => (SensorKind::Voltage, entry.value),
+                _ => return None,
+            };
+            if entry.reading_type == TYPE_TEMPERATURE && !is_plausible(value) {
+                return None;
+            }
+            Some(SensorReading::new(kind, Some(format!("{}: {}", entry.sensor, entry.label)), value))
+        })
+        .collect();
+    if readings.is_empty() {
+        return Err(TemperatureError::NoValidReadings);
+    }
+
+    Ok(readings)
+}
+
+/// Classifies the temperatures of a sensor by its name, or returns `None`
+/// for sensors such as motherboard chips that fit no [`SensorKind`].
+fn temperature_kind(sensor: &str) -> Option<SensorKind> {
+    if sensor.starts_with("CPU") {
+        Some(SensorKind::Cpu)
+    } else if sensor.starts_with("GPU") {
+        Some(SensorKind::Gpu)
+    } else if sensor.starts_with("S.M.A.R.T.") || sensor.starts_with("Drive") {
+        Some(SensorKind::Disk)
+    } else if sensor.contains("Battery") {
+        Some(SensorKind::Battery)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SENSOR_SIZE: usize = 264;
+    const READING_SIZE: usize = 316;
+
+    fn put(data: &mut [u8], offset: usize, bytes: &[u8]) {
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    /// Builds a shared memory block of `sensors` and `(type, sensor, label,
+    /// unit, value)` readings.
+    fn block(sensors: &[&str], readings: &[(u32, u32, &str, &[u8], f64)]) -> Vec<u8> {
+        let sensors_at = 44;
+        let readings_at = sensors_at + sensors.len() * SENSOR_SIZE;
+        let mut data = vec![0u8; readings_at + readings.len() * READING_SIZE];
+        put(&mut data, 0, SIGNATURE);
+        for (offset, value) in [(20, sensors_at), (24, SENSOR_SIZE), (28, sensors.len())]
+            .into_iter()
+            .chain([(32, readings_at), (36, READING_SIZE), (40, readings.len())])
+        {
+            put(&mut data, offset, &(value as u32).to_le_bytes());
+        }
+        for (index, name) in sensors.iter().enumerate() {
+            put(&mut data, sensors_at + index * SENSOR_SIZE + SENSOR_NAME_OFFSET, name.as_bytes());
+        }
+        for (index, &(reading_type, sensor, label, unit, value)) in readings.iter().enumerate() {
+            let element = readings_at + index * READING_SIZE;
+            put(&mut data, element + READING_TYPE_OFFSET, &reading_type.to_le_bytes());
+            put(&mut data, element + READING_SENSOR_OFFSET, &sensor.to_le_bytes());
+            put(&mut data, element + READING_LABEL_OFFSET, label.as_bytes());
+            put(&mut data, element + READING_UNIT_OFFSET, unit);
+            put(&mut data, element + READING_VALUE_OFFSET, &value.to_le_bytes());
+        }
+        data
+    }
+
+    fn sample() -> Vec<u8> {
+        block(
+            &["CPU [#0]: Intel Core i7-12700K: DTS", "CPU [#0]: Intel Core i7-12700K: Enhanced", "GPU [#0]: NVIDIA GeForce RTX 3080", "Nuvoton NCT6798D"],
+            &[
+                (TYPE_TEMPERATURE, 0, "Core 0", b"\xB0C", 52.0),
+                (TYPE_TEMPERATURE, 0, "CPU Package", b"\xB0C", 58.0),
+                (TYPE_TEMPERATURE, 1, "CPU Package", b"\xB0C", 58.5),
+                (TYPE_TEMPERATURE, 2, "GPU Temperature", b"\xB0F", 140.0),
+                (TYPE_TEMPERATURE, 3, "Motherboard", b"\xB0C", 35.0),
+                (TYPE_FAN, 3, "CPU", b"RPM", 1180.0),
+                (TYPE_VOLTAGE, 3, "Vcore", b"V", 1.248),
+                (5, 1, "CPU Package Power", b"W", 64.0),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_cpu_temperatures() {
+        let readings = cpu_temperatures_from(&entries_from(&sample()).unwrap()).unwrap();
+        assert_eq!(readings.len(), 2);
+        assert_eq!(readings[0].instance_name.as_deref(), Some("Core 0"));
+        assert_eq!(readings[1].instance_name.as_deref(), Some("CPU Package"));
+        assert_eq!(readings[1].temperature.celsius(), 58.0);
+    }
+
+    #[test]
+    fn test_sensors() {
+        let readings = sensors_from(&entries_from(&sample()).unwrap()).unwrap();
+        let kinds: Vec<SensorKind> = readings.iter().map(|reading| reading.kind).collect();
+        assert_eq!(kinds, [SensorKind::Cpu, SensorKind::Cpu, SensorKind::Cpu, SensorKind::Gpu, SensorKind::Fan, SensorKind::Voltage]);
+        assert_eq!(readings[3].name.as_deref(), Some("GPU [#0]: NVIDIA GeForce RTX 3080: GPU Temperature"));
+        assert!((readings[3].value - 60.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_signature() {
+        let mut data = sample();
+        put(&mut data, 0, STOPPED_SIGNATURE);
+        assert_eq!(entries_from(&data), Err(TemperatureError::NoSensors));
+        assert!(matches!(entries_from(b"HW"), Err(TemperatureError::ParseError(_))));
+    }
+}
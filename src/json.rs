@@ -0,0 +1,48 @@
+//! Minimal JSON encoding helpers for hand-built payloads.
+
+/// Encodes `value` as a JSON string literal, including the quotes.
+pub(crate) fn string(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len() + 2);
+    encoded.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => encoded.push_str("\\\""),
+            '\\' => encoded.push_str("\\\\"),
+            '\n' => encoded.push_str("\\n"),
+            '\r' => encoded.push_str("\\r"),
+            '\t' => encoded.push_str("\\t"),
+            c if (c as u32) < 0x20 => encoded.push_str(&format!("\\u{:04x}", c as u32)),
+            c => encoded.push(c),
+        }
+    }
+    encoded.push('"');
+    encoded
+}
+
+/// Encodes `value` as a JSON number with two decimals, or `null` if it is not
+/// finite.
+pub(crate) fn number(value: f64) -> String {
+    if value.is_finite() {
+        format!("{:.2}", value)
+    } else {
+        "null".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_escaping() {
+        assert_eq!(string("CPU \"hot\"\n\\"), r#""CPU \"hot\"\n\\""#);
+        assert_eq!(string("bell\u{7}"), r#""bell\u0007""#);
+    }
+
+    #[test]
+    fn test_number_encoding() {
+        assert_eq!(number(38.849), "38.85");
+        assert_eq!(number(f64::NAN), "null");
+        assert_eq!(number(f64::INFINITY), "null");
+    }
+}
@@ -0,0 +1,231 @@
+//! Last-known-value fallback for WMI outages.
+//!
+//! Each successful reading is written to a small cache file. When the provider
+//! later fails outright, the cached value is returned instead, flagged as
+//! stale, so callers degrade to an old-but-real number rather than nothing.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::{is_plausible, CpuTemperature, TemperatureError, TemperatureProvider, WmiProvider};
+
+/// The name of the cache file inside the system temporary directory.
+const CACHE_FILE_NAME: &str = "tunjukin_suhu_cpu_windows.last";
+
+/// A reading returned by [`LastKnownReader`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LastKnownReading {
+    /// The temperature, either fresh or loaded from the cache file.
+    pub temperature: CpuTemperature,
+    /// `true` if the provider failed and `temperature` came from the cache file.
+    pub stale: bool,
+    /// How long ago `temperature` was measured. Zero for fresh readings.
+    pub age: Duration,
+    /// When `temperature` was measured, `age` before the read. Not
+    /// serialized; deserialized readings are stamped with the moment they
+    /// were deserialized.
+    #[cfg_attr(feature = "serde", serde(skip, default = "Instant::now"))]
+    pub at: Instant,
+    /// When `temperature` was measured, on the system clock. For a stale
+    /// reading, this is the time written to the cache file.
+    pub time: SystemTime,
+}
+
+/// A reader that falls back to the last successful reading when the provider
+/// fails.
+///
+/// Every successful read is written to a cache file, by default
+/// `tunjukin_suhu_cpu_windows.last` in [`std::env::temp_dir`] (usually
+/// `%TEMP%` on Windows). If a later read fails and the cache file holds a
+/// value, that value is returned with [`LastKnownReading::stale`] set and its
+/// [`LastKnownReading::age`]. Only when there is no usable cache file is the
+/// provider's error returned.
+///
+/// The cache file is shared by every program using the same path, so a value
+/// written by one process can bridge an outage seen by another. It is
+/// replaced as a whole, never read half-written, and a cached value that is
+/// not a plausible temperature is ignored like a missing file. Failures to
+/// write the cache file are ignored; they never turn a good reading into an
+/// error.
+///
+/// Check `stale` and `age` before acting on a reading: a day-old value is of
+/// little use for thermal throttling decisions.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use tunjukin_suhu_cpu_windows::LastKnownReader;
+///
+/// let reader = LastKnownReader::new();
+/// let reading = reader.read()?;
+///
+/// if reading.stale {
+///     println!("{:.1}°C (WMI unavailable, {}s old)", reading.temperature.celsius(), reading.age.as_secs());
+/// } else {
+///     println!("{:.1}°C", reading.temperature.celsius());
+/// }
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct LastKnownReader<P = WmiProvider> {
+    provider: P,
+    cache_path: PathBuf,
+}
+
+impl LastKnownReader {
+    /// Creates a reader over the default WMI provider, caching to the default
+    /// location.
+    pub fn new() -> Self {
+        Self::with_provider(WmiProvider)
+    }
+}
+
+impl Default for LastKnownReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P> LastKnownReader<P> {
+    /// Creates a reader over `provider`, caching to the default location.
+    pub fn with_provider(provider: P) -> Self {
+        LastKnownReader {
+            provider,
+            cache_path: std::env::temp_dir().join(CACHE_FILE_NAME),
+        }
+    }
+
+    /// Stores the cache file at `path` instead of the default location.
+    pub fn cache_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache_path = path.into();
+        self
+    }
+
+    /// Returns the location of the cache file.
+    pub fn path(&self) -> &Path {
+        &self.cache_path
+    }
+}
+
+impl<P: TemperatureProvider> LastKnownReader<P> {
+    /// Takes a reading, falling back to the cached last value on failure.
+    pub fn read(&self) -> Result<LastKnownReading, TemperatureError> {
+        let now = SystemTime::now();
+
+        match self.provider.read_one() {
+            Ok(temperature) => {
+                let _ = write_replacing(&self.cache_path, &encode(&temperature, now));
+                Ok(LastKnownReading {
+                    temperature,
+                    stale: false,
+                    age: Duration::ZERO,
+                    at: Instant::now(),
+                    time: now,
+                })
+            }
+            Err(e) => fs::read_to_string(&self.cache_path)
+                .ok()
+                .and_then(|contents| decode(&contents, now))
+                .ok_or(e),
+        }
+    }
+}
+
+/// Writes `contents` to a file next to `path`, then renames it over `path`,
+/// so that readers see either the old or the new contents in full.
+fn write_replacing(path: &Path, contents: &str) -> io::Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(format!(".{}.tmp", process::id()));
+    let temporary = PathBuf::from(temporary);
+
+    fs::write(&temporary, contents)?;
+    fs::rename(&temporary, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temporary);
+    })
+}
+
+/// Encodes a reading and when it was taken as `<unix seconds> <celsius>`.
+fn encode(reading: &CpuTemperature, at: SystemTime) -> String {
+    let seconds = at.duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or(0);
+    format!("{} {}\n", seconds, reading.celsius())
+}
+
+/// Decodes a cache file written by [`encode`] into a stale reading, or
+/// `None` if it holds anything else.
+fn decode(contents: &str, now: SystemTime) -> Option<LastKnownReading> {
+    let mut fields = contents.split_whitespace();
+    let seconds: u64 = fields.next()?.parse().ok()?;
+    let celsius: f64 = fields.next()?.parse().ok()?;
+    if !celsius.is_finite() || !is_plausible(celsius) {
+        return None;
+    }
+
+    let taken_at = UNIX_EPOCH.checked_add(Duration::from_secs(seconds))?;
+    let age = now.duration_since(taken_at).unwrap_or(Duration::ZERO);
+    Some(LastKnownReading {
+        temperature: CpuTemperature::from_celsius(celsius),
+        stale: true,
+        age,
+        at: Instant::now().checked_sub(age).unwrap_or_else(Instant::now),
+        time: taken_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockProvider;
+
+    fn scratch_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("tunjukin_suhu_test_{}_{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_falls_back_to_cached_value() {
+        let path = scratch_path("fallback");
+        let provider = MockProvider::sequence(vec![Ok(CpuTemperature::from_celsius(48.5)), Err("WMI down".into())]);
+        let reader = LastKnownReader::with_provider(provider).cache_path(&path);
+
+        let fresh = reader.read().unwrap();
+        assert!(!fresh.stale);
+        assert_eq!(fresh.age, Duration::ZERO);
+
+        let stale = reader.read().unwrap();
+        assert!(stale.stale);
+        assert_eq!(stale.temperature, CpuTemperature::from_celsius(48.5));
+        assert!(!Path::new(&format!("{}.{}.tmp", path.display(), process::id())).exists());
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_error_without_cache_file() {
+        let path = scratch_path("missing");
+        let _ = fs::remove_file(&path);
+        let reader = LastKnownReader::with_provider(MockProvider::failing("WMI down")).cache_path(&path);
+
+        assert_eq!(reader.read().unwrap_err(), "WMI down".into());
+    }
+
+    #[test]
+    fn test_decode_age_and_garbage() {
+        let taken_at = UNIX_EPOCH + Duration::from_secs(1_760_400_000);
+        let contents = encode(&CpuTemperature::from_celsius(40.25), taken_at);
+
+        let reading = decode(&contents, taken_at + Duration::from_secs(90)).unwrap();
+        assert_eq!(reading.age, Duration::from_secs(90));
+        assert_eq!(reading.time, taken_at);
+        assert_eq!(reading.temperature.celsius(), 40.25);
+
+        assert_eq!(decode("", taken_at), None);
+        assert_eq!(decode("1760400000 NaN", taken_at), None);
+        assert_eq!(decode("soon 40.0", taken_at), None);
+        assert_eq!(decode("1760400000 2732", taken_at), None);
+        assert_eq!(decode("18446744073709551615 40.0", taken_at), None);
+    }
+}
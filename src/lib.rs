@@ -17,9 +17,49 @@
 //! ## Requirements
 //!
 //! - Windows operating system
-//! - PowerShell available in PATH
+//! - Windows PowerShell or PowerShell 7 (`pwsh`) available in PATH, unless
+//!   the `native` feature is used
 //! - Administrator privileges may be required for some systems
 //!
+//! ## Cargo features
+//!
+//! - `native`: query WMI in-process through COM instead of spawning
+//!   PowerShell for [`CpuTemperature::get`]. Only has an effect on Windows.
+//! - `powershell-fallback` (default): let the native backend fall back to
+//!   PowerShell when COM cannot reach WMI.
+//! - `msr`: `MsrProvider`, which reads per-core temperatures of Intel CPUs
+//!   through the WinRing0 driver the application ships. Only available on
+//!   Windows.
+//! - `shared-memory`: `CoreTempProvider`, `HwInfoProvider` and
+//!   `Aida64Provider`, which read temperatures from the shared memory Core
+//!   Temp, HWiNFO and AIDA64 publish while they run. Only available on
+//!   Windows.
+//! - `config`: [`Config`], which loads the providers, thresholds, sampling
+//!   interval, calibration offsets and sinks of a deployment from a TOML
+//!   file.
+//! - `uom`: conversions between [`Temperature`], [`CpuTemperature`] and
+//!   `uom`'s `ThermodynamicTemperature`.
+//! - `async`: [`CpuTemperature::get_async`] and other async wrappers that
+//!   run queries on tokio's blocking thread pool.
+//! - `serde`: `Serialize` and `Deserialize` for [`CpuTemperature`] and the
+//!   other reading types.
+//! - `history-sqlite`: [`HistoryStore`], which keeps every reading in an
+//!   SQLite database for range queries and retention pruning.
+//! - `mqtt`: [`MqttPublisher`], which publishes readings to an MQTT broker.
+//! - `toast`: [`ToastNotifier`], which raises Windows toast notifications
+//!   when an [`AlertMonitor`] threshold is crossed.
+//! - `event-log`: [`EventLogSink`], which writes alerts and periodic
+//!   readings to the Windows Event Log.
+//! - `cli`: the `suhu` command-line tool.
+//! - `exporter`: adds `suhu exporter`, a Prometheus exporter.
+//! - `server`: adds `suhu serve`, an HTTP endpoint for the current reading
+//!   with a WebSocket stream of live readings.
+//! - `service`: adds `suhu service`, which runs the exporter and CSV logging
+//!   as a Windows service.
+//! - `tray`: adds `suhu tray`, which shows the temperature in the
+//!   notification area.
+//! - `tui`: adds `suhu tui`, a live dashboard in the terminal.
+//!
 //! ## Quick Start
 //!
 //! ```no_run
@@ -28,7 +68,7 @@
 //! // Get the current CPU temperature
 //! match CpuTemperature::get() {
 //!     Ok(temp) => {
-//!         println!("CPU Temperature: {:.2}°C / {:.2}°F", temp.celsius, temp.fahrenheit);
+//!         println!("CPU Temperature: {:.2}°C / {:.2}°F", temp.celsius(), temp.fahrenheit());
 //!     }
 //!     Err(e) => {
 //!         eprintln!("Error reading temperature: {}", e);
@@ -38,24 +78,175 @@
 //!
 //! ## Error Handling
 //!
-//! This library returns detailed error messages for common issues:
+//! Every fallible operation returns a [`TemperatureError`], whose variants
+//! distinguish common issues:
 //! - PowerShell execution failures
-//! - WMI query errors
+//! - WMI query errors and denied access
 //! - Temperature sensor unavailability
 //! - Parsing errors
 
-use std::process::Command;
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::Read;
+use std::process::{Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 use regex::Regex;
 
+#[cfg(all(windows, feature = "shared-memory"))]
+mod aida64;
+mod aggregate;
+mod alert;
+mod alert_monitor;
+#[cfg(feature = "async")]
+mod asynchronous;
+mod battery;
+mod budget;
+mod cache;
+mod calibration;
+mod chain;
+mod clock;
+mod columns;
+mod confidence;
+#[cfg(feature = "config")]
+mod config;
+#[cfg(all(windows, feature = "shared-memory"))]
+mod core_temp;
+mod csv;
+mod detect;
+mod diagnostics;
+mod disk;
+mod duration;
+mod env;
+mod error;
+#[cfg(feature = "event-log")]
+mod event_log;
+mod fallback;
+mod fan;
+mod gpu;
+mod hardware_monitor;
+mod history;
+#[cfg(feature = "history-sqlite")]
+mod history_store;
+#[cfg(all(windows, feature = "shared-memory"))]
+mod hwinfo;
+mod json;
+mod last_known;
+mod limit;
+mod logger;
+mod monitor;
+#[cfg(feature = "mqtt")]
+mod mqtt;
+#[cfg(all(windows, feature = "msr"))]
+mod msr;
+#[cfg(all(windows, feature = "native"))]
+mod native;
+mod ordering;
+mod perf_counter;
+mod probe;
+mod profile;
+mod provider;
+mod provider_kind;
+mod raw;
+mod reader;
+mod reliability;
+mod sampler;
+mod sensor;
+mod sensors;
+mod session;
+#[cfg(all(windows, feature = "shared-memory"))]
+mod shared_memory;
+mod smoothing;
+mod snapshot;
+mod stats;
+mod temperature;
+mod thermal_state;
+mod throttle;
+mod timestamp;
+mod validity;
+#[cfg(feature = "toast")]
+mod toast;
+mod voltage;
+mod zone;
+
+#[cfg(all(windows, feature = "shared-memory"))]
+pub use aida64::Aida64Provider;
+pub use aggregate::Aggregate;
+pub use alert_monitor::{Alert, AlertMonitor, Crossing};
+pub use battery::BatteryTemperature;
+pub use cache::CachedReader;
+pub use calibration::{CalibratedProvider, Calibration};
+pub use chain::{ProviderChain, DEFAULT_CHAIN};
+pub use clock::{ClockedTemperature, CpuClock};
+pub use columns::ZoneColumns;
+pub use confidence::{Confidence, ConfidentReading};
+#[cfg(feature = "config")]
+pub use config::{Config, EventLogSinkConfig, MqttSinkConfig, SinkConfig, DEFAULT_INTERVAL};
+#[cfg(all(windows, feature = "shared-memory"))]
+pub use core_temp::CoreTempProvider;
+pub use csv::WmiCsvProvider;
+pub use detect::{ProviderStatus, Providers};
+pub use disk::{DiskTemperature, NvmeTemperature};
+pub use duration::parse_duration;
+pub use error::TemperatureError;
+#[cfg(feature = "event-log")]
+pub use event_log::EventLogSink;
+pub use fan::FanSpeed;
+pub use gpu::GpuTemperature;
+pub use hardware_monitor::{AmdGpuProvider, LibreHardwareMonitorProvider, OpenHardwareMonitorProvider};
+pub use history::{downsample, thermal_time_constant, TempHistory, TimedReading};
+#[cfg(feature = "history-sqlite")]
+pub use history_store::{HistoryStore, StoredReading};
+#[cfg(all(windows, feature = "shared-memory"))]
+pub use hwinfo::HwInfoProvider;
+pub use last_known::{LastKnownReader, LastKnownReading};
+pub use limit::{max_concurrent, set_max_concurrent, DEFAULT_MAX_CONCURRENT};
+pub use logger::CsvLogger;
+#[cfg(feature = "mqtt")]
+pub use mqtt::MqttPublisher;
+#[cfg(all(windows, feature = "msr"))]
+pub use msr::{CoreTemperature, MsrProvider};
+#[cfg(all(windows, feature = "native"))]
+pub use native::NativeWmiProvider;
+pub use ordering::{AsTemperature, ByTemperature};
+pub use perf_counter::{PerfCounterProvider, PerfCounterZone};
+pub use probe::TemperatureProbeProvider;
+pub use profile::ProfileDiff;
+pub use provider::{MockProvider, TemperatureProvider, WmiProvider};
+pub use provider_kind::{BoxedProvider, ProviderKind};
+pub use raw::RawReading;
+pub use reader::{TemperatureReader, TemperatureReaderBuilder};
+pub use reliability::ReliabilityReader;
+pub use sampler::{SamplerHandle, TemperatureSampler};
+pub use sensor::{SensorKind, SensorProvider, SensorReading};
+pub use sensors::{SensorInfo, Sensors};
+pub use session::TemperatureSession;
+pub use smoothing::{Smoother, Smoothing, WindowSmoother};
+pub use snapshot::SystemThermalSnapshot;
+pub use stats::{correlation, histogram, TemperatureStats, MAX_HISTOGRAM_BINS};
+pub use temperature::{Temperature, TemperatureUnit};
+pub use thermal_state::{StateTransition, ThermalState, ThermalStateMachine};
+pub use throttle::{ThrottleDetector, ThrottleStatus};
+pub use timestamp::format_rfc3339;
+#[cfg(feature = "toast")]
+pub use toast::ToastNotifier;
+pub use validity::{set_valid_range, valid_range, DEFAULT_VALID_RANGE};
+pub use voltage::VoltageProvider;
+pub use zone::ThermalZoneReading;
+
 /// Represents a CPU temperature reading with values in both Celsius and Fahrenheit.
 ///
 /// This struct contains the temperature values converted from the raw thermal zone
 /// sensor data obtained through Windows WMI queries.
 ///
-/// # Fields
+/// The temperature is stored once, as a [`Temperature`], so
+/// [`celsius`](CpuTemperature::celsius) and
+/// [`fahrenheit`](CpuTemperature::fahrenheit) always describe the same
+/// value. Build readings with [`CpuTemperature::from_celsius`] or from a
+/// [`Temperature`].
 ///
-/// * `celsius` - Temperature in degrees Celsius
-/// * `fahrenheit` - Temperature in degrees Fahrenheit
+/// Readings compare by their Celsius value, so `temp > threshold` works;
+/// [`ByTemperature`] finds the hottest or coldest of many.
 ///
 /// # Example
 ///
@@ -63,28 +254,37 @@ use regex::Regex;
 /// use tunjukin_suhu_cpu_windows::CpuTemperature;
 ///
 /// let temp = CpuTemperature::get()?;
-/// println!("Temperature: {:.1}°C ({:.1}°F)", temp.celsius, temp.fahrenheit);
+/// println!("Temperature: {:.1}°C ({:.1}°F)", temp.celsius(), temp.fahrenheit());
 /// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(into = "temperature::ReadingFields", from = "temperature::ReadingFields"))]
 pub struct CpuTemperature {
-    /// Temperature in degrees Celsius
-    pub celsius: f64,
-    /// Temperature in degrees Fahrenheit  
-    pub fahrenheit: f64,
+    temperature: Temperature,
 }
 
 impl CpuTemperature {
     /// Retrieves the current CPU temperature from Windows thermal zone sensors.
     ///
-    /// This method executes a PowerShell WMI query to get temperature data from
+    /// This method executes a PowerShell CIM query to get temperature data from
     /// `MSAcpi_ThermalZoneTemperature` and returns the first available temperature
     /// reading converted to both Celsius and Fahrenheit.
     ///
+    /// With the `native` feature, WMI is queried in-process through COM
+    /// instead (see [`NativeWmiProvider`]), falling back to PowerShell if COM is
+    /// unavailable and the `powershell-fallback` feature is enabled.
+    ///
+    /// If the machine has no usable ACPI thermal zones, the
+    /// [`Win32_TemperatureProbe`](TemperatureProbeProvider) class and the
+    /// sensors published by a running
+    /// [LibreHardwareMonitor](LibreHardwareMonitorProvider) or
+    /// [OpenHardwareMonitor](OpenHardwareMonitorProvider) are tried next. When
+    /// they yield nothing either, the ACPI error is returned.
+    ///
     /// # Returns
     ///
     /// * `Ok(CpuTemperature)` - Successfully retrieved temperature
-    /// * `Err(String)` - Error message describing what went wrong
+    /// * `Err(TemperatureError)` - What went wrong
     ///
     /// # Errors
     ///
@@ -102,8 +302,8 @@ impl CpuTemperature {
     /// match CpuTemperature::get() {
     ///     Ok(temp) => {
     ///         println!("Current CPU temperature:");
-    ///         println!("  Celsius: {:.2}°C", temp.celsius);
-    ///         println!("  Fahrenheit: {:.2}°F", temp.fahrenheit);
+    ///         println!("  Celsius: {:.2}°C", temp.celsius());
+    ///         println!("  Fahrenheit: {:.2}°F", temp.fahrenheit());
     ///     }
     ///     Err(error) => {
     ///         eprintln!("Failed to read temperature: {}", error);
@@ -114,39 +314,510 @@ impl CpuTemperature {
     /// # Platform Requirements
     ///
     /// - Windows operating system
-    /// - PowerShell available in system PATH
+    /// - Windows PowerShell or PowerShell 7 (`pwsh`) available in system PATH
     /// - May require administrator privileges on some systems
-    pub fn get() -> Result<Self, String> {
+    pub fn get() -> Result<Self, TemperatureError> {
+        Self::get_traced().map(|(reading, _)| reading)
+    }
+
+    /// Like [`CpuTemperature::get`], but stamped with the time the reading
+    /// was taken.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`CpuTemperature::get`].
+    pub fn get_timed() -> Result<TimedReading, TemperatureError> {
+        Self::get().map(TimedReading::now)
+    }
+
+    /// Like [`CpuTemperature::get`], but also returns the fallback backend
+    /// that produced the reading, or `None` if it came from the ACPI thermal
+    /// zones.
+    pub(crate) fn get_traced() -> Result<(Self, Option<ProviderKind>), TemperatureError> {
+        #[cfg(all(windows, feature = "native"))]
+        let primary = NativeWmiProvider.read_one();
+
+        #[cfg(not(all(windows, feature = "native")))]
+        let primary = Self::read_with(Self::run_wmi_query);
+
+        fallback::recover_traced(primary, |provider| provider.read_one())
+    }
+
+    /// Retrieves the CPU temperature, retrying when WMI reports it is busy.
+    ///
+    /// Under heavy concurrent polling WMI can reject a query with a transient
+    /// "server too busy" error (`WBEM_E_SERVER_TOO_BUSY`) or a COM
+    /// "retry later" error (`RPC_E_SERVERCALL_RETRYLATER`). Those failures are
+    /// retried up to `max_attempts` times in total, sleeping `delay` between
+    /// attempts. Any other failure, such as a missing namespace or absent
+    /// sensors, is permanent and returned immediately.
+    ///
+    /// Every attempt reads like [`CpuTemperature::get`], through COM with the
+    /// `native` feature and falling back to other backends when there are no
+    /// usable ACPI zones.
+    ///
+    /// A `max_attempts` of `0` is treated as `1`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// let temp = CpuTemperature::get_with_retries(3, Duration::from_millis(200))?;
+    /// println!("CPU Temperature: {:.1}°C", temp.celsius());
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    pub fn get_with_retries(max_attempts: u32, delay: Duration) -> Result<Self, TemperatureError> {
+        Self::retry_with(max_attempts, delay, Self::get)
+    }
+
+    /// Runs a caller-supplied WQL query and reads `property` from every result.
+    ///
+    /// This is an escape hatch for hardware and WMI classes the crate does not
+    /// special-case. The query is executed with `Get-CimInstance -Query` in the
+    /// default `root/cimv2` namespace; use [`CpuTemperature::get_with_wql_in`]
+    /// to target another namespace.
+    ///
+    /// The caller is responsible for choosing a query and property that
+    /// actually describe a temperature. Every value of `property` is
+    /// interpreted as tenths of a Kelvin (decikelvin), like
+    /// `MSAcpi_ThermalZoneTemperature.CurrentTemperature`, and no sanity range
+    /// is applied. Convert the results yourself if your class reports another
+    /// unit.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `property` is not a plain property name, if the query cannot
+    /// be executed, or if no numeric `property` values are found.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// let probes = CpuTemperature::get_with_wql(
+    ///     "SELECT CurrentReading FROM Win32_TemperatureProbe",
+    ///     "CurrentReading",
+    /// )?;
+    /// println!("Found {} probe readings", probes.len());
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    pub fn get_with_wql(wql: &str, property: &str) -> Result<Vec<Self>, TemperatureError> {
+        Self::get_with_wql_in("root/cimv2", wql, property)
+    }
+
+    /// Like [`CpuTemperature::get_with_wql`], but runs the query in `namespace`
+    /// (for example `root/wmi`).
+    pub fn get_with_wql_in(namespace: &str, wql: &str, property: &str) -> Result<Vec<Self>, TemperatureError> {
+        if property.is_empty() || !property.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(TemperatureError::InvalidArgument(format!("Invalid WMI property name: '{}'", property)));
+        }
+
+        let cmd = format!(
+            "Get-CimInstance -Namespace {} -Query {} | Format-List -Property {}",
+            powershell_quote(namespace),
+            powershell_quote(wql),
+            property
+        );
+        let output = Self::run_powershell(&cmd)?;
+
+        let values = Self::parse_property_values(&output, property)?;
+        if values.is_empty() {
+            return Err(TemperatureError::ParseError(format!("no numeric '{}' values found", property)));
+        }
+
+        Ok(values.into_iter().map(Self::from_decikelvin).collect())
+    }
+
+    /// Reads every thermal zone, keeping a slot for zones that fail to parse.
+    ///
+    /// The returned vector has exactly one entry per thermal zone record
+    /// returned by WMI, in WMI's enumeration order. A zone whose
+    /// `CurrentTemperature` is missing, unparsable or implausible yields
+    /// `None` instead of being dropped, so index `i` always refers to the same
+    /// physical zone as long as the set of zones does not change. This suits
+    /// displays with a fixed set of zone slots.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the WMI query fails or returns no thermal zone records at all.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// for (slot, zone) in CpuTemperature::get_all_optional()?.iter().enumerate() {
+    ///     match zone {
+    ///         Some(temp) => println!("Zone {}: {:.1}°C", slot, temp.celsius()),
+    ///         None => println!("Zone {}: unreadable", slot),
+    ///     }
+    /// }
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    pub fn get_all_optional() -> Result<Vec<Option<Self>>, TemperatureError> {
+        let output = Self::run_wmi_query()?;
+        let slots = Self::parse_zone_slots(&output);
+
+        if slots.is_empty() {
+            return Err(TemperatureError::NoSensors);
+        }
+
+        Ok(slots)
+    }
+
+    /// Returns the hottest thermal zone together with its instance name.
+    ///
+    /// The name is the zone's WMI `InstanceName` (for example
+    /// `ACPI\ThermalZone\TZ00_0`), or `None` if WMI did not report one. When
+    /// several zones share the highest temperature, the first one in WMI order
+    /// is returned.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the WMI query fails or no zone has a valid reading.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// let (zone, temp) = CpuTemperature::hottest_named()?;
+    /// println!("Hottest: {} at {:.1}°C", zone.as_deref().unwrap_or("unknown zone"), temp.celsius());
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    pub fn hottest_named() -> Result<(Option<String>, Self), TemperatureError> {
         let output = Self::run_wmi_query()?;
+        hottest(Self::parse_zones(&output)).ok_or_else(|| Self::no_readings_error(&output))
+    }
+
+    /// Returns how often the firmware actually refreshes the thermal zones.
+    ///
+    /// `MSAcpi_ThermalZoneTemperature` reports a `SamplingPeriod` in tenths
+    /// of a second. Polling faster than that only returns stale duplicates, so
+    /// monitors can use this value as a lower bound for their interval. When
+    /// zones report different periods, the shortest one is returned so no
+    /// zone's updates are missed.
+    ///
+    /// Returns `None` if the query fails or no zone reports a non-zero
+    /// `SamplingPeriod`; a period of zero means the zone is not polled by
+    /// firmware.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// let interval = CpuTemperature::recommended_poll_interval()
+    ///     .unwrap_or(Duration::from_secs(1));
+    /// ```
+    pub fn recommended_poll_interval() -> Option<Duration> {
+        let output = Self::run_wmi_query().ok()?;
+        parse_sampling_period(&output)
+    }
+
+    /// Expresses every thermal zone as an offset from a reference zone.
+    ///
+    /// Returns `(instance_name, delta)` pairs where `delta` is the zone's
+    /// Celsius value minus the reference zone's Celsius value; the reference
+    /// itself is included with a delta of `0.0`. This is useful when absolute
+    /// values are uncalibrated but the relative differences between zones are
+    /// meaningful.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the WMI query fails or if no zone has the `InstanceName`
+    /// `reference_instance`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// for (zone, delta) in CpuTemperature::normalized_to_reference(r"ACPI\ThermalZone\TZ00_0")? {
+    ///     println!("{}: {:+.1}°C", zone, delta);
+    /// }
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    pub fn normalized_to_reference(reference_instance: &str) -> Result<Vec<(String, f64)>, TemperatureError> {
+        let output = Self::run_wmi_query()?;
+        normalize_zones(&Self::parse_named_zones(&output), reference_instance)
+    }
+
+    /// Runs the query through `runner` and parses the first valid reading.
+    fn read_with(runner: impl FnOnce() -> Result<String, TemperatureError>) -> Result<Self, TemperatureError> {
+        let output = runner()?;
         let celsius = Self::parse_temperature(&output)?;
-        let fahrenheit = celsius * 9.0 / 5.0 + 32.0;
-        
-        Ok(CpuTemperature {
-            celsius,
-            fahrenheit,
-        })
+
+        Ok(Self::from_celsius(celsius))
+    }
+
+    /// Repeats `read` while it fails with a transient error.
+    fn retry_with<T>(max_attempts: u32, delay: Duration, mut read: impl FnMut() -> Result<T, TemperatureError>) -> Result<T, TemperatureError> {
+        let mut attempt = 1;
+        loop {
+            match read() {
+                Err(e) if attempt < max_attempts && e.is_transient() => {
+                    attempt += 1;
+                    thread::sleep(delay);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Creates a reading from a Celsius value, deriving the Fahrenheit value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// let temp = CpuTemperature::from_celsius(25.0);
+    /// assert_eq!(temp.fahrenheit(), 77.0);
+    /// ```
+    pub fn from_celsius(celsius: f64) -> Self {
+        CpuTemperature { temperature: Temperature::from_celsius(celsius) }
+    }
+
+    /// Returns the temperature in degrees Celsius.
+    pub fn celsius(&self) -> f64 {
+        self.temperature.as_celsius()
+    }
+
+    /// Returns the temperature in degrees Fahrenheit.
+    pub fn fahrenheit(&self) -> f64 {
+        self.temperature.as_fahrenheit()
+    }
+
+    /// Returns the Celsius value as an `f32`.
+    ///
+    /// Useful for GPU uniforms and large in-memory histories, where storing
+    /// `f32` halves the memory of `f64`. An `f32` keeps about seven
+    /// significant digits, so CPU temperatures retain a precision of roughly
+    /// 0.00001 °C, far finer than any thermal sensor resolves. The conversion
+    /// is lossy, so prefer the `f64` fields for further arithmetic.
+    pub fn celsius_f32(&self) -> f32 {
+        self.celsius() as f32
+    }
+
+    /// Creates a reading from an `f32` Celsius value, deriving the Fahrenheit
+    /// value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// let stored = CpuTemperature::from_celsius(38.85).celsius_f32();
+    /// let restored = CpuTemperature::from_celsius_f32(stored);
+    /// assert!((restored.celsius() - 38.85).abs() < 1e-4);
+    /// ```
+    pub fn from_celsius_f32(celsius: f32) -> Self {
+        Self::from_celsius(f64::from(celsius))
+    }
+
+    /// Returns `true` if the Celsius value lies within `[low, high]`,
+    /// boundaries included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// let temp = CpuTemperature::from_celsius(70.0);
+    /// assert!(temp.in_band(40.0, 70.0));
+    /// assert!(!temp.in_band(40.0, 69.9));
+    /// ```
+    pub fn in_band(&self, low: f64, high: f64) -> bool {
+        self.band_position(low, high) == Ordering::Equal
+    }
+
+    /// Tells whether the Celsius value is below, within or above
+    /// `[low, high]`.
+    ///
+    /// Returns [`Ordering::Less`] below `low`, [`Ordering::Greater`] above
+    /// `high`, and [`Ordering::Equal`] within the band, boundaries included.
+    /// This maps directly onto a green/yellow/red style display.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// let color = match CpuTemperature::from_celsius(92.0).band_position(40.0, 80.0) {
+    ///     Ordering::Less => "blue",
+    ///     Ordering::Equal => "green",
+    ///     Ordering::Greater => "red",
+    /// };
+    /// assert_eq!(color, "red");
+    /// ```
+    pub fn band_position(&self, low: f64, high: f64) -> Ordering {
+        if self.celsius() < low {
+            Ordering::Less
+        } else if self.celsius() > high {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    /// Returns the temperature in Kelvin.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// let temp = CpuTemperature::from_celsius(25.0);
+    /// assert_eq!(temp.kelvin(), 298.15);
+    /// ```
+    pub fn kelvin(&self) -> f64 {
+        self.celsius() + 273.15
+    }
+
+    /// Creates a reading from a value in Kelvin, deriving the Celsius and
+    /// Fahrenheit values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// let temp = CpuTemperature::from_kelvin(373.15);
+    /// assert!((temp.celsius() - 100.0).abs() < 1e-9);
+    /// ```
+    pub fn from_kelvin(kelvin: f64) -> Self {
+        Self::from_celsius(kelvin - 273.15)
+    }
+
+    /// Creates a reading from a value in tenths of a Kelvin, the unit used by
+    /// `MSAcpi_ThermalZoneTemperature`.
+    fn from_decikelvin(decikelvin: f64) -> Self {
+        Self::from_kelvin(decikelvin / 10.0)
     }
 
     /// Executes the PowerShell WMI query to retrieve thermal zone temperature data.
     ///
     /// This internal method runs the WMI query using PowerShell and returns the
     /// raw output for further processing.
-    fn run_wmi_query() -> Result<String, String> {
-        let cmd = r#"Get-WmiObject MSAcpi_ThermalZoneTemperature -Namespace 'root/wmi' | Format-List"#;
-
-        let output = Command::new("powershell")
-            .args(["-NoProfile", "-Command", cmd])
-            .output()
-            .map_err(|e| format!("Failed to execute PowerShell: {}. Ensure PowerShell is installed and accessible.", e))?;
-
-        if !output.status.success() {
-            return Err(format!(
-                "WMI query failed with exit code: {}. You may need to run as administrator.",
-                output.status
-            ));
+    fn run_wmi_query() -> Result<String, TemperatureError> {
+        Self::run_powershell(ACPI_QUERY)
+    }
+
+    /// Executes `cmd` with PowerShell and returns its standard output.
+    ///
+    /// Windows PowerShell is tried first, then PowerShell 7 (`pwsh`) if
+    /// Windows PowerShell cannot be started.
+    fn run_powershell(cmd: &str) -> Result<String, TemperatureError> {
+        with_powershell(|shell| Self::run_shell(shell, cmd))
+    }
+
+    /// Executes `cmd` with the given PowerShell executable and returns its
+    /// standard output.
+    fn run_shell(shell: &str, cmd: &str) -> Result<String, TemperatureError> {
+        let _permit = limit::acquire();
+        let output = shell_command(shell, cmd).output().map_err(spawn_error)?;
+
+        check_output(output)
+    }
+
+    /// Like [`Self::run_powershell`], but kills PowerShell and fails if it
+    /// is still running at `deadline`.
+    fn run_powershell_until(cmd: &str, deadline: Instant) -> Result<String, TemperatureError> {
+        with_powershell(|shell| Self::run_shell_until(shell, cmd, deadline))
+    }
+
+    /// Runs `cmd` like [`Self::run_powershell_until`] if there is a
+    /// `deadline`, or like [`Self::run_powershell`] otherwise.
+    fn run_powershell_within(cmd: &str, deadline: Option<Instant>) -> Result<String, TemperatureError> {
+        match deadline {
+            Some(deadline) => Self::run_powershell_until(cmd, deadline),
+            None => Self::run_powershell(cmd),
         }
+    }
+
+    /// Like [`Self::run_shell`], but kills the shell and fails if it is
+    /// still running at `deadline`.
+    fn run_shell_until(shell: &str, cmd: &str, deadline: Instant) -> Result<String, TemperatureError> {
+        let _permit = limit::acquire();
+        let mut child = shell_command(shell, cmd)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(spawn_error)?;
+
+        // Drain both pipes on their own threads so a chatty child cannot
+        // block on a full pipe while we poll for its exit.
+        let stdout = child.stdout.take().map(drain);
+        let stderr = child.stderr.take().map(drain);
+
+        let status = loop {
+            if let Some(status) = child.try_wait().map_err(|e| TemperatureError::Other(format!("Failed to wait for PowerShell: {}", e)))? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(TemperatureError::Timeout);
+            }
+            thread::sleep(Duration::from_millis(5));
+        };
+
+        let collect = |pipe: Option<thread::JoinHandle<Vec<u8>>>| pipe.and_then(|handle| handle.join().ok()).unwrap_or_default();
+        check_output(Output {
+            status,
+            stdout: collect(stdout),
+            stderr: collect(stderr),
+        })
+    }
+
+    /// Extracts every numeric value of `property` from Format-List output.
+    fn parse_property_values(output: &str, property: &str) -> Result<Vec<f64>, TemperatureError> {
+        let pattern = format!(r"(?m)^\s*{}\s*:\s*(\d+(?:\.\d+)?)\s*$", regex::escape(property));
+        let re = Regex::new(&pattern).map_err(|e| TemperatureError::ParseError(format!("regex compilation failed: {}", e)))?;
+
+        Ok(re
+            .captures_iter(output)
+            .filter_map(|cap| cap.get(1)?.as_str().parse::<f64>().ok())
+            .collect())
+    }
 
-        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    /// Parses one slot per zone record, with `None` for unusable readings.
+    fn parse_zone_slots(output: &str) -> Vec<Option<Self>> {
+        parse_format_list(output)
+            .iter()
+            .map(|record| {
+                let raw: f64 = record_value(record, "CurrentTemperature")?.parse().ok()?;
+                Some(Self::from_decikelvin(raw)).filter(|reading| is_plausible(reading.celsius()))
+            })
+            .collect()
+    }
+
+    /// Parses every zone with a plausible `CurrentTemperature`, in WMI order,
+    /// together with its `InstanceName` if one was reported.
+    fn parse_zones(output: &str) -> Vec<(Option<String>, Self)> {
+        parse_format_list(output)
+            .iter()
+            .filter_map(|record| {
+                let raw: f64 = record_value(record, "CurrentTemperature")?.parse().ok()?;
+                let name = record_value(record, "InstanceName").map(str::to_string);
+                Some((name, Self::from_decikelvin(raw)))
+            })
+            .filter(|(_, reading)| is_plausible(reading.celsius()))
+            .collect()
+    }
+
+    /// Like [`Self::parse_zones`], but only keeps zones with an
+    /// `InstanceName`.
+    fn parse_named_zones(output: &str) -> Vec<(String, Self)> {
+        Self::parse_zones(output)
+            .into_iter()
+            .filter_map(|(name, reading)| Some((name?, reading)))
+            .collect()
     }
 
     /// Parses the PowerShell WMI output to extract temperature values.
@@ -154,9 +825,9 @@ impl CpuTemperature {
     /// This method processes the Format-List output from the WMI query and
     /// extracts the first available CurrentTemperature value, converting it
     /// from the raw format (0.1 Kelvin units) to Celsius.
-    fn parse_temperature(output: &str) -> Result<f64, String> {
+    fn parse_temperature(output: &str) -> Result<f64, TemperatureError> {
         let re_temp = Regex::new(r"(?m)^\s*CurrentTemperature\s*:\s*(\d+)")
-            .map_err(|e| format!("Regex compilation failed: {}", e))?;
+            .map_err(|e| TemperatureError::ParseError(format!("regex compilation failed: {}", e)))?;
 
         // Look for temperature values in the output
         for cap in re_temp.captures_iter(output) {
@@ -166,34 +837,254 @@ impl CpuTemperature {
                     let celsius = (raw_value / 10.0) - 273.15;
                     
                     // Sanity check: temperature should be reasonable for CPU
-                    if celsius > -50.0 && celsius < 150.0 {
+                    if is_plausible(celsius) {
                         return Ok(celsius);
                     }
                 }
             }
         }
 
+        Err(Self::no_readings_error(output))
+    }
+
+    /// Describes why `output` yielded no valid reading.
+    fn no_readings_error(output: &str) -> TemperatureError {
         if output.trim().is_empty() {
-            Err("No temperature data received from WMI query. Check if thermal sensors are available.".to_string())
-        } else {
-            Err("No valid temperature readings found in WMI output. The thermal zone sensors may not be accessible.".to_string())
+            return TemperatureError::NoSensors;
+        }
+
+        let records = parse_format_list(output);
+        let rejected: Vec<(Option<&str>, f64)> = records
+            .iter()
+            .filter_map(|record| {
+                let raw: f64 = record_value(record, "CurrentTemperature")?.parse().ok()?;
+                Some((record_value(record, "InstanceName"), Self::from_decikelvin(raw).celsius()))
+            })
+            .collect();
+        match rejected.is_empty() {
+            true => TemperatureError::NoValidReadings,
+            false => validity::out_of_range(rejected),
         }
     }
 }
 
+impl fmt::Debug for CpuTemperature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CpuTemperature").field("celsius", &self.celsius()).field("fahrenheit", &self.fahrenheit()).finish()
+    }
+}
+
+/// Formats the reading as `38.9 °C (102.0 °F)`.
+///
+/// The precision of the format string applies to both values and defaults to
+/// one decimal.
+///
+/// # Example
+///
+/// ```
+/// use tunjukin_suhu_cpu_windows::CpuTemperature;
+///
+/// let temp = CpuTemperature::from_celsius(38.85);
+/// assert_eq!(temp.to_string(), "38.9 °C (101.9 °F)");
+/// assert_eq!(format!("{:.0}", temp), "39 °C (102 °F)");
+/// ```
+impl fmt::Display for CpuTemperature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(1);
+        write!(f, "{:.*} °C ({:.*} °F)", precision, self.celsius(), precision, self.fahrenheit())
+    }
+}
+
+/// The PowerShell command used to query the ACPI thermal zones.
+const ACPI_QUERY: &str = r#"Get-CimInstance -Namespace 'root/wmi' -ClassName MSAcpi_ThermalZoneTemperature | Format-List"#;
+
+/// The PowerShell executables to try, in order: Windows PowerShell, which
+/// ships with Windows, and PowerShell 7, which may be the only one installed.
+const POWERSHELL_EXECUTABLES: &[&str] = &["powershell", "pwsh"];
+
+/// Calls `run` with each PowerShell executable until one can be started.
+///
+/// If none can be started, the error for the first executable is returned.
+fn with_powershell<T>(mut run: impl FnMut(&str) -> Result<T, TemperatureError>) -> Result<T, TemperatureError> {
+    let mut first_error = None;
+
+    for shell in POWERSHELL_EXECUTABLES {
+        match run(shell) {
+            Err(e @ TemperatureError::PowerShellNotFound { .. }) => {
+                first_error.get_or_insert(e);
+            }
+            result => return result,
+        }
+    }
+
+    Err(first_error.expect("at least one PowerShell executable is configured"))
+}
+
+/// Builds the command that runs `cmd` with the given PowerShell executable.
+///
+/// On Windows the child is created without a console window, so GUI
+/// applications do not flash one on every query.
+fn shell_command(shell: &str, cmd: &str) -> Command {
+    let mut command = Command::new(shell);
+    command.args(["-NoProfile", "-Command", cmd]);
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    command
+}
+
+/// Describes a failure to start PowerShell.
+fn spawn_error(e: std::io::Error) -> TemperatureError {
+    TemperatureError::PowerShellNotFound { message: e.to_string() }
+}
+
+/// Returns the standard output of a finished PowerShell process, or an error
+/// if it exited unsuccessfully.
+fn check_output(output: Output) -> Result<String, TemperatureError> {
+    if !output.status.success() {
+        return Err(TemperatureError::query_failed(output.status.code(), &String::from_utf8_lossy(&output.stderr)));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Reads `pipe` to the end on a background thread.
+fn drain(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = pipe.read_to_end(&mut buffer);
+        buffer
+    })
+}
+
+/// Returns `true` if `celsius` lies within the [valid range](set_valid_range),
+/// or the range of the reader reading on this thread.
+fn is_plausible(celsius: f64) -> bool {
+    validity::accepts(celsius)
+}
+
+/// Splits PowerShell `Format-List` output into records of `(name, value)`
+/// pairs.
+///
+/// Records are separated by blank lines. Lines without a `:` separator, such
+/// as wrapped continuation lines, are ignored.
+fn parse_format_list(output: &str) -> Vec<Vec<(String, String)>> {
+    let mut records = Vec::new();
+    let mut current = Vec::new();
+
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                records.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            let name = name.trim();
+            if !name.is_empty() && !name.contains(char::is_whitespace) {
+                current.push((name.to_string(), value.trim().to_string()));
+            }
+        }
+    }
+
+    if !current.is_empty() {
+        records.push(current);
+    }
+
+    records
+}
+
+/// Looks up the value of `name` in a record produced by [`parse_format_list`].
+fn record_value<'a>(record: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    record
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+        .filter(|value| !value.is_empty())
+}
+
+/// Parses `Format-List` output into one value per record that `parse`
+/// accepts, failing with [`TemperatureError::NoSensors`] if the output holds
+/// no record and with [`TemperatureError::NoValidReadings`] if `parse`
+/// rejects them all.
+fn parse_records<T>(output: &str, parse: impl FnMut(&[(String, String)]) -> Option<T>) -> Result<Vec<T>, TemperatureError> {
+    let records = parse_format_list(output);
+    if records.is_empty() {
+        return Err(TemperatureError::NoSensors);
+    }
+
+    let values: Vec<T> = records.iter().map(Vec::as_slice).filter_map(parse).collect();
+    if values.is_empty() {
+        return Err(TemperatureError::NoValidReadings);
+    }
+
+    Ok(values)
+}
+
+/// Finds the shortest non-zero `SamplingPeriod` (in tenths of a second).
+fn parse_sampling_period(output: &str) -> Option<Duration> {
+    parse_format_list(output)
+        .iter()
+        .filter_map(|record| record_value(record, "SamplingPeriod")?.parse::<u64>().ok())
+        .filter(|&tenths| tenths > 0)
+        .min()
+        .map(|tenths| Duration::from_millis(tenths * 100))
+}
+
+/// Picks the hottest zone, preferring the earliest one on ties.
+fn hottest<N>(zones: Vec<(N, CpuTemperature)>) -> Option<(N, CpuTemperature)> {
+    zones.into_iter().fold(None, |hottest, zone| match hottest {
+        Some(current) if current.1.celsius() >= zone.1.celsius() => Some(current),
+        _ => Some(zone),
+    })
+}
+
+/// Computes each zone's Celsius offset from the zone named `reference`.
+fn normalize_zones(zones: &[(String, CpuTemperature)], reference: &str) -> Result<Vec<(String, f64)>, TemperatureError> {
+    let (_, baseline) = zones.iter().find(|(name, _)| name == reference).ok_or_else(|| {
+        let available: Vec<&str> = zones.iter().map(|(name, _)| name.as_str()).collect();
+        TemperatureError::InvalidArgument(format!(
+            "Reference thermal zone '{}' not found. Available zones: {}",
+            reference,
+            if available.is_empty() { "none".to_string() } else { available.join(", ") }
+        ))
+    })?;
+
+    Ok(zones
+        .iter()
+        .map(|(name, reading)| (name.clone(), reading.celsius() - baseline.celsius()))
+        .collect())
+}
+
+/// Quotes `value` as a single-quoted PowerShell string literal.
+fn powershell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_temperature_conversion() {
-        let temp = CpuTemperature {
-            celsius: 25.0,
-            fahrenheit: 77.0,
-        };
-        
-        assert_eq!(temp.celsius, 25.0);
-        assert_eq!(temp.fahrenheit, 77.0);
+        let temp = CpuTemperature::from_celsius(25.0);
+
+        assert_eq!(temp.celsius(), 25.0);
+        assert_eq!(temp.fahrenheit(), 77.0);
+    }
+
+    #[test]
+    fn test_display() {
+        let temp = CpuTemperature::from_celsius(-5.0);
+        assert_eq!(temp.to_string(), "-5.0 °C (23.0 °F)");
+        assert_eq!(format!("{:.2}", temp), "-5.00 °C (23.00 °F)");
     }
 
     #[test]
@@ -223,4 +1114,281 @@ InstanceName         : ACPI\ThermalZone\TZ00_0
         let result = CpuTemperature::parse_temperature(sample_output);
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_temperature_out_of_range() {
+        let sample_output = "CurrentTemperature : 2132\nInstanceName       : ACPI\\ThermalZone\\TZ00_0\n";
+        let Err(TemperatureError::OutOfRange { details }) = CpuTemperature::parse_temperature(sample_output) else {
+            panic!("expected an out-of-range error");
+        };
+        assert!(details.starts_with("ACPI\\ThermalZone\\TZ00_0: -59.95 °C"));
+    }
+
+    const SAMPLE_OUTPUT: &str = "CurrentTemperature   : 3120\n";
+    const BUSY_STDERR: &str = "Get-WmiObject : Server too busy (Exception from HRESULT: 0x80041045)";
+
+    fn busy_error() -> TemperatureError {
+        TemperatureError::query_failed(Some(1), BUSY_STDERR)
+    }
+
+    #[test]
+    fn test_retry_recovers_from_busy_provider() {
+        let mut calls = 0;
+        let result = CpuTemperature::retry_with(3, Duration::ZERO, || {
+            calls += 1;
+            if calls < 3 {
+                Err(busy_error())
+            } else {
+                CpuTemperature::read_with(|| Ok(SAMPLE_OUTPUT.to_string()))
+            }
+        });
+
+        assert!((result.unwrap().celsius() - 38.85).abs() < 0.01);
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn test_retry_gives_up_after_max_attempts() {
+        let mut calls = 0;
+        let result = CpuTemperature::retry_with(2, Duration::ZERO, || {
+            calls += 1;
+            Err::<CpuTemperature, _>(busy_error())
+        });
+
+        assert!(result.is_err());
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn test_retry_does_not_repeat_permanent_errors() {
+        let mut calls = 0;
+        let result = CpuTemperature::retry_with(5, Duration::ZERO, || {
+            calls += 1;
+            Err::<CpuTemperature, _>(TemperatureError::query_failed(Some(1), "Get-WmiObject : Invalid namespace (0x8004100E)"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_retry_repeats_busy_com_errors() {
+        let mut calls = 0;
+        let result = CpuTemperature::retry_with(3, Duration::ZERO, || {
+            calls += 1;
+            match calls {
+                1 => Err(TemperatureError::Com { code: 0x8001_010a_u32 as i32, message: "The message filter indicated that the application is busy.".to_string() }),
+                _ => Ok(CpuTemperature::from_celsius(45.0)),
+            }
+        });
+
+        assert_eq!(result, Ok(CpuTemperature::from_celsius(45.0)));
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn test_falls_back_to_pwsh_only_when_powershell_is_missing() {
+        let not_found = |shell: &str| TemperatureError::PowerShellNotFound { message: format!("{} not found", shell) };
+
+        let mut tried = Vec::new();
+        let result = with_powershell(|shell| {
+            tried.push(shell.to_string());
+            if shell == "pwsh" { Ok("3132".to_string()) } else { Err(not_found(shell)) }
+        });
+        assert_eq!(result, Ok("3132".to_string()));
+        assert_eq!(tried, vec!["powershell", "pwsh"]);
+
+        let mut calls = 0;
+        let result: Result<String, _> = with_powershell(|_| {
+            calls += 1;
+            Err(busy_error())
+        });
+        assert_eq!(result, Err(busy_error()));
+        assert_eq!(calls, 1);
+
+        assert_eq!(with_powershell::<String>(|shell| Err(not_found(shell))), Err(not_found("powershell")));
+    }
+
+    #[test]
+    fn test_parse_property_values() {
+        let sample_output = r#"
+CurrentReading : 3132
+
+CurrentReading : 3001.5
+
+CurrentReading :
+        "#;
+
+        let values = CpuTemperature::parse_property_values(sample_output, "CurrentReading").unwrap();
+        assert_eq!(values, vec![3132.0, 3001.5]);
+    }
+
+    #[test]
+    fn test_get_with_wql_rejects_bad_property() {
+        let result = CpuTemperature::get_with_wql("SELECT * FROM Win32_TemperatureProbe", "Name; Remove-Item");
+        assert!(matches!(result, Err(TemperatureError::InvalidArgument(message)) if message.contains("Invalid WMI property name")));
+    }
+
+    #[test]
+    fn test_powershell_quote_escapes_single_quotes() {
+        assert_eq!(powershell_quote("SELECT * FROM X WHERE Name='A'"), "'SELECT * FROM X WHERE Name=''A'''");
+    }
+
+    #[test]
+    fn test_parse_format_list_records() {
+        let sample_output = r#"
+Name        : \_TZ.TZ00
+Temperature : 301
+
+Name        : \_TZ.TZ01
+Temperature :
+        "#;
+
+        let records = parse_format_list(sample_output);
+        assert_eq!(records.len(), 2);
+        assert_eq!(record_value(&records[0], "Name"), Some("\\_TZ.TZ00"));
+        assert_eq!(record_value(&records[0], "Temperature"), Some("301"));
+        assert_eq!(record_value(&records[1], "Temperature"), None);
+        assert_eq!(record_value(&records[1], "Missing"), None);
+    }
+
+    #[test]
+    fn test_parse_records() {
+        let temperature = |record: &[(String, String)]| record_value(record, "Temperature")?.parse::<u32>().ok();
+
+        assert_eq!(parse_records("Name : TZ00\nTemperature : 301\n\nName : TZ01\nTemperature :\n", temperature), Ok(vec![301]));
+        assert_eq!(parse_records("Name : TZ01\nTemperature :\n", temperature), Err(TemperatureError::NoValidReadings));
+        assert_eq!(parse_records("", temperature), Err(TemperatureError::NoSensors));
+    }
+
+    const TWO_ZONE_OUTPUT: &str = r#"
+Active             : True
+CurrentTemperature : 3132
+InstanceName       : ACPI\ThermalZone\TZ00_0
+
+Active             : True
+CurrentTemperature : 3182
+InstanceName       : ACPI\ThermalZone\TZ01_0
+"#;
+
+    #[test]
+    fn test_parse_named_zones() {
+        let zones = CpuTemperature::parse_named_zones(TWO_ZONE_OUTPUT);
+
+        assert_eq!(zones.len(), 2);
+        assert_eq!(zones[0].0, r"ACPI\ThermalZone\TZ00_0");
+        assert!((zones[1].1.celsius() - 45.05).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_normalize_zones_to_reference() {
+        let zones = CpuTemperature::parse_named_zones(TWO_ZONE_OUTPUT);
+        let normalized = normalize_zones(&zones, r"ACPI\ThermalZone\TZ01_0").unwrap();
+
+        assert_eq!(normalized[0].0, r"ACPI\ThermalZone\TZ00_0");
+        assert!((normalized[0].1 + 5.0).abs() < 0.01);
+        assert_eq!(normalized[1].1, 0.0);
+    }
+
+    #[test]
+    fn test_normalize_zones_missing_reference() {
+        let zones = CpuTemperature::parse_named_zones(TWO_ZONE_OUTPUT);
+        let error = normalize_zones(&zones, "TZ99").unwrap_err().to_string();
+
+        assert!(error.contains("'TZ99' not found"));
+        assert!(error.contains(r"ACPI\ThermalZone\TZ00_0"));
+    }
+
+    #[test]
+    fn test_band_boundaries_are_inclusive() {
+        let low = CpuTemperature::from_celsius(40.0);
+        let high = CpuTemperature::from_celsius(80.0);
+
+        assert!(low.in_band(40.0, 80.0));
+        assert!(high.in_band(40.0, 80.0));
+        assert_eq!(low.band_position(40.0, 80.0), Ordering::Equal);
+        assert_eq!(high.band_position(40.0, 80.0), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_band_position_outside() {
+        assert_eq!(CpuTemperature::from_celsius(39.9).band_position(40.0, 80.0), Ordering::Less);
+        assert_eq!(CpuTemperature::from_celsius(80.1).band_position(40.0, 80.0), Ordering::Greater);
+        assert!(!CpuTemperature::from_celsius(80.1).in_band(40.0, 80.0));
+    }
+
+    #[test]
+    fn test_parse_zone_slots_keeps_positions() {
+        let sample_output = r#"
+CurrentTemperature : 3132
+InstanceName       : ACPI\ThermalZone\TZ00_0
+
+CurrentTemperature : 0
+InstanceName       : ACPI\ThermalZone\TZ01_0
+
+InstanceName       : ACPI\ThermalZone\TZ02_0
+
+CurrentTemperature : 3182
+InstanceName       : ACPI\ThermalZone\TZ03_0
+"#;
+
+        let slots = CpuTemperature::parse_zone_slots(sample_output);
+        assert_eq!(slots.len(), 4);
+        assert!((slots[0].as_ref().unwrap().celsius() - 40.05).abs() < 0.01);
+        assert_eq!(slots[1], None);
+        assert_eq!(slots[2], None);
+        assert!((slots[3].as_ref().unwrap().celsius() - 45.05).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_hottest_zone_with_name() {
+        let sample_output = r#"
+CurrentTemperature : 3132
+InstanceName       : ACPI\ThermalZone\TZ00_0
+
+CurrentTemperature : 3232
+
+CurrentTemperature : 3232
+InstanceName       : ACPI\ThermalZone\TZ02_0
+"#;
+
+        let (name, reading) = hottest(CpuTemperature::parse_zones(sample_output)).unwrap();
+        assert_eq!(name, None);
+        assert!((reading.celsius() - 50.05).abs() < 0.01);
+
+        assert!(hottest(CpuTemperature::parse_zones("")).is_none());
+    }
+
+    #[test]
+    fn test_f32_round_trip_precision() {
+        for celsius in [-20.0, 0.0, 25.0, 38.85, 72.4, 99.99, 110.0] {
+            let restored = CpuTemperature::from_celsius_f32(CpuTemperature::from_celsius(celsius).celsius_f32());
+
+            assert!((restored.celsius() - celsius).abs() < 1e-4);
+            assert!((restored.fahrenheit() - (celsius * 9.0 / 5.0 + 32.0)).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_parse_sampling_period() {
+        let sample_output = "CurrentTemperature : 3132\nSamplingPeriod     : 50\n\nCurrentTemperature : 3182\nSamplingPeriod     : 15\n";
+        assert_eq!(parse_sampling_period(sample_output), Some(Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn test_parse_sampling_period_absent_or_zero() {
+        assert_eq!(parse_sampling_period("CurrentTemperature : 3132\n"), None);
+        assert_eq!(parse_sampling_period("SamplingPeriod : 0\n"), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let temp = CpuTemperature::from_celsius(25.0);
+        let json = serde_json::to_string(&temp).unwrap();
+
+        assert_eq!(json, r#"{"celsius":25.0,"fahrenheit":77.0}"#);
+        assert_eq!(serde_json::from_str::<CpuTemperature>(&json).unwrap(), temp);
+        assert_eq!(serde_json::from_str::<CpuTemperature>(r#"{"celsius":25.0,"fahrenheit":0.0}"#).unwrap(), temp);
+    }
+}
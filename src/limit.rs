@@ -0,0 +1,154 @@
+//! Process-wide limit on concurrent WMI queries.
+//!
+//! Every WMI query, whether it spawns PowerShell or goes through COM, holds a
+//! permit from a global semaphore for as long as it runs. On a shared host
+//! this keeps bursty, multi-threaded callers from overwhelming the WMI
+//! service.
+
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+/// The number of simultaneous queries allowed until
+/// [`set_max_concurrent`] is called.
+pub const DEFAULT_MAX_CONCURRENT: usize = 16;
+
+static LIMITER: Semaphore = Semaphore::new(DEFAULT_MAX_CONCURRENT);
+
+/// Sets how many WMI queries this crate may run at the same time across the
+/// whole process.
+///
+/// Additional queries block until a running one finishes. The default of
+/// [`DEFAULT_MAX_CONCURRENT`] is generous; lower it on shared hosts where
+/// many threads poll temperatures at once, or raise it if queries queue up
+/// while WMI is otherwise idle. Raising the limit immediately releases
+/// waiting queries. A limit of `0` is treated as `1`.
+///
+/// # Example
+///
+/// ```
+/// use tunjukin_suhu_cpu_windows::{max_concurrent, set_max_concurrent};
+///
+/// set_max_concurrent(4);
+/// assert_eq!(max_concurrent(), 4);
+/// ```
+pub fn set_max_concurrent(limit: usize) {
+    LIMITER.set_limit(limit);
+}
+
+/// Returns the current process-wide limit on concurrent WMI queries.
+pub fn max_concurrent() -> usize {
+    LIMITER.limit()
+}
+
+/// Blocks until a global query permit is available.
+pub(crate) fn acquire() -> Permit<'static> {
+    LIMITER.acquire()
+}
+
+/// A counting semaphore whose limit can change at runtime.
+struct Semaphore {
+    state: Mutex<State>,
+    released: Condvar,
+}
+
+struct State {
+    in_use: usize,
+    limit: usize,
+}
+
+/// A held permit, released when dropped.
+pub(crate) struct Permit<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl Semaphore {
+    const fn new(limit: usize) -> Self {
+        Semaphore {
+            state: Mutex::new(State { in_use: 0, limit }),
+            released: Condvar::new(),
+        }
+    }
+
+    fn acquire(&self) -> Permit<'_> {
+        let mut state = self.lock();
+        while state.in_use >= state.limit {
+            state = self.released.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+        state.in_use += 1;
+
+        Permit { semaphore: self }
+    }
+
+    fn set_limit(&self, limit: usize) {
+        self.lock().limit = limit.max(1);
+        self.released.notify_all();
+    }
+
+    fn limit(&self) -> usize {
+        self.lock().limit
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        self.semaphore.lock().in_use -= 1;
+        self.semaphore.released.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_semaphore_caps_concurrency() {
+        let semaphore = Semaphore::new(2);
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    let _permit = semaphore.acquire();
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(10));
+                    active.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_raising_limit_releases_waiters() {
+        let semaphore = Semaphore::new(1);
+        let held = semaphore.acquire();
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| drop(semaphore.acquire()));
+            thread::sleep(Duration::from_millis(20));
+            assert!(!waiter.is_finished());
+
+            semaphore.set_limit(2);
+            waiter.join().unwrap();
+        });
+
+        drop(held);
+        assert_eq!(semaphore.lock().in_use, 0);
+    }
+
+    #[test]
+    fn test_zero_limit_is_treated_as_one() {
+        let semaphore = Semaphore::new(4);
+        semaphore.set_limit(0);
+        assert_eq!(semaphore.limit(), 1);
+    }
+}
@@ -0,0 +1,286 @@
+//! Long-running CSV logs of readings, split into rotating files.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::timestamp::{file_stamp, format_rfc3339};
+use crate::{CpuTemperature, SamplerHandle, ThermalZoneReading};
+
+/// The header row written at the top of every log file.
+const HEADER: &str = "timestamp,zone,celsius,fahrenheit";
+
+/// Appends readings as CSV rows to files in a directory, starting a new file
+/// when the current one grows too large or too old.
+///
+/// Rows are `timestamp,zone,celsius,fahrenheit`, with an RFC 3339 UTC
+/// timestamp (see [`format_rfc3339`](crate::format_rfc3339)) and an empty zone
+/// for readings that are not tied to a named thermal zone. Each file starts
+/// with that header row and is named after the moment it was opened, such as
+/// `temperature-20251014T083000Z.csv`, so the files sort chronologically.
+///
+/// By default a new file is started every 24 hours. Rows are flushed as they
+/// are written, so a crash loses at most the row being written.
+///
+/// # Example
+///
+/// Logging every sample for days, one file per day or 10 MB:
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use tunjukin_suhu_cpu_windows::{CsvLogger, TemperatureSampler};
+///
+/// let sampler = TemperatureSampler::new(Duration::from_secs(5)).spawn();
+///
+/// CsvLogger::new(r"C:\Logs\cpu")
+///     .max_file_size(10 * 1024 * 1024)
+///     .attach(&sampler);
+/// ```
+#[derive(Debug)]
+pub struct CsvLogger {
+    directory: PathBuf,
+    prefix: String,
+    max_file_size: Option<u64>,
+    rotate_every: Option<Duration>,
+    current: Option<LogFile>,
+}
+
+/// The file rows are currently appended to.
+#[derive(Debug)]
+struct LogFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    opened_at: SystemTime,
+    size: u64,
+}
+
+impl CsvLogger {
+    /// Creates a logger writing into `directory`, which is created on the
+    /// first write if it does not exist.
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        CsvLogger {
+            directory: directory.into(),
+            prefix: "temperature".to_string(),
+            max_file_size: None,
+            rotate_every: Some(Duration::from_secs(24 * 60 * 60)),
+            current: None,
+        }
+    }
+
+    /// Names files `<prefix>-<timestamp>.csv`. The default prefix is
+    /// `temperature`.
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Starts a new file before a row would make the current one larger than
+    /// `bytes`. A file always holds at least one row.
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Starts a new file once the current one has been open for `interval`,
+    /// or never with `None`. The default is 24 hours.
+    pub fn rotate_every(mut self, interval: Option<Duration>) -> Self {
+        self.rotate_every = interval;
+        self
+    }
+
+    /// Returns the file rows are currently written to, if one is open.
+    pub fn current_path(&self) -> Option<&Path> {
+        self.current.as_ref().map(|file| file.path.as_path())
+    }
+
+    /// Appends a row for `reading` without a zone, stamped with the current
+    /// time.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the directory or a log file cannot be created or written.
+    pub fn log(&mut self, reading: &CpuTemperature) -> io::Result<()> {
+        self.write_row(SystemTime::now(), None, reading)
+    }
+
+    /// Appends a row for every zone in `zones`, all stamped with the current
+    /// time.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the directory or a log file cannot be created or written.
+    pub fn log_zones(&mut self, zones: &[ThermalZoneReading]) -> io::Result<()> {
+        let now = SystemTime::now();
+        for zone in zones {
+            self.write_row(now, zone.instance_name.as_deref(), &zone.temperature)?;
+        }
+        Ok(())
+    }
+
+    /// Logs every successful sample from `sampler` on the sampler's thread.
+    ///
+    /// Failed reads are not logged, and neither are rows that cannot be
+    /// written, for example while the disk is full; logging resumes with the
+    /// next sample that can be written.
+    pub fn attach(mut self, sampler: &SamplerHandle) {
+        sampler.subscribe_with(move |sample| {
+            if let Ok(reading) = sample {
+                let _ = self.log(reading);
+            }
+        });
+    }
+
+    fn write_row(&mut self, at: SystemTime, zone: Option<&str>, reading: &CpuTemperature) -> io::Result<()> {
+        let row = format!(
+            "{},{},{:.2},{:.2}\n",
+            format_rfc3339(at),
+            csv_field(zone.unwrap_or_default()),
+            reading.celsius(),
+            reading.fahrenheit()
+        );
+
+        if self.needs_rotation(at, row.len() as u64) {
+            self.current = None;
+        }
+        let file = match self.current.as_mut() {
+            Some(file) => file,
+            None => self.current.insert(self.open(at)?),
+        };
+
+        let written = file.writer.write_all(row.as_bytes()).and_then(|()| file.writer.flush());
+        if written.is_err() {
+            // Start over in a new file rather than continue a damaged one.
+            self.current = None;
+            return written;
+        }
+        file.size += row.len() as u64;
+        Ok(())
+    }
+
+    fn needs_rotation(&self, now: SystemTime, row_len: u64) -> bool {
+        let Some(file) = &self.current else {
+            return false;
+        };
+
+        let too_large = self
+            .max_file_size
+            .is_some_and(|max| file.size > HEADER.len() as u64 + 1 && file.size + row_len > max);
+        let too_old = self
+            .rotate_every
+            .is_some_and(|interval| now.duration_since(file.opened_at).unwrap_or_default() >= interval);
+
+        too_large || too_old
+    }
+
+    /// Creates a new log file with the header row, adding a counter to the
+    /// name if a file for the same second already exists.
+    fn open(&self, at: SystemTime) -> io::Result<LogFile> {
+        fs::create_dir_all(&self.directory)?;
+
+        let stamp = file_stamp(at);
+        let mut attempt = 0;
+        loop {
+            let name = match attempt {
+                0 => format!("{}-{}.csv", self.prefix, stamp),
+                n => format!("{}-{}-{}.csv", self.prefix, stamp, n),
+            };
+            let path = self.directory.join(name);
+
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => {
+                    let mut writer = BufWriter::new(file);
+                    writeln!(writer, "{}", HEADER)?;
+                    writer.flush()?;
+                    return Ok(LogFile { path, writer, opened_at: at, size: HEADER.len() as u64 + 1 });
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => attempt += 1,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Quotes `value` if it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tunjukin_suhu_test_{}_{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn files(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_rows_and_header() {
+        let dir = scratch_dir("csv_rows");
+        let mut logger = CsvLogger::new(&dir);
+
+        logger.log(&CpuTemperature::from_celsius(40.0)).unwrap();
+        logger
+            .log_zones(&[ThermalZoneReading::new(Some("Core #1, hot".to_string()), CpuTemperature::from_celsius(50.0))])
+            .unwrap();
+
+        let contents = fs::read_to_string(logger.current_path().unwrap()).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines[0], "timestamp,zone,celsius,fahrenheit");
+        assert!(lines[1].ends_with("Z,,40.00,104.00"));
+        assert!(lines[2].ends_with("Z,\"Core #1, hot\",50.00,122.00"));
+
+        let name = &files(&dir)[0];
+        assert!(name.starts_with("temperature-") && name.ends_with("Z.csv"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_size_rotation() {
+        let dir = scratch_dir("csv_size");
+        // The header and one row fit, a second row does not.
+        let mut logger = CsvLogger::new(&dir).prefix("laptop").max_file_size(80);
+
+        for _ in 0..3 {
+            logger.log(&CpuTemperature::from_celsius(40.0)).unwrap();
+        }
+
+        let names = files(&dir);
+        assert_eq!(names.len(), 3);
+        assert!(names.iter().all(|name| name.starts_with("laptop-")));
+        for name in &names {
+            assert_eq!(fs::read_to_string(dir.join(name)).unwrap().lines().count(), 2);
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_time_rotation() {
+        let dir = scratch_dir("csv_time");
+        let mut logger = CsvLogger::new(&dir).rotate_every(Some(Duration::from_secs(60)));
+        let start = SystemTime::now();
+        let reading = CpuTemperature::from_celsius(40.0);
+
+        logger.write_row(start, None, &reading).unwrap();
+        logger.write_row(start + Duration::from_secs(59), None, &reading).unwrap();
+        logger.write_row(start + Duration::from_secs(60), None, &reading).unwrap();
+
+        assert_eq!(files(&dir).len(), 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,90 @@
+//! Background polling that delivers readings over a channel.
+
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+use crate::{CpuTemperature, TemperatureError, TemperatureProvider, WmiProvider};
+
+impl CpuTemperature {
+    /// Polls the CPU temperature on a background thread and delivers every
+    /// result through a channel.
+    ///
+    /// A reading is taken immediately and then every `interval`. Failed reads
+    /// are delivered as `Err` values and polling continues. Dropping the
+    /// returned [`Receiver`] stops the background thread the next time it
+    /// tries to deliver a result.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// let readings = CpuTemperature::monitor_to_channel(Duration::from_secs(1));
+    /// for result in readings.iter().take(10) {
+    ///     match result {
+    ///         Ok(temp) => println!("{:.1}°C", temp.celsius()),
+    ///         Err(e) => eprintln!("read failed: {}", e),
+    ///     }
+    /// }
+    /// ```
+    pub fn monitor_to_channel(interval: Duration) -> Receiver<Result<CpuTemperature, TemperatureError>> {
+        monitor_provider_to_channel(WmiProvider, interval)
+    }
+}
+
+/// Polls `provider` every `interval` on a background thread until the receiver
+/// is dropped.
+fn monitor_provider_to_channel<P>(provider: P, interval: Duration) -> Receiver<Result<CpuTemperature, TemperatureError>>
+where
+    P: TemperatureProvider + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || loop {
+        if sender.send(provider.read_one()).is_err() {
+            break;
+        }
+        thread::sleep(interval);
+    });
+
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockProvider;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_channel_delivers_readings_and_errors() {
+        let provider = MockProvider::sequence(vec![
+            Ok(CpuTemperature::from_celsius(40.0)),
+            Err("busy".into()),
+            Ok(CpuTemperature::from_celsius(42.0)),
+        ]);
+        let receiver = monitor_provider_to_channel(provider, Duration::from_millis(1));
+
+        assert_eq!(receiver.recv().unwrap().unwrap().celsius(), 40.0);
+        assert_eq!(receiver.recv().unwrap(), Err("busy".into()));
+        assert_eq!(receiver.recv().unwrap().unwrap().celsius(), 42.0);
+    }
+
+    #[test]
+    fn test_dropping_receiver_stops_producer() {
+        let provider = Arc::new(MockProvider::fixed(CpuTemperature::from_celsius(40.0)));
+        let receiver = monitor_provider_to_channel(Arc::clone(&provider), Duration::from_millis(1));
+
+        assert!(receiver.recv().unwrap().is_ok());
+        drop(receiver);
+
+        // Give the producer time to notice the disconnect, then make sure it
+        // has stopped reading.
+        thread::sleep(Duration::from_millis(50));
+        let calls = provider.call_count();
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(provider.call_count(), calls);
+    }
+}
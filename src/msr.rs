@@ -0,0 +1,223 @@
+//! Per-core temperatures from the Digital Thermal Sensor MSRs.
+//!
+//! Intel CPUs report each core's temperature in `IA32_THERM_STATUS`
+//! (MSR `0x19C`) as degrees below `TjMax`, the temperature at which the core
+//! throttles, which `MSR_TEMPERATURE_TARGET` (MSR `0x1A2`) holds. Reading an
+//! MSR needs kernel mode, so this backend goes through the WinRing0 helper
+//! driver, loaded from `WinRing0x64.dll` and `WinRing0x64.sys`, which the
+//! application has to ship next to its executable.
+//!
+//! Only available on Windows with the `msr` feature.
+
+use std::ffi::OsString;
+use std::mem;
+
+use windows::core::{s, HSTRING};
+use windows::Win32::Foundation::{FreeLibrary, HMODULE};
+use windows::Win32::System::LibraryLoader::{GetProcAddress, LoadLibraryW};
+
+use crate::{CpuTemperature, TemperatureError, TemperatureProvider, ThermalZoneReading};
+
+/// The MSR holding a core's Digital Thermal Sensor readout.
+const IA32_THERM_STATUS: u32 = 0x19C;
+/// The MSR holding the core's `TjMax`.
+const MSR_TEMPERATURE_TARGET: u32 = 0x1A2;
+/// The bit of `IA32_THERM_STATUS` set when the readout is valid.
+const READING_VALID: u32 = 1 << 31;
+
+/// The largest number of logical processors a thread affinity mask covers.
+const MAX_PROCESSORS: usize = usize::BITS as usize;
+
+type Export = unsafe extern "system" fn() -> isize;
+type InitializeOls = unsafe extern "system" fn() -> i32;
+type DeinitializeOls = unsafe extern "system" fn();
+type RdmsrTx = unsafe extern "system" fn(index: u32, eax: *mut u32, edx: *mut u32, affinity: usize) -> i32;
+
+/// The temperature of one logical processor.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CoreTemperature {
+    /// The index of the logical processor, from 0.
+    pub processor: usize,
+    /// The temperature of the core it runs on.
+    pub temperature: CpuTemperature,
+    /// The core's `TjMax` in degrees Celsius.
+    pub tj_max: f64,
+}
+
+/// Reads per-core temperatures from the Digital Thermal Sensor MSRs through
+/// the WinRing0 driver.
+///
+/// ACPI thermal zones report one value for the whole machine, often updated
+/// slowly; the Digital Thermal Sensors report every core. This needs:
+///
+/// - an Intel CPU, since AMD CPUs report temperatures through other
+///   registers;
+/// - `WinRing0x64.dll` and `WinRing0x64.sys` next to the executable, or the
+///   DLL at the path given to [`library`](MsrProvider::library);
+/// - administrator rights, to load the driver;
+/// - a system that allows the driver: Windows' vulnerable driver blocklist
+///   rejects WinRing0, so Memory Integrity must be off.
+///
+/// The driver is loaded for each read and released afterwards. As a
+/// [`TemperatureProvider`], each logical processor becomes a reading named
+/// `CPU 0`, `CPU 1` and so on.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::MsrProvider;
+///
+/// for core in MsrProvider::new().read_cores()? {
+///     println!("CPU {}: {:.0}°C", core.processor, core.temperature.celsius());
+/// }
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MsrProvider {
+    library: OsString,
+}
+
+impl MsrProvider {
+    /// Creates a provider loading `WinRing0x64.dll` from the DLL search path.
+    pub fn new() -> Self {
+        MsrProvider { library: "WinRing0x64.dll".into() }
+    }
+
+    /// Loads the WinRing0 DLL from `path` instead.
+    pub fn library(mut self, path: impl Into<OsString>) -> Self {
+        self.library = path.into();
+        self
+    }
+
+    /// Reads the temperature of every logical processor.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the DLL cannot be loaded, the driver cannot be started, or
+    /// no processor returns a valid readout.
+    pub fn read_cores(&self) -> Result<Vec<CoreTemperature>, TemperatureError> {
+        let ols = Ols::load(&self.library)?;
+        let processors = std::thread::available_parallelism().map_or(1, |count| count.get()).min(MAX_PROCESSORS);
+
+        let cores: Vec<CoreTemperature> = (0..processors)
+            .filter_map(|processor| {
+                let status = ols.read(IA32_THERM_STATUS, processor)?;
+                let target = ols.read(MSR_TEMPERATURE_TARGET, processor)?;
+                decode(processor, status, target)
+            })
+            .collect();
+        if cores.is_empty() {
+            return Err(TemperatureError::NoValidReadings);
+        }
+
+        Ok(cores)
+    }
+}
+
+impl Default for MsrProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TemperatureProvider for MsrProvider {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        Ok(self
+            .read_cores()?
+            .into_iter()
+            .map(|core| ThermalZoneReading::new(Some(format!("CPU {}", core.processor)), core.temperature))
+            .collect())
+    }
+}
+
+/// Decodes the low halves of `IA32_THERM_STATUS` and
+/// `MSR_TEMPERATURE_TARGET`, or returns `None` if the readout is invalid.
+fn decode(processor: usize, status: u32, target: u32) -> Option<CoreTemperature> {
+    if status & READING_VALID == 0 {
+        return None;
+    }
+    let tj_max = f64::from((target >> 16) & 0xFF);
+    if tj_max == 0.0 {
+        return None;
+    }
+    let below_tj_max = f64::from((status >> 16) & 0x7F);
+
+    Some(CoreTemperature { processor, temperature: CpuTemperature::from_celsius(tj_max - below_tj_max), tj_max })
+}
+
+/// The loaded WinRing0 DLL, with its driver initialized.
+struct Ols {
+    module: HMODULE,
+    deinitialize: DeinitializeOls,
+    rdmsr_tx: RdmsrTx,
+}
+
+impl Ols {
+    fn load(library: &OsString) -> Result<Self, TemperatureError> {
+        // SAFETY: the DLL's exports are transmuted to the signatures
+        // documented in WinRing0's OlsApi.h.
+        unsafe {
+            let module = LoadLibraryW(&HSTRING::from(library.as_os_str()))
+                .map_err(|e| TemperatureError::Other(format!("Failed to load {}: {}", library.to_string_lossy(), e)))?;
+            let exports = (
+                GetProcAddress(module, s!("InitializeOls")),
+                GetProcAddress(module, s!("DeinitializeOls")),
+                GetProcAddress(module, s!("RdmsrTx")),
+            );
+            let (Some(initialize), Some(deinitialize), Some(rdmsr_tx)) = exports else {
+                let _ = FreeLibrary(module);
+                return Err(TemperatureError::Other(format!("{} is not a WinRing0 DLL", library.to_string_lossy())));
+            };
+            let initialize = mem::transmute::<Export, InitializeOls>(initialize);
+
+            if initialize() == 0 {
+                let _ = FreeLibrary(module);
+                return Err(TemperatureError::AccessDenied {
+                    details: "The WinRing0 driver could not be started; it needs administrator rights and is blocked while Memory Integrity is on"
+                        .to_string(),
+                });
+            }
+
+            Ok(Ols {
+                module,
+                deinitialize: mem::transmute::<Export, DeinitializeOls>(deinitialize),
+                rdmsr_tx: mem::transmute::<Export, RdmsrTx>(rdmsr_tx),
+            })
+        }
+    }
+
+    /// Reads the low half of MSR `index` on logical processor `processor`.
+    fn read(&self, index: u32, processor: usize) -> Option<u32> {
+        let (mut eax, mut edx) = (0, 0);
+        // SAFETY: both pointers are valid for the call, and the driver was
+        // initialized in `load`.
+        let ok = unsafe { (self.rdmsr_tx)(index, &mut eax, &mut edx, 1 << processor) };
+        (ok != 0).then_some(eax)
+    }
+}
+
+impl Drop for Ols {
+    fn drop(&mut self) {
+        // SAFETY: the driver was initialized in `load` and is released once.
+        unsafe {
+            (self.deinitialize)();
+            let _ = FreeLibrary(self.module);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        // Valid, 38 °C below a TjMax of 100 °C.
+        let core = decode(3, READING_VALID | (38 << 16), 100 << 16).unwrap();
+        assert_eq!(core, CoreTemperature { processor: 3, temperature: CpuTemperature::from_celsius(62.0), tj_max: 100.0 });
+
+        assert_eq!(decode(0, 38 << 16, 100 << 16), None);
+        assert_eq!(decode(0, READING_VALID | (38 << 16), 0), None);
+    }
+}
@@ -0,0 +1,267 @@
+//! Native WMI backend that talks to WMI through COM.
+//!
+//! Spawning `powershell.exe` costs several hundred milliseconds per query and
+//! fails outright on machines where PowerShell is blocked by policy. This
+//! backend asks WMI for the same `MSAcpi_ThermalZoneTemperature` instances
+//! through `IWbemLocator` and `IWbemServices` in-process instead.
+//!
+//! Only available on Windows with the `native` feature.
+
+use std::time::Instant;
+
+use windows::core::{BSTR, HRESULT, VARIANT, w};
+use windows::Win32::Foundation::{E_ACCESSDENIED, RPC_E_CHANGED_MODE};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoSetProxyBlanket, CoUninitialize, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+    COINIT_MULTITHREADED, EOAC_NONE, RPC_C_AUTHN_LEVEL_CALL, RPC_C_IMP_LEVEL_IMPERSONATE,
+};
+use windows::Win32::System::Rpc::{RPC_C_AUTHN_WINNT, RPC_C_AUTHZ_NONE};
+use windows::Win32::System::Wmi::{
+    IWbemClassObject, IWbemLocator, WbemLocator, WBEM_E_ACCESS_DENIED, WBEM_FLAG_FORWARD_ONLY, WBEM_FLAG_RETURN_IMMEDIATELY, WBEM_INFINITE,
+    WBEM_S_TIMEDOUT,
+};
+
+use crate::{is_plausible, limit, validity, CpuTemperature, TemperatureError, TemperatureProvider, ThermalZoneReading};
+
+/// The WQL query for the ACPI thermal zones.
+const ACPI_WQL: &str = "SELECT InstanceName, CurrentTemperature FROM MSAcpi_ThermalZoneTemperature";
+
+/// Reads the ACPI thermal zones through COM, without spawning PowerShell.
+///
+/// With the `powershell-fallback` feature (enabled by default), a failure to
+/// reach WMI through COM falls back to the PowerShell query, so enabling the
+/// native backend never makes reads fail where they used to work. This
+/// includes COM being unusable on the calling thread; see below. Without the
+/// feature, COM failures are returned as errors and PowerShell is never
+/// started.
+///
+/// # COM apartments
+///
+/// Each read initializes COM on the calling thread for its own use. If the
+/// thread was already initialized in a different threading model by another
+/// library (`CoInitializeEx` returning `RPC_E_CHANGED_MODE`), the backend
+/// retries with the single-threaded apartment model, which joins the
+/// existing apartment instead of fighting over it. Only if that fails too
+/// does it fall back to PowerShell.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::{NativeWmiProvider, TemperatureProvider};
+///
+/// let temp = NativeWmiProvider.read_one()?;
+/// println!("{:.1}°C", temp.celsius());
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NativeWmiProvider;
+
+impl TemperatureProvider for NativeWmiProvider {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        Ok(self
+            .read_zones()?
+            .into_iter()
+            .map(|(instance_name, temperature)| ThermalZoneReading::new(instance_name, temperature))
+            .collect())
+    }
+
+    fn read_one(&self) -> Result<CpuTemperature, TemperatureError> {
+        Ok(first(self.read_zones()?))
+    }
+
+    fn read_until(&self, deadline: Instant) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        Ok(self
+            .read_zones_until(Some(deadline))?
+            .into_iter()
+            .map(|(instance_name, temperature)| ThermalZoneReading::new(instance_name, temperature))
+            .collect())
+    }
+}
+
+impl NativeWmiProvider {
+    /// Reads every plausible zone, falling back to PowerShell if enabled.
+    pub(crate) fn read_zones(&self) -> Result<Vec<(Option<String>, CpuTemperature)>, TemperatureError> {
+        match query_zones(None) {
+            Ok(zones) => plausible_zones(zones),
+            #[cfg(feature = "powershell-fallback")]
+            Err(_) => {
+                let output = CpuTemperature::run_wmi_query()?;
+                let zones = CpuTemperature::parse_zones(&output);
+                if zones.is_empty() {
+                    return Err(CpuTemperature::no_readings_error(&output));
+                }
+                Ok(zones)
+            }
+            #[cfg(not(feature = "powershell-fallback"))]
+            Err(e) => Err(describe(&e)),
+        }
+    }
+
+    /// Reads every plausible zone, giving up on the query at `deadline`, if
+    /// any.
+    ///
+    /// Never falls back to PowerShell.
+    pub(crate) fn read_zones_until(&self, deadline: Option<Instant>) -> Result<Vec<(Option<String>, CpuTemperature)>, TemperatureError> {
+        plausible_zones(query_zones(deadline).map_err(|e| describe(&e))?)
+    }
+
+    /// Reads the first plausible zone, giving up on the query at `deadline`,
+    /// if any.
+    ///
+    /// Never falls back to PowerShell.
+    pub(crate) fn read_one_until(&self, deadline: Option<Instant>) -> Result<CpuTemperature, TemperatureError> {
+        Ok(first(self.read_zones_until(deadline)?))
+    }
+}
+
+/// Converts raw zones to readings, keeping only those that pass the sanity
+/// check and failing if none does.
+fn plausible_zones(zones: Vec<(Option<String>, u32)>) -> Result<Vec<(Option<String>, CpuTemperature)>, TemperatureError> {
+    if zones.is_empty() {
+        return Err(TemperatureError::NoSensors);
+    }
+
+    let (plausible, rejected): (Vec<_>, Vec<_>) = zones
+        .into_iter()
+        .map(|(name, raw)| (name, CpuTemperature::from_decikelvin(f64::from(raw))))
+        .partition(|(_, reading)| is_plausible(reading.celsius()));
+    if plausible.is_empty() {
+        return Err(validity::out_of_range(rejected.iter().map(|(name, reading)| (name.as_deref(), reading.celsius()))));
+    }
+
+    Ok(plausible)
+}
+
+/// Takes the first reading of a non-empty list of zones.
+fn first(zones: Vec<(Option<String>, CpuTemperature)>) -> CpuTemperature {
+    zones.into_iter().next().map(|(_, reading)| reading).expect("zone list is never empty")
+}
+
+/// Converts a COM failure into a [`TemperatureError`].
+fn describe(e: &windows::core::Error) -> TemperatureError {
+    match e.code() {
+        code if code == E_ACCESSDENIED || code == HRESULT(WBEM_E_ACCESS_DENIED.0) => TemperatureError::AccessDenied {
+            details: e.message(),
+        },
+        code if code == HRESULT(WBEM_S_TIMEDOUT.0) => TemperatureError::Timeout,
+        code => TemperatureError::Com {
+            code: code.0,
+            message: e.message(),
+        },
+    }
+}
+
+/// Queries every thermal zone's `InstanceName` and raw `CurrentTemperature`,
+/// in tenths of a Kelvin.
+fn query_zones(deadline: Option<Instant>) -> windows::core::Result<Vec<(Option<String>, u32)>> {
+    let _permit = limit::acquire();
+    let _com = ComGuard::init()?;
+
+    // SAFETY: COM is initialized on this thread for the guard's lifetime, and
+    // every interface pointer is released before the guard drops.
+    unsafe {
+        let locator: IWbemLocator = CoCreateInstance(&WbemLocator, None, CLSCTX_INPROC_SERVER)?;
+        let services = locator.ConnectServer(
+            &BSTR::from(r"root\WMI"),
+            &BSTR::new(),
+            &BSTR::new(),
+            &BSTR::new(),
+            0,
+            &BSTR::new(),
+            None,
+        )?;
+        CoSetProxyBlanket(
+            &services,
+            RPC_C_AUTHN_WINNT,
+            RPC_C_AUTHZ_NONE,
+            None,
+            RPC_C_AUTHN_LEVEL_CALL,
+            RPC_C_IMP_LEVEL_IMPERSONATE,
+            None,
+            EOAC_NONE,
+        )?;
+
+        let enumerator = services.ExecQuery(
+            &BSTR::from("WQL"),
+            &BSTR::from(ACPI_WQL),
+            WBEM_FLAG_FORWARD_ONLY | WBEM_FLAG_RETURN_IMMEDIATELY,
+            None,
+        )?;
+
+        let mut zones = Vec::new();
+        loop {
+            let timeout = match deadline {
+                Some(deadline) => {
+                    i32::try_from(deadline.saturating_duration_since(Instant::now()).as_millis()).unwrap_or(i32::MAX)
+                }
+                None => WBEM_INFINITE,
+            };
+
+            let mut row = [None];
+            let mut returned = 0;
+            let hr = enumerator.Next(timeout, &mut row, &mut returned);
+            if hr == HRESULT(WBEM_S_TIMEDOUT.0) {
+                return Err(windows::core::Error::new(hr, "WMI query timed out"));
+            }
+            hr.ok()?;
+
+            match row[0].take() {
+                Some(zone) if returned != 0 => zones.extend(read_zone(&zone)),
+                _ => break,
+            }
+        }
+
+        Ok(zones)
+    }
+}
+
+/// Extracts one zone's name and raw reading, or `None` if the reading is
+/// missing.
+///
+/// # Safety
+///
+/// COM must be initialized on the calling thread.
+unsafe fn read_zone(zone: &IWbemClassObject) -> Option<(Option<String>, u32)> {
+    let mut value = VARIANT::default();
+    zone.Get(w!("CurrentTemperature"), 0, &mut value, None, None).ok()?;
+    let raw = u32::try_from(&value).ok()?;
+
+    let mut name = VARIANT::default();
+    let name = zone
+        .Get(w!("InstanceName"), 0, &mut name, None, None)
+        .ok()
+        .and_then(|()| BSTR::try_from(&name).ok())
+        .map(|name| name.to_string())
+        .filter(|name| !name.is_empty());
+
+    Some((name, raw))
+}
+
+/// Keeps COM initialized on the current thread while alive.
+struct ComGuard;
+
+impl ComGuard {
+    /// Initializes COM, retrying with the single-threaded apartment model if
+    /// the thread already uses it.
+    fn init() -> windows::core::Result<Self> {
+        // SAFETY: Every successful call, including `S_FALSE` for an already
+        // initialized thread, is balanced by `CoUninitialize` in `drop`. A
+        // failed call, such as `RPC_E_CHANGED_MODE`, needs no balancing.
+        let hr = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) };
+        let hr = if hr == RPC_E_CHANGED_MODE {
+            unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED) }
+        } else {
+            hr
+        };
+        hr.ok()?;
+
+        Ok(ComGuard)
+    }
+}
+
+impl Drop for ComGuard {
+    fn drop(&mut self) {
+        // SAFETY: Balances the successful `CoInitializeEx` in `init`.
+        unsafe { CoUninitialize() };
+    }
+}
@@ -0,0 +1,106 @@
+//! Picking readings by temperature.
+//!
+//! `f64` is not [`Ord`], so `readings.iter().max()` does not compile for
+//! temperature readings. [`ByTemperature`] adds `max_by_temp` and
+//! `min_by_temp` to every iterator over readings instead.
+
+use std::cmp::Ordering;
+
+use crate::{CpuTemperature, Temperature, ThermalZoneReading};
+
+/// A value that carries a temperature it can be compared by.
+pub trait AsTemperature {
+    /// Returns the temperature of the value.
+    fn as_temperature(&self) -> Temperature;
+}
+
+impl AsTemperature for Temperature {
+    fn as_temperature(&self) -> Temperature {
+        *self
+    }
+}
+
+impl AsTemperature for CpuTemperature {
+    fn as_temperature(&self) -> Temperature {
+        self.temperature()
+    }
+}
+
+impl AsTemperature for ThermalZoneReading {
+    fn as_temperature(&self) -> Temperature {
+        self.temperature.temperature()
+    }
+}
+
+impl<T: AsTemperature + ?Sized> AsTemperature for &T {
+    fn as_temperature(&self) -> Temperature {
+        (**self).as_temperature()
+    }
+}
+
+/// Finds the hottest and coldest items of an iterator of readings.
+///
+/// Temperatures are compared with [`f64::total_cmp`], and ties go to the
+/// earliest item.
+///
+/// # Example
+///
+/// ```
+/// use tunjukin_suhu_cpu_windows::{ByTemperature, CpuTemperature};
+///
+/// let readings = [CpuTemperature::from_celsius(41.0), CpuTemperature::from_celsius(67.5), CpuTemperature::from_celsius(52.0)];
+/// assert_eq!(readings.iter().max_by_temp(), Some(&readings[1]));
+/// assert_eq!(readings.iter().min_by_temp(), Some(&readings[0]));
+/// assert!(readings[1] > readings[2]);
+/// ```
+pub trait ByTemperature: Iterator {
+    /// Returns the hottest item, or `None` if the iterator is empty.
+    fn max_by_temp(self) -> Option<Self::Item>;
+
+    /// Returns the coldest item, or `None` if the iterator is empty.
+    fn min_by_temp(self) -> Option<Self::Item>;
+}
+
+impl<I> ByTemperature for I
+where
+    I: Iterator,
+    I::Item: AsTemperature,
+{
+    fn max_by_temp(self) -> Option<Self::Item> {
+        self.reduce(|max, item| match item.as_temperature().as_celsius().total_cmp(&max.as_temperature().as_celsius()) {
+            Ordering::Greater => item,
+            _ => max,
+        })
+    }
+
+    fn min_by_temp(self) -> Option<Self::Item> {
+        self.reduce(|min, item| match item.as_temperature().as_celsius().total_cmp(&min.as_temperature().as_celsius()) {
+            Ordering::Less => item,
+            _ => min,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(name: &str, celsius: f64) -> ThermalZoneReading {
+        ThermalZoneReading::new(Some(name.to_string()), CpuTemperature::from_celsius(celsius))
+    }
+
+    #[test]
+    fn test_max_and_min_by_temp() {
+        let zones = vec![zone("TZ00", 48.0), zone("TZ01", 61.0), zone("TZ02", 61.0), zone("TZ03", 35.5)];
+
+        assert_eq!(zones.iter().max_by_temp().and_then(|zone| zone.instance_name.as_deref()), Some("TZ01"));
+        assert_eq!(zones.clone().into_iter().min_by_temp().and_then(|zone| zone.instance_name), Some("TZ03".to_string()));
+        assert_eq!(Vec::<Temperature>::new().into_iter().max_by_temp(), None);
+    }
+
+    #[test]
+    fn test_partial_ord() {
+        assert!(CpuTemperature::from_celsius(80.0) > CpuTemperature::from_celsius(79.9));
+        assert!(Temperature::from_celsius(-1.0) < Temperature::from_celsius(0.0));
+    }
+}
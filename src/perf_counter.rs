@@ -0,0 +1,161 @@
+//! Thermal zone performance counters backend.
+//!
+//! `Win32_PerfFormattedData_Counters_ThermalZoneInformation` is the WMI view of
+//! the "Thermal Zone Information" performance counters. On some systems it
+//! updates more often than `MSAcpi_ThermalZoneTemperature` and it does not
+//! live in the `root/wmi` namespace.
+//!
+//! **Units differ from the ACPI class.** `Temperature` is reported in whole
+//! Kelvin, not tenths of a Kelvin, while `HighPrecisionTemperature` is
+//! reported in tenths of a Kelvin. Reusing the ACPI decikelvin conversion on
+//! `Temperature` would yield readings around -243 °C.
+
+use std::time::Instant;
+
+use crate::{is_plausible, parse_format_list, record_value, CpuTemperature, TemperatureError, TemperatureProvider, ThermalZoneReading};
+
+/// The query listing every thermal zone counter instance.
+const PERF_COUNTER_QUERY: &str = "Get-CimInstance -ClassName Win32_PerfFormattedData_Counters_ThermalZoneInformation | Format-List Name,Temperature,HighPrecisionTemperature";
+
+/// One thermal zone as reported by the performance counters.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PerfCounterZone {
+    /// Counter instance name, such as `\_TZ.TZ00`
+    pub name: String,
+    /// The standard-resolution reading, converted from whole Kelvin
+    pub temperature: CpuTemperature,
+    /// The high-precision reading, converted from tenths of a Kelvin, when
+    /// the counter is available
+    pub high_precision: Option<CpuTemperature>,
+}
+
+impl PerfCounterZone {
+    /// Returns the high-precision reading if available, otherwise the
+    /// standard one.
+    pub fn best(&self) -> &CpuTemperature {
+        self.high_precision.as_ref().unwrap_or(&self.temperature)
+    }
+}
+
+/// Reads temperatures from the thermal zone performance counters.
+///
+/// As a [`TemperatureProvider`], it returns every plausible zone, preferring
+/// the high-precision value and using the counter instance name as the
+/// zone's name.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::PerfCounterProvider;
+///
+/// for zone in PerfCounterProvider.read_zones()? {
+///     println!("{}: {:.1}°C", zone.name, zone.best().celsius());
+/// }
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PerfCounterProvider;
+
+impl PerfCounterProvider {
+    /// Queries every thermal zone exposed by the performance counters.
+    pub fn read_zones(&self) -> Result<Vec<PerfCounterZone>, TemperatureError> {
+        zones_from(&CpuTemperature::run_powershell(PERF_COUNTER_QUERY)?)
+    }
+
+    /// Returns the first plausible high-precision reading, killing the query
+    /// if it is still running at `deadline`, if any.
+    pub(crate) fn read_precise_until(&self, deadline: Option<Instant>) -> Result<CpuTemperature, TemperatureError> {
+        zones_from(&CpuTemperature::run_powershell_within(PERF_COUNTER_QUERY, deadline)?)?
+            .into_iter()
+            .filter_map(|zone| zone.high_precision)
+            .find(|reading| is_plausible(reading.celsius()))
+            .ok_or(TemperatureError::NoValidReadings)
+    }
+}
+
+/// Parses the counter query output, failing if it lists no zones.
+fn zones_from(output: &str) -> Result<Vec<PerfCounterZone>, TemperatureError> {
+    let zones = parse_zones(output);
+    if zones.is_empty() {
+        return Err(TemperatureError::NoSensors);
+    }
+
+    Ok(zones)
+}
+
+impl TemperatureProvider for PerfCounterProvider {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        let zones: Vec<ThermalZoneReading> = self
+            .read_zones()?
+            .into_iter()
+            .filter(|zone| is_plausible(zone.best().celsius()))
+            .map(|zone| {
+                let temperature = zone.best().clone();
+                ThermalZoneReading::new(Some(zone.name), temperature)
+            })
+            .collect();
+
+        if zones.is_empty() {
+            return Err(TemperatureError::NoValidReadings);
+        }
+
+        Ok(zones)
+    }
+}
+
+/// Parses `Format-List` output of the thermal zone performance counter class.
+fn parse_zones(output: &str) -> Vec<PerfCounterZone> {
+    parse_format_list(output)
+        .iter()
+        .filter_map(|record| {
+            let kelvin: f64 = record_value(record, "Temperature")?.parse().ok()?;
+            let high_precision = record_value(record, "HighPrecisionTemperature")
+                .and_then(|value| value.parse::<f64>().ok())
+                .filter(|&decikelvin| decikelvin > 0.0)
+                .map(CpuTemperature::from_decikelvin);
+
+            Some(PerfCounterZone {
+                name: record_value(record, "Name").unwrap_or_default().to_string(),
+                temperature: CpuTemperature::from_kelvin(kelvin),
+                high_precision,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_zones_units() {
+        let sample_output = r#"
+Name                     : \_TZ.TZ00
+Temperature              : 312
+HighPrecisionTemperature : 3121
+
+Name                     : \_TZ.TZ01
+Temperature              : 300
+HighPrecisionTemperature : 0
+        "#;
+
+        let zones = parse_zones(sample_output);
+        assert_eq!(zones.len(), 2);
+
+        // Whole Kelvin: 312 K = 38.85 °C
+        assert_eq!(zones[0].name, r"\_TZ.TZ00");
+        assert!((zones[0].temperature.celsius() - 38.85).abs() < 0.01);
+        // Tenths of a Kelvin: 3121 dK = 38.95 °C
+        assert!((zones[0].best().celsius() - 38.95).abs() < 0.01);
+
+        assert_eq!(zones[1].high_precision, None);
+        assert!((zones[1].best().celsius() - 26.85).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_parse_zones_skips_records_without_temperature() {
+        let zones = parse_zones("Name : \\_TZ.TZ00\n");
+        assert!(zones.is_empty());
+    }
+}
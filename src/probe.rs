@@ -0,0 +1,86 @@
+//! Temperature probe backend for servers.
+//!
+//! Some servers report temperatures through `Win32_TemperatureProbe` in the
+//! default `root\cimv2` namespace instead of the ACPI thermal zone class.
+//! Like the ACPI class, `CurrentReading` is in tenths of a Kelvin. Many
+//! desktop firmwares list probes but leave `CurrentReading` empty.
+
+use std::time::Instant;
+
+use crate::{is_plausible, parse_records, record_value, CpuTemperature, TemperatureError, TemperatureProvider, ThermalZoneReading};
+
+/// The query listing every temperature probe.
+const PROBE_QUERY: &str = "Get-CimInstance -ClassName Win32_TemperatureProbe | Format-List DeviceID,Name,CurrentReading";
+
+/// Reads the temperature probes exposed through `Win32_TemperatureProbe`.
+///
+/// Each [`ThermalZoneReading`] carries the probe's `DeviceID`, or its `Name`
+/// when there is no device ID, as its instance name.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::{TemperatureProbeProvider, TemperatureProvider};
+///
+/// let temp = TemperatureProbeProvider.read_one()?;
+/// println!("{:.1}°C", temp.celsius());
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TemperatureProbeProvider;
+
+impl TemperatureProvider for TemperatureProbeProvider {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        probes_from(&CpuTemperature::run_powershell(PROBE_QUERY)?)
+    }
+
+    fn read_until(&self, deadline: Instant) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        probes_from(&CpuTemperature::run_powershell_until(PROBE_QUERY, deadline)?)
+    }
+}
+
+/// Parses the probe query output, failing if no probe has a valid reading.
+fn probes_from(output: &str) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+    parse_records(output, |record| {
+        let raw: u32 = record_value(record, "CurrentReading")?.parse().ok()?;
+        let temperature = CpuTemperature::from_decikelvin(f64::from(raw));
+        is_plausible(temperature.celsius()).then(|| ThermalZoneReading::new(
+            record_value(record, "DeviceID").or_else(|| record_value(record, "Name")).map(str::to_string),
+            temperature,
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_probes() {
+        let output = "\r\n\
+            DeviceID       : root/cimv2 0\r\n\
+            Name           : Numeric Sensor\r\n\
+            CurrentReading : 3132\r\n\
+            \r\n\
+            DeviceID       : \r\n\
+            Name           : Numeric Sensor\r\n\
+            CurrentReading : 3232\r\n\
+            \r\n\
+            DeviceID       : root/cimv2 2\r\n\
+            Name           : Numeric Sensor\r\n\
+            CurrentReading : \r\n";
+
+        let probes = probes_from(output).unwrap();
+
+        assert_eq!(probes.len(), 2);
+        assert_eq!(probes[0].instance_name.as_deref(), Some("root/cimv2 0"));
+        assert_eq!(probes[1].instance_name.as_deref(), Some("Numeric Sensor"));
+        assert!((probes[0].temperature.celsius() - 40.05).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_probes_without_readings() {
+        assert_eq!(probes_from(""), Err(TemperatureError::NoSensors));
+        assert_eq!(probes_from("DeviceID : root/cimv2 0\nCurrentReading :\n"), Err(TemperatureError::NoValidReadings));
+    }
+}
@@ -0,0 +1,168 @@
+//! Comparison of live readings against a recorded golden profile.
+
+use crate::{CpuTemperature, TemperatureError};
+
+/// One difference between a golden profile and the current readings.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ProfileDiff {
+    /// The zone exists in both sets but drifted beyond the tolerance.
+    Drifted {
+        /// Zone instance name
+        zone: String,
+        /// Celsius value recorded in the profile
+        expected: f64,
+        /// Celsius value read now
+        actual: f64,
+    },
+    /// The zone is in the profile but was not read now.
+    Missing {
+        /// Zone instance name
+        zone: String,
+        /// Celsius value recorded in the profile
+        expected: f64,
+    },
+    /// The zone was read now but is not in the profile.
+    Unexpected {
+        /// Zone instance name
+        zone: String,
+        /// Celsius value read now
+        actual: f64,
+    },
+}
+
+impl ProfileDiff {
+    /// Returns the zone instance name this difference refers to.
+    pub fn zone(&self) -> &str {
+        match self {
+            ProfileDiff::Drifted { zone, .. }
+            | ProfileDiff::Missing { zone, .. }
+            | ProfileDiff::Unexpected { zone, .. } => zone,
+        }
+    }
+
+    /// Returns `actual - expected` for drifted zones, `None` otherwise.
+    pub fn delta(&self) -> Option<f64> {
+        match self {
+            ProfileDiff::Drifted { expected, actual, .. } => Some(actual - expected),
+            _ => None,
+        }
+    }
+}
+
+impl CpuTemperature {
+    /// Compares the current thermal zones against a recorded golden profile.
+    ///
+    /// `profile` holds `(instance_name, celsius)` pairs recorded earlier, for
+    /// example before a firmware update. Zones whose current reading differs
+    /// from the profile by more than `tolerance` degrees Celsius are reported
+    /// as [`ProfileDiff::Drifted`]; zones present in only one of the two sets
+    /// are reported as [`ProfileDiff::Missing`] or
+    /// [`ProfileDiff::Unexpected`]. Zones within tolerance are omitted, so an
+    /// empty result means the profile matched.
+    ///
+    /// Differences are listed in profile order, followed by unexpected zones
+    /// in WMI order.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the WMI query fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// let golden = vec![(r"ACPI\ThermalZone\TZ00_0".to_string(), 41.0)];
+    /// for diff in CpuTemperature::compare_to_profile(&golden, 3.0)? {
+    ///     println!("{} differs from the profile: {:?}", diff.zone(), diff);
+    /// }
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    pub fn compare_to_profile(profile: &[(String, f64)], tolerance: f64) -> Result<Vec<ProfileDiff>, TemperatureError> {
+        let output = Self::run_wmi_query()?;
+        let current: Vec<(String, f64)> = Self::parse_named_zones(&output)
+            .into_iter()
+            .map(|(zone, reading)| (zone, reading.celsius()))
+            .collect();
+
+        Ok(diff_profiles(profile, &current, tolerance))
+    }
+}
+
+/// Lists the differences between `expected` and `actual` zone readings.
+fn diff_profiles(expected: &[(String, f64)], actual: &[(String, f64)], tolerance: f64) -> Vec<ProfileDiff> {
+    let find = |set: &[(String, f64)], zone: &str| set.iter().find(|(name, _)| name == zone).map(|&(_, c)| c);
+
+    let mut diffs: Vec<ProfileDiff> = expected
+        .iter()
+        .filter_map(|(zone, expected)| match find(actual, zone) {
+            None => Some(ProfileDiff::Missing {
+                zone: zone.clone(),
+                expected: *expected,
+            }),
+            Some(actual) if (actual - expected).abs() > tolerance => Some(ProfileDiff::Drifted {
+                zone: zone.clone(),
+                expected: *expected,
+                actual,
+            }),
+            Some(_) => None,
+        })
+        .collect();
+
+    diffs.extend(
+        actual
+            .iter()
+            .filter(|(zone, _)| find(expected, zone).is_none())
+            .map(|(zone, actual)| ProfileDiff::Unexpected {
+                zone: zone.clone(),
+                actual: *actual,
+            }),
+    );
+
+    diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zones(pairs: &[(&str, f64)]) -> Vec<(String, f64)> {
+        pairs.iter().map(|&(zone, c)| (zone.to_string(), c)).collect()
+    }
+
+    #[test]
+    fn test_diff_profiles_reports_drift_and_membership() {
+        let golden = zones(&[("TZ00", 40.0), ("TZ01", 45.0), ("TZ02", 50.0)]);
+        let current = zones(&[("TZ00", 42.0), ("TZ01", 49.5), ("TZ03", 38.0)]);
+
+        let diffs = diff_profiles(&golden, &current, 2.0);
+        assert_eq!(
+            diffs,
+            vec![
+                ProfileDiff::Drifted {
+                    zone: "TZ01".to_string(),
+                    expected: 45.0,
+                    actual: 49.5
+                },
+                ProfileDiff::Missing {
+                    zone: "TZ02".to_string(),
+                    expected: 50.0
+                },
+                ProfileDiff::Unexpected {
+                    zone: "TZ03".to_string(),
+                    actual: 38.0
+                },
+            ]
+        );
+        assert_eq!(diffs[0].delta(), Some(4.5));
+        assert_eq!(diffs[1].delta(), None);
+        assert_eq!(diffs[2].zone(), "TZ03");
+    }
+
+    #[test]
+    fn test_diff_profiles_matching() {
+        let golden = zones(&[("TZ00", 40.0)]);
+        assert!(diff_profiles(&golden, &zones(&[("TZ00", 39.0)]), 1.0).is_empty());
+    }
+}
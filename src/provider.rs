@@ -0,0 +1,388 @@
+//! Pluggable temperature providers.
+//!
+//! Code that consumes temperature readings can be written against the
+//! [`TemperatureProvider`] trait instead of calling [`CpuTemperature::get`]
+//! directly. Production code passes a [`WmiProvider`] or one of the other
+//! backends, such as [`PerfCounterProvider`](crate::PerfCounterProvider),
+//! while tests pass a [`MockProvider`] that never spawns PowerShell.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::{fallback, CpuTemperature, TemperatureError, ThermalZoneReading, TimedReading};
+
+/// A backend that reads CPU temperatures.
+///
+/// Implement [`read`](TemperatureProvider::read) to plug a new source of
+/// readings, such as a vendor SDK or a network sensor, into every reader in
+/// this crate. Wrappers like [`CachedReader`](crate::CachedReader) and
+/// [`TemperatureSampler`](crate::TemperatureSampler) accept any provider.
+///
+/// # Example
+///
+/// ```
+/// use tunjukin_suhu_cpu_windows::{CpuTemperature, TemperatureError, TemperatureProvider, ThermalZoneReading};
+///
+/// struct FixedZones;
+///
+/// impl TemperatureProvider for FixedZones {
+///     fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+///         Ok(vec![ThermalZoneReading::new(Some("package".to_string()), CpuTemperature::from_celsius(95.0))])
+///     }
+/// }
+///
+/// fn is_overheating(provider: &impl TemperatureProvider) -> Result<bool, TemperatureError> {
+///     Ok(provider.read_one()?.celsius() >= 90.0)
+/// }
+///
+/// assert_eq!(is_overheating(&FixedZones), Ok(true));
+/// ```
+pub trait TemperatureProvider {
+    /// Reads every zone the provider knows about.
+    ///
+    /// Implementations should return an error rather than an empty vector
+    /// when nothing could be read.
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError>;
+
+    /// Takes one temperature reading, by default the first zone returned by
+    /// [`read`](TemperatureProvider::read).
+    ///
+    /// Override this if a single reading is cheaper than reading every zone.
+    fn read_one(&self) -> Result<CpuTemperature, TemperatureError> {
+        self.read()?
+            .into_iter()
+            .next()
+            .map(|zone| zone.temperature)
+            .ok_or(TemperatureError::NoSensors)
+    }
+
+    /// Reads one temperature like [`read_one`](TemperatureProvider::read_one)
+    /// and stamps it with the moment the read returned, so callers need not
+    /// take the time themselves after other work has skewed it.
+    fn read_timed(&self) -> Result<TimedReading, TemperatureError> {
+        self.read_one().map(TimedReading::now)
+    }
+
+    /// Reads every zone like [`read`](TemperatureProvider::read), but fails
+    /// with [`TemperatureError::Timeout`] rather than returning after
+    /// `deadline`.
+    ///
+    /// By default this waits for `read` however long it takes, and readers
+    /// with a [timeout](crate::TemperatureReaderBuilder::timeout) stop
+    /// waiting for it on a helper thread. Override this if the backend can
+    /// abandon a read, such as by killing a child process, so that nothing
+    /// keeps running past the deadline.
+    fn read_until(&self, deadline: Instant) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        let zones = self.read()?;
+        if Instant::now() > deadline {
+            return Err(TemperatureError::Timeout);
+        }
+        Ok(zones)
+    }
+}
+
+impl<T: TemperatureProvider + ?Sized> TemperatureProvider for &T {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        (**self).read()
+    }
+
+    fn read_one(&self) -> Result<CpuTemperature, TemperatureError> {
+        (**self).read_one()
+    }
+
+    fn read_timed(&self) -> Result<TimedReading, TemperatureError> {
+        (**self).read_timed()
+    }
+
+    fn read_until(&self, deadline: Instant) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        (**self).read_until(deadline)
+    }
+}
+
+impl<T: TemperatureProvider + ?Sized> TemperatureProvider for Box<T> {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        (**self).read()
+    }
+
+    fn read_one(&self) -> Result<CpuTemperature, TemperatureError> {
+        (**self).read_one()
+    }
+
+    fn read_timed(&self) -> Result<TimedReading, TemperatureError> {
+        (**self).read_timed()
+    }
+
+    fn read_until(&self, deadline: Instant) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        (**self).read_until(deadline)
+    }
+}
+
+impl<T: TemperatureProvider + ?Sized> TemperatureProvider for Arc<T> {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        (**self).read()
+    }
+
+    fn read_one(&self) -> Result<CpuTemperature, TemperatureError> {
+        (**self).read_one()
+    }
+
+    fn read_timed(&self) -> Result<TimedReading, TemperatureError> {
+        (**self).read_timed()
+    }
+
+    fn read_until(&self, deadline: Instant) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        (**self).read_until(deadline)
+    }
+}
+
+/// The default provider, backed by the same query as
+/// [`CpuTemperature::get`] and [`CpuTemperature::get_all`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WmiProvider;
+
+impl TemperatureProvider for WmiProvider {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        CpuTemperature::get_all()
+    }
+
+    fn read_one(&self) -> Result<CpuTemperature, TemperatureError> {
+        CpuTemperature::get()
+    }
+
+    /// Reads like [`CpuTemperature::get_all`], killing PowerShell or
+    /// abandoning the COM query still running at `deadline`, including
+    /// while falling back to other backends.
+    fn read_until(&self, deadline: Instant) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        fallback::recover(CpuTemperature::read_acpi_zones_until(deadline), |provider| provider.read_until(deadline))
+    }
+}
+
+/// A deterministic temperature provider that never spawns PowerShell.
+///
+/// Use it to unit-test code built on [`TemperatureProvider`], or to develop
+/// on machines and CI runners without thermal sensors. A mock either returns
+/// the same result on every read ([`MockProvider::fixed`],
+/// [`MockProvider::failing`]), follows a sine wave
+/// ([`MockProvider::sine`]), or plays back a scripted sequence of results
+/// ([`MockProvider::sequence`]). A scripted mock returns an error once its
+/// script is exhausted.
+///
+/// # Example
+///
+/// ```
+/// use tunjukin_suhu_cpu_windows::{CpuTemperature, MockProvider, TemperatureProvider};
+///
+/// let source = MockProvider::sequence(vec![
+///     Ok(CpuTemperature::from_celsius(40.0)),
+///     Err("sensor unavailable".into()),
+/// ]);
+///
+/// assert_eq!(source.read_one().unwrap().celsius(), 40.0);
+/// assert!(source.read_one().is_err());
+/// assert_eq!(source.call_count(), 2);
+/// ```
+#[derive(Debug)]
+pub struct MockProvider {
+    script: Script,
+    calls: AtomicUsize,
+}
+
+#[derive(Debug)]
+enum Script {
+    Fixed(Result<CpuTemperature, TemperatureError>),
+    Sequence(Mutex<VecDeque<Result<CpuTemperature, TemperatureError>>>),
+    Sine { center: f64, amplitude: f64, period: usize },
+}
+
+impl MockProvider {
+    /// Creates a mock that returns `reading` on every read.
+    pub fn fixed(reading: CpuTemperature) -> Self {
+        Self::with_script(Script::Fixed(Ok(reading)))
+    }
+
+    /// Creates a mock that fails with `error` on every read.
+    pub fn failing(error: impl Into<TemperatureError>) -> Self {
+        Self::with_script(Script::Fixed(Err(error.into())))
+    }
+
+    /// Creates a mock that returns the given results in order, one per read.
+    pub fn sequence(results: impl IntoIterator<Item = Result<CpuTemperature, TemperatureError>>) -> Self {
+        Self::with_script(Script::Sequence(Mutex::new(results.into_iter().collect())))
+    }
+
+    /// Creates a mock whose readings oscillate around `center` degrees
+    /// Celsius by up to `amplitude` degrees, completing one cycle every
+    /// `period` reads.
+    ///
+    /// The first read returns `center`, and the wave advances one step per
+    /// read, so the values are the same on every run.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tunjukin_suhu_cpu_windows::{MockProvider, TemperatureProvider};
+    ///
+    /// let provider = MockProvider::sine(50.0, 10.0, 4);
+    /// let readings: Vec<f64> = (0..4).map(|_| provider.read_one().unwrap().celsius().round()).collect();
+    ///
+    /// assert_eq!(readings, vec![50.0, 60.0, 50.0, 40.0]);
+    /// ```
+    pub fn sine(center: f64, amplitude: f64, period: usize) -> Self {
+        assert!(period > 0, "MockProvider::sine period must be non-zero");
+        Self::with_script(Script::Sine { center, amplitude, period })
+    }
+
+    /// Returns how many readings have been taken from the mock.
+    pub fn call_count(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    fn with_script(script: Script) -> Self {
+        MockProvider {
+            script,
+            calls: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl TemperatureProvider for MockProvider {
+    /// Returns the next scripted result as a single unnamed zone.
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        let temperature = self.read_one()?;
+        Ok(vec![ThermalZoneReading::new(None, temperature)])
+    }
+
+    fn read_one(&self) -> Result<CpuTemperature, TemperatureError> {
+        let step = self.calls.fetch_add(1, Ordering::SeqCst);
+
+        match &self.script {
+            Script::Fixed(result) => result.clone(),
+            Script::Sine { center, amplitude, period } => {
+                let phase = (step % period) as f64 / *period as f64 * std::f64::consts::TAU;
+                Ok(CpuTemperature::from_celsius(center + amplitude * phase.sin()))
+            }
+            Script::Sequence(queue) => queue
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .pop_front()
+                .unwrap_or_else(|| Err(TemperatureError::Other("MockProvider script exhausted".to_string()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_fixed_repeats() {
+        let source = MockProvider::fixed(CpuTemperature::from_celsius(25.0));
+
+        for _ in 0..3 {
+            assert_eq!(source.read_one().unwrap().fahrenheit(), 77.0);
+        }
+        assert_eq!(source.call_count(), 3);
+    }
+
+    #[test]
+    fn test_wrappers_forward_read_timed() {
+        struct Stamped(Instant);
+
+        impl TemperatureProvider for Stamped {
+            fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+                Err(TemperatureError::NoSensors)
+            }
+
+            fn read_timed(&self) -> Result<TimedReading, TemperatureError> {
+                Ok(TimedReading::new(CpuTemperature::from_celsius(40.0), self.0))
+            }
+        }
+
+        let at = Instant::now();
+        let stamped = Stamped(at);
+        assert_eq!(TemperatureProvider::read_timed(&&stamped).unwrap().at, at);
+        assert_eq!(Box::new(Stamped(at)).read_timed().unwrap().at, at);
+        assert_eq!(Arc::new(Stamped(at)).read_timed().unwrap().at, at);
+    }
+
+    #[test]
+    fn test_mock_failing() {
+        let source = MockProvider::failing("boom");
+        assert_eq!(source.read_one(), Err("boom".into()));
+    }
+
+    #[test]
+    fn test_mock_sequence_then_exhausted() {
+        let source = MockProvider::sequence(vec![
+            Ok(CpuTemperature::from_celsius(30.0)),
+            Err("transient".into()),
+            Ok(CpuTemperature::from_celsius(31.0)),
+        ]);
+
+        assert_eq!(source.read_one().unwrap().celsius(), 30.0);
+        assert_eq!(source.read_one(), Err("transient".into()));
+        assert_eq!(source.read_one().unwrap().celsius(), 31.0);
+        assert!(source.read_one().unwrap_err().to_string().contains("exhausted"));
+    }
+
+    #[test]
+    fn test_mock_sine_wraps_around() {
+        let provider = MockProvider::sine(45.0, 5.0, 8);
+        let readings: Vec<f64> = (0..16).map(|_| provider.read_one().unwrap().celsius()).collect();
+
+        assert_eq!(readings[0], 45.0);
+        assert!((readings[2] - 50.0).abs() < 1e-9);
+        assert!((readings[6] - 40.0).abs() < 1e-9);
+        assert_eq!(readings[..8], readings[8..]);
+        assert!(readings.iter().all(|&c| (40.0..=50.0).contains(&c)));
+    }
+
+    #[test]
+    #[should_panic(expected = "period must be non-zero")]
+    fn test_mock_sine_rejects_zero_period() {
+        MockProvider::sine(45.0, 5.0, 0);
+    }
+
+    #[test]
+    fn test_source_through_references() {
+        let source = Arc::new(MockProvider::fixed(CpuTemperature::from_celsius(50.0)));
+        let boxed: Box<dyn TemperatureProvider> = Box::new(Arc::clone(&source));
+        let read_by_value = |s: &dyn TemperatureProvider| s.read_one();
+
+        assert!(boxed.read_one().is_ok());
+        assert!(read_by_value(&&*source).is_ok());
+        assert_eq!(boxed.read().unwrap().len(), 1);
+        assert_eq!(source.call_count(), 3);
+    }
+
+    #[test]
+    fn test_read_one_defaults_to_first_zone() {
+        struct TwoZones;
+
+        impl TemperatureProvider for TwoZones {
+            fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+                Ok([40.0, 60.0]
+                    .into_iter()
+                    .map(|celsius| ThermalZoneReading::new(None, CpuTemperature::from_celsius(celsius)))
+                    .collect())
+            }
+        }
+
+        struct NoZones;
+
+        impl TemperatureProvider for NoZones {
+            fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+                Ok(Vec::new())
+            }
+        }
+
+        assert_eq!(TwoZones.read_one(), Ok(CpuTemperature::from_celsius(40.0)));
+        assert_eq!(NoZones.read_one(), Err(TemperatureError::NoSensors));
+    }
+}
@@ -0,0 +1,199 @@
+//! Naming temperature backends, for configuration files and options.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::{
+    CpuTemperature, LibreHardwareMonitorProvider, OpenHardwareMonitorProvider, PerfCounterProvider, TemperatureError,
+    TemperatureProbeProvider, TemperatureProvider, ThermalZoneReading, WmiProvider,
+};
+
+/// A provider that can be created by name.
+pub type BoxedProvider = Box<dyn TemperatureProvider + Send + Sync>;
+
+/// One of the temperature backends of this crate, named the way
+/// configuration files and command-line options refer to it.
+///
+/// Every kind exists in every build, so a configuration naming a backend
+/// parses everywhere; [`provider`](ProviderKind::provider) fails for
+/// backends whose Cargo feature or platform is missing.
+///
+/// # Example
+///
+/// ```
+/// use tunjukin_suhu_cpu_windows::ProviderKind;
+///
+/// let kind: ProviderKind = "libre-hardware-monitor".parse()?;
+/// assert_eq!(kind, ProviderKind::LibreHardwareMonitor);
+/// assert_eq!(kind.to_string(), "libre-hardware-monitor");
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
+#[non_exhaustive]
+pub enum ProviderKind {
+    /// `wmi`: [`WmiProvider`], the query behind [`CpuTemperature::get`],
+    /// with its fallbacks.
+    Wmi,
+    /// `native`: `NativeWmiProvider`, reading the ACPI thermal zones through
+    /// COM. Needs the `native` feature.
+    Native,
+    /// `acpi`: the ACPI thermal zones alone, without the fallbacks of
+    /// [`Wmi`](ProviderKind::Wmi).
+    Acpi,
+    /// `probe`: [`TemperatureProbeProvider`].
+    Probe,
+    /// `perf-counter`: [`PerfCounterProvider`].
+    PerfCounter,
+    /// `libre-hardware-monitor`: [`LibreHardwareMonitorProvider`].
+    LibreHardwareMonitor,
+    /// `open-hardware-monitor`: [`OpenHardwareMonitorProvider`].
+    OpenHardwareMonitor,
+    /// `core-temp`: `CoreTempProvider`. Needs the `shared-memory` feature.
+    CoreTemp,
+    /// `hwinfo`: `HwInfoProvider`. Needs the `shared-memory` feature.
+    #[cfg_attr(feature = "serde", serde(rename = "hwinfo"))]
+    HwInfo,
+    /// `aida64`: `Aida64Provider`. Needs the `shared-memory` feature.
+    Aida64,
+    /// `msr`: `MsrProvider`, loading `WinRing0x64.dll` from the DLL search
+    /// path. Needs the `msr` feature.
+    Msr,
+}
+
+impl ProviderKind {
+    /// Every kind, in the order of the variants.
+    pub const ALL: [ProviderKind; 11] = [
+        ProviderKind::Wmi,
+        ProviderKind::Native,
+        ProviderKind::Acpi,
+        ProviderKind::Probe,
+        ProviderKind::PerfCounter,
+        ProviderKind::LibreHardwareMonitor,
+        ProviderKind::OpenHardwareMonitor,
+        ProviderKind::CoreTemp,
+        ProviderKind::HwInfo,
+        ProviderKind::Aida64,
+        ProviderKind::Msr,
+    ];
+
+    /// Returns the name of the kind, such as `"core-temp"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ProviderKind::Wmi => "wmi",
+            ProviderKind::Native => "native",
+            ProviderKind::Acpi => "acpi",
+            ProviderKind::Probe => "probe",
+            ProviderKind::PerfCounter => "perf-counter",
+            ProviderKind::LibreHardwareMonitor => "libre-hardware-monitor",
+            ProviderKind::OpenHardwareMonitor => "open-hardware-monitor",
+            ProviderKind::CoreTemp => "core-temp",
+            ProviderKind::HwInfo => "hwinfo",
+            ProviderKind::Aida64 => "aida64",
+            ProviderKind::Msr => "msr",
+        }
+    }
+
+    /// Creates the provider of this kind.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`TemperatureError::InvalidArgument`] if the backend was
+    /// not compiled into this build.
+    pub fn provider(self) -> Result<BoxedProvider, TemperatureError> {
+        Ok(match self {
+            ProviderKind::Wmi => Box::new(WmiProvider),
+            #[cfg(all(windows, feature = "native"))]
+            ProviderKind::Native => Box::new(crate::NativeWmiProvider),
+            #[cfg(not(all(windows, feature = "native")))]
+            ProviderKind::Native => return Err(self.unavailable("native")),
+            ProviderKind::Acpi => Box::new(AcpiProvider),
+            ProviderKind::Probe => Box::new(TemperatureProbeProvider),
+            ProviderKind::PerfCounter => Box::new(PerfCounterProvider),
+            ProviderKind::LibreHardwareMonitor => Box::new(LibreHardwareMonitorProvider),
+            ProviderKind::OpenHardwareMonitor => Box::new(OpenHardwareMonitorProvider),
+            #[cfg(all(windows, feature = "shared-memory"))]
+            ProviderKind::CoreTemp => Box::new(crate::CoreTempProvider),
+            #[cfg(all(windows, feature = "shared-memory"))]
+            ProviderKind::HwInfo => Box::new(crate::HwInfoProvider),
+            #[cfg(all(windows, feature = "shared-memory"))]
+            ProviderKind::Aida64 => Box::new(crate::Aida64Provider),
+            #[cfg(not(all(windows, feature = "shared-memory")))]
+            ProviderKind::CoreTemp | ProviderKind::HwInfo | ProviderKind::Aida64 => return Err(self.unavailable("shared-memory")),
+            #[cfg(all(windows, feature = "msr"))]
+            ProviderKind::Msr => Box::new(crate::MsrProvider::new()),
+            #[cfg(not(all(windows, feature = "msr")))]
+            ProviderKind::Msr => return Err(self.unavailable("msr")),
+        })
+    }
+
+    /// The error for a backend that needs `feature`.
+    #[allow(dead_code)] // Unused when every backend is compiled in.
+    fn unavailable(self, feature: &str) -> TemperatureError {
+        TemperatureError::InvalidArgument(format!("the `{}` provider needs the `{}` feature on Windows", self, feature))
+    }
+}
+
+impl fmt::Display for ProviderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ProviderKind {
+    type Err = TemperatureError;
+
+    /// Parses the name of a kind, ignoring case and surrounding whitespace.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        ProviderKind::ALL.into_iter().find(|kind| kind.name().eq_ignore_ascii_case(text)).ok_or_else(|| {
+            let names: Vec<&str> = ProviderKind::ALL.iter().map(|kind| kind.name()).collect();
+            TemperatureError::InvalidArgument(format!("unknown provider '{}' (expected one of {})", text, names.join(", ")))
+        })
+    }
+}
+
+/// The ACPI thermal zones, without falling back to other backends.
+struct AcpiProvider;
+
+impl TemperatureProvider for AcpiProvider {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        CpuTemperature::read_acpi_zones()
+    }
+
+    fn read_until(&self, deadline: Instant) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        CpuTemperature::read_acpi_zones_until(deadline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_round_trip() {
+        for kind in ProviderKind::ALL {
+            assert_eq!(kind.name().parse::<ProviderKind>(), Ok(kind));
+        }
+        assert_eq!(" HWiNFO ".parse::<ProviderKind>(), Ok(ProviderKind::HwInfo));
+        assert!(matches!("speedfan".parse::<ProviderKind>(), Err(TemperatureError::InvalidArgument(message)) if message.contains("core-temp")));
+    }
+
+    #[cfg(not(all(windows, feature = "msr")))]
+    #[test]
+    fn test_unavailable_provider() {
+        let Err(TemperatureError::InvalidArgument(message)) = ProviderKind::Msr.provider() else {
+            panic!("the msr provider should be unavailable");
+        };
+        assert_eq!(message, "the `msr` provider needs the `msr` feature on Windows");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_names() {
+        for kind in ProviderKind::ALL {
+            assert_eq!(serde_json::to_string(&kind).unwrap(), format!("\"{}\"", kind.name()));
+        }
+    }
+}
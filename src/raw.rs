@@ -0,0 +1,123 @@
+//! Thermal zone readings exactly as WMI reported them.
+
+use crate::{is_plausible, parse_format_list, record_value, CpuTemperature, TemperatureError};
+
+/// One `MSAcpi_ThermalZoneTemperature` record, unfiltered and unconverted.
+///
+/// [`CpuTemperature::get_all`] keeps only the converted temperature and
+/// drops zones that fail the sanity check. A `RawReading` keeps what the
+/// firmware reported, which helps when debugging a board whose zones report
+/// bogus values such as a constant `2732` (0 °C).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawReading {
+    /// The zone's `InstanceName`, such as `ACPI\ThermalZone\TZ00_0`, or
+    /// `None` if WMI did not report one.
+    pub instance_name: Option<String>,
+    /// The zone's `CurrentTemperature` in tenths of a Kelvin, or `None` if it
+    /// is missing or not a number.
+    pub decikelvin: Option<u32>,
+    /// Every property of the record as `(name, value)` pairs, in the order
+    /// WMI listed them.
+    pub record: Vec<(String, String)>,
+}
+
+impl RawReading {
+    /// Converts [`decikelvin`](RawReading::decikelvin), whether or not the
+    /// result is plausible.
+    pub fn temperature(&self) -> Option<CpuTemperature> {
+        self.decikelvin.map(|raw| CpuTemperature::from_decikelvin(f64::from(raw)))
+    }
+
+    /// Returns `true` if the temperature passes the sanity check that
+    /// [`CpuTemperature::get_all`] applies.
+    pub fn is_plausible(&self) -> bool {
+        self.temperature().is_some_and(|reading| is_plausible(reading.celsius()))
+    }
+
+    /// Returns the value of the property `name`, such as
+    /// `CriticalTripPoint`, or `None` if the record has no such property or
+    /// it is empty.
+    pub fn property(&self, name: &str) -> Option<&str> {
+        record_value(&self.record, name)
+    }
+}
+
+impl CpuTemperature {
+    /// Reads every ACPI thermal zone record as reported, including zones
+    /// whose value fails the sanity check.
+    ///
+    /// This always queries through PowerShell, even with the `native`
+    /// feature, and never falls back to other providers.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the WMI query fails or returns no records.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// for zone in CpuTemperature::get_raw()? {
+    ///     println!("{:?}: {:?} ({:?})", zone.instance_name, zone.decikelvin, zone.property("CriticalTripPoint"));
+    /// }
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    pub fn get_raw() -> Result<Vec<RawReading>, TemperatureError> {
+        parse_raw(&Self::run_wmi_query()?)
+    }
+}
+
+/// Parses every record of the ACPI query output.
+fn parse_raw(output: &str) -> Result<Vec<RawReading>, TemperatureError> {
+    let readings: Vec<RawReading> = parse_format_list(output)
+        .into_iter()
+        .map(|record| RawReading {
+            instance_name: record_value(&record, "InstanceName").map(str::to_string),
+            decikelvin: record_value(&record, "CurrentTemperature").and_then(|raw| raw.parse().ok()),
+            record,
+        })
+        .collect();
+    if readings.is_empty() {
+        return Err(TemperatureError::NoSensors);
+    }
+
+    Ok(readings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_raw() {
+        let output = "\r\n\
+            Active             : True\r\n\
+            CriticalTripPoint  : 3782\r\n\
+            CurrentTemperature : 2732\r\n\
+            InstanceName       : ACPI\\ThermalZone\\TZ00_0\r\n\
+            \r\n\
+            CurrentTemperature : \r\n\
+            InstanceName       : ACPI\\ThermalZone\\TZ01_0\r\n\
+            \r\n";
+
+        let readings = parse_raw(output).unwrap();
+        assert_eq!(readings.len(), 2);
+        assert_eq!(readings[0].instance_name.as_deref(), Some("ACPI\\ThermalZone\\TZ00_0"));
+        assert_eq!(readings[0].decikelvin, Some(2732));
+        assert_eq!(readings[0].property("CriticalTripPoint"), Some("3782"));
+        assert_eq!(readings[0].record.len(), 4);
+        assert!((readings[0].temperature().unwrap().celsius() - 0.05).abs() < 0.001);
+        assert!(readings[0].is_plausible());
+
+        assert_eq!(readings[1].decikelvin, None);
+        assert_eq!(readings[1].temperature(), None);
+        assert!(!readings[1].is_plausible());
+    }
+
+    #[test]
+    fn test_parse_raw_empty() {
+        assert_eq!(parse_raw(""), Err(TemperatureError::NoSensors));
+    }
+}
@@ -0,0 +1,407 @@
+//! A reusable, configured temperature reader.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{
+    validity, Aggregate, Calibration, CpuTemperature, Smoother, Smoothing, TemperatureError, TemperatureProvider, TemperatureUnit, ThermalZoneReading,
+    TimedReading, WmiProvider,
+};
+
+/// The provider of a [`TemperatureReader`], shareable with the thread that
+/// enforces its timeout.
+type SharedProvider = Arc<dyn TemperatureProvider + Send + Sync>;
+
+/// Configures a [`TemperatureReader`], created by [`CpuTemperature::builder`].
+///
+/// Every option has a default, so `CpuTemperature::builder().build()` reads
+/// like [`CpuTemperature::get`].
+pub struct TemperatureReaderBuilder {
+    provider: SharedProvider,
+    timeout: Option<Duration>,
+    unit: TemperatureUnit,
+    pub(crate) calibration: Calibration,
+    smoothing: Option<Smoothing>,
+    aggregate: Aggregate,
+    valid_range: Option<RangeInclusive<f64>>,
+}
+
+impl TemperatureReaderBuilder {
+    /// Reads from `provider` instead of the default [`WmiProvider`].
+    pub fn provider(mut self, provider: impl TemperatureProvider + Send + Sync + 'static) -> Self {
+        self.provider = Arc::new(provider);
+        self
+    }
+
+    /// Fails reads with [`TemperatureError::Timeout`] once they take longer
+    /// than `timeout`. By default, reads wait as long as the provider takes.
+    ///
+    /// Reads go through [`TemperatureProvider::read_until`], so providers
+    /// that honor its deadline, like [`WmiProvider`], give up with the
+    /// reader: a PowerShell query still running is killed. Any other
+    /// provider keeps reading on a background thread until it returns, and
+    /// its result is discarded.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the unit of [`TemperatureReader::get_value`], Celsius by
+    /// default.
+    pub fn unit(mut self, unit: TemperatureUnit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    /// Applies `calibration` to every reading. By default, readings are not
+    /// corrected.
+    pub fn calibration(mut self, calibration: Calibration) -> Self {
+        self.calibration = calibration;
+        self
+    }
+
+    /// Smooths the readings returned by [`TemperatureReader::get`], or passes
+    /// them through unchanged with `None`, the default.
+    pub fn smoothing(mut self, smoothing: Option<Smoothing>) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Sets how [`TemperatureReader::get`] combines the zones of a read,
+    /// [`Aggregate::First`] by default.
+    pub fn aggregate(mut self, aggregate: Aggregate) -> Self {
+        self.aggregate = aggregate;
+        self
+    }
+
+    /// Accepts raw readings within `range`, in degrees Celsius and bounds
+    /// included, instead of the process-wide
+    /// [valid range](crate::set_valid_range).
+    ///
+    /// The crate's own providers check their readings against this range
+    /// while reading for this reader, so a wider range keeps readings they
+    /// would otherwise drop. Zones of any provider outside it are dropped,
+    /// and a read whose zones all lie outside it fails with
+    /// [`TemperatureError::OutOfRange`]. Other readers are unaffected.
+    pub fn valid_range(mut self, range: RangeInclusive<f64>) -> Self {
+        self.valid_range = Some(range);
+        self
+    }
+
+    /// Creates the reader.
+    pub fn build(self) -> TemperatureReader {
+        TemperatureReader {
+            provider: self.provider,
+            timeout: self.timeout,
+            unit: self.unit,
+            calibration: self.calibration,
+            smoother: Mutex::new(self.smoothing.map(Smoother::new)),
+            aggregate: self.aggregate,
+            valid_range: self.valid_range,
+        }
+    }
+}
+
+impl fmt::Debug for TemperatureReaderBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TemperatureReaderBuilder")
+            .field("timeout", &self.timeout)
+            .field("unit", &self.unit)
+            .field("calibration", &self.calibration)
+            .field("smoothing", &self.smoothing)
+            .field("aggregate", &self.aggregate)
+            .field("valid_range", &self.valid_range)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Reads temperatures with a fixed configuration of provider, timeout, unit,
+/// calibration and smoothing.
+///
+/// Build one with [`CpuTemperature::builder`] and keep it for as long as
+/// readings are needed: the smoothing filter carries over from one
+/// [`get`](TemperatureReader::get) to the next. A reader can be shared
+/// between threads, and is itself a [`TemperatureProvider`], so it can be
+/// handed to any wrapper in this crate.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use tunjukin_suhu_cpu_windows::{Calibration, CpuTemperature, Smoothing, TemperatureUnit};
+///
+/// let reader = CpuTemperature::builder()
+///     .timeout(Duration::from_secs(2))
+///     .unit(TemperatureUnit::Fahrenheit)
+///     .calibration(Calibration::new().zone_offset("TZ01", -8.0))
+///     .smoothing(Some(Smoothing::Exponential { alpha: 0.3 }))
+///     .build();
+///
+/// for _ in 0..10 {
+///     println!("{:.1}{}", reader.get_value()?, reader.unit().symbol());
+/// }
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+pub struct TemperatureReader {
+    provider: SharedProvider,
+    timeout: Option<Duration>,
+    unit: TemperatureUnit,
+    calibration: Calibration,
+    smoother: Mutex<Option<Smoother>>,
+    aggregate: Aggregate,
+    valid_range: Option<RangeInclusive<f64>>,
+}
+
+impl CpuTemperature {
+    /// Starts configuring a reusable [`TemperatureReader`].
+    pub fn builder() -> TemperatureReaderBuilder {
+        TemperatureReaderBuilder {
+            provider: Arc::new(WmiProvider),
+            timeout: None,
+            unit: TemperatureUnit::default(),
+            calibration: Calibration::default(),
+            smoothing: None,
+            aggregate: Aggregate::default(),
+            valid_range: None,
+        }
+    }
+}
+
+impl TemperatureReader {
+    /// Reads every zone, with the calibration applied.
+    ///
+    /// Zone readings are not smoothed, since the filter follows a single
+    /// value.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the provider fails or the read exceeds the timeout, or
+    /// with [`TemperatureError::OutOfRange`] if every zone lies outside the
+    /// [valid range](TemperatureReaderBuilder::valid_range).
+    pub fn get_all(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        let range = self.valid_range.as_ref();
+        let zones = validity::with_range(range, || {
+            let zones = match self.timeout {
+                Some(timeout) => read_within(&self.provider, timeout, range.cloned())?,
+                None => self.provider.read()?,
+            };
+            match range {
+                Some(_) => validity::accepted(zones),
+                None => Ok(zones),
+            }
+        })?;
+        Ok(zones.into_iter().map(|zone| self.calibration.apply(zone)).collect())
+    }
+
+    /// Reads every zone and combines them as configured by
+    /// [`aggregate`](TemperatureReaderBuilder::aggregate), by default taking
+    /// the first one, with the calibration and smoothing applied.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the provider fails, returns no zone, or the read exceeds the
+    /// timeout, or if [`Aggregate::apply`] fails. A failed read leaves the
+    /// smoothing filter unchanged.
+    pub fn get(&self) -> Result<CpuTemperature, TemperatureError> {
+        self.get_timed().map(|timed| timed.reading)
+    }
+
+    /// Like [`get`](TemperatureReader::get), but stamped with the time the
+    /// first zone was read.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`get`](TemperatureReader::get).
+    pub fn get_timed(&self) -> Result<TimedReading, TemperatureError> {
+        let zones = self.get_all()?;
+        let reading = self.aggregate.apply(&zones)?;
+
+        let mut smoother = self.smoother.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let reading = match smoother.as_mut() {
+            Some(smoother) => smoother.push(&reading),
+            None => reading,
+        };
+        Ok(TimedReading { reading, at: zones[0].at, time: zones[0].time })
+    }
+
+    /// Like [`get`](TemperatureReader::get), but returns the value in the
+    /// configured [unit](TemperatureReader::unit).
+    pub fn get_value(&self) -> Result<f64, TemperatureError> {
+        Ok(self.get()?.temperature().as_unit(self.unit))
+    }
+
+    /// Returns the unit of [`get_value`](TemperatureReader::get_value).
+    pub fn unit(&self) -> TemperatureUnit {
+        self.unit
+    }
+
+    /// Returns the timeout of each read, if any.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Returns the calibration applied to readings.
+    pub fn calibration(&self) -> &Calibration {
+        &self.calibration
+    }
+
+    /// Returns the range of accepted raw readings, if the reader has its
+    /// own.
+    pub fn valid_range(&self) -> Option<&RangeInclusive<f64>> {
+        self.valid_range.as_ref()
+    }
+}
+
+impl TemperatureProvider for TemperatureReader {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        self.get_all()
+    }
+
+    fn read_one(&self) -> Result<CpuTemperature, TemperatureError> {
+        self.get()
+    }
+}
+
+impl fmt::Debug for TemperatureReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TemperatureReader")
+            .field("timeout", &self.timeout)
+            .field("unit", &self.unit)
+            .field("calibration", &self.calibration)
+            .field("aggregate", &self.aggregate)
+            .field("valid_range", &self.valid_range)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Reads `provider` on a helper thread, accepting readings within `range`,
+/// giving up after `timeout`, and passes the same deadline to the provider
+/// so it can give up too. A timeout too long to have a deadline, such as
+/// [`Duration::MAX`], waits for a plain read.
+fn read_within(provider: &SharedProvider, timeout: Duration, range: Option<RangeInclusive<f64>>) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+    let (sender, receiver) = mpsc::channel();
+    let provider = Arc::clone(provider);
+    let deadline = Instant::now().checked_add(timeout);
+    thread::spawn(move || {
+        let _ = sender.send(validity::with_range(range.as_ref(), || match deadline {
+            Some(deadline) => provider.read_until(deadline),
+            None => provider.read(),
+        }));
+    });
+
+    receiver.recv_timeout(timeout).unwrap_or(Err(TemperatureError::Timeout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockProvider;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    struct SlowProvider;
+
+    impl TemperatureProvider for SlowProvider {
+        fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+            thread::sleep(Duration::from_millis(500));
+            Ok(Vec::new())
+        }
+    }
+
+    /// A provider that hangs on a plain read, but gives up at a deadline.
+    struct DeadlineProvider {
+        finished: Arc<AtomicBool>,
+    }
+
+    impl TemperatureProvider for DeadlineProvider {
+        fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+            thread::sleep(Duration::from_secs(5));
+            Ok(Vec::new())
+        }
+
+        fn read_until(&self, deadline: Instant) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+            while Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(1));
+            }
+            self.finished.store(true, Ordering::SeqCst);
+            Err(TemperatureError::Timeout)
+        }
+    }
+
+    struct TwoZones;
+
+    impl TemperatureProvider for TwoZones {
+        fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+            Ok([("TZ00", 28.0), ("TZ01", 64.0)]
+                .map(|(name, celsius)| ThermalZoneReading::new(Some(name.to_string()), CpuTemperature::from_celsius(celsius)))
+                .into())
+        }
+    }
+
+    #[test]
+    fn test_calibrated_and_smoothed() {
+        let provider = MockProvider::sequence([60.0, 70.0].map(|c| Ok(CpuTemperature::from_celsius(c))));
+        let reader = CpuTemperature::builder()
+            .provider(provider)
+            .unit(TemperatureUnit::Kelvin)
+            .calibration(Calibration::new().global_offset(-10.0))
+            .smoothing(Some(Smoothing::Exponential { alpha: 0.5 }))
+            .build();
+
+        assert_eq!(reader.get(), Ok(CpuTemperature::from_celsius(50.0)));
+        assert!((reader.get_value().unwrap() - 328.15).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_get_timed_keeps_read_time() {
+        let reader = CpuTemperature::builder().provider(TwoZones).build();
+        let before = Instant::now();
+        let timed = reader.get_timed().unwrap();
+
+        assert_eq!(timed.reading, CpuTemperature::from_celsius(28.0));
+        assert!(timed.at >= before && timed.at <= Instant::now());
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let reader = CpuTemperature::builder().provider(TwoZones).aggregate(Aggregate::Max).calibration(Calibration::new().zone_offset("TZ00", 40.0)).build();
+
+        assert_eq!(reader.get(), Ok(CpuTemperature::from_celsius(68.0)));
+    }
+
+    #[test]
+    fn test_valid_range() {
+        let reader = CpuTemperature::builder().provider(TwoZones).valid_range(0.0..=30.0).build();
+        assert_eq!(reader.get_all().unwrap().len(), 1);
+
+        let reader = CpuTemperature::builder().provider(TwoZones).valid_range(-80.0..=0.0).build();
+        assert!(matches!(reader.get(), Err(TemperatureError::OutOfRange { details }) if details.contains("TZ01: 64.00 °C")));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let reader = CpuTemperature::builder().provider(SlowProvider).timeout(Duration::from_millis(20)).build();
+
+        assert_eq!(reader.get_all(), Err(TemperatureError::Timeout));
+    }
+
+    #[test]
+    fn test_timeout_without_deadline() {
+        let reader = CpuTemperature::builder().provider(TwoZones).timeout(Duration::MAX).build();
+
+        assert_eq!(reader.get_all().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_timeout_stops_deadline_aware_provider() {
+        let finished = Arc::new(AtomicBool::new(false));
+        let reader = CpuTemperature::builder().provider(DeadlineProvider { finished: Arc::clone(&finished) }).timeout(Duration::from_millis(20)).build();
+
+        assert_eq!(reader.get_all(), Err(TemperatureError::Timeout));
+        thread::sleep(Duration::from_millis(50));
+        assert!(finished.load(Ordering::SeqCst), "the read outlived its deadline");
+    }
+}
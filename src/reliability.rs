@@ -0,0 +1,114 @@
+//! Success and failure bookkeeping across a session of reads.
+
+use crate::{CpuTemperature, TemperatureError, TemperatureProvider, WmiProvider};
+
+/// A reader that counts successful and failed reads.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::ReliabilityReader;
+///
+/// let mut reader = ReliabilityReader::new();
+/// for _ in 0..10 {
+///     let _ = reader.read();
+/// }
+///
+/// println!(
+///     "temperature sensor: {}/{} reads OK",
+///     reader.success_count(),
+///     reader.success_count() + reader.failure_count()
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ReliabilityReader<P = WmiProvider> {
+    provider: P,
+    success_count: u64,
+    failure_count: u64,
+    last_error: Option<TemperatureError>,
+}
+
+impl ReliabilityReader {
+    /// Creates a reader over the default WMI provider.
+    pub fn new() -> Self {
+        Self::with_provider(WmiProvider)
+    }
+}
+
+impl<P> ReliabilityReader<P> {
+    /// Creates a reader over `provider`.
+    pub fn with_provider(provider: P) -> Self {
+        ReliabilityReader {
+            provider,
+            success_count: 0,
+            failure_count: 0,
+            last_error: None,
+        }
+    }
+
+    /// Returns the number of successful reads.
+    pub fn success_count(&self) -> u64 {
+        self.success_count
+    }
+
+    /// Returns the number of failed reads.
+    pub fn failure_count(&self) -> u64 {
+        self.failure_count
+    }
+
+    /// Returns the error of the most recent failed read, if any read failed.
+    ///
+    /// The error is kept after later successful reads.
+    pub fn last_error(&self) -> Option<&TemperatureError> {
+        self.last_error.as_ref()
+    }
+
+    /// Returns the fraction of reads that succeeded, from `0.0` to `1.0`, or
+    /// `None` before the first read.
+    pub fn success_rate(&self) -> Option<f64> {
+        let total = self.success_count + self.failure_count;
+        (total > 0).then(|| self.success_count as f64 / total as f64)
+    }
+}
+
+impl<P: TemperatureProvider> ReliabilityReader<P> {
+    /// Takes a reading and records whether it succeeded.
+    pub fn read(&mut self) -> Result<CpuTemperature, TemperatureError> {
+        let result = self.provider.read_one();
+        match &result {
+            Ok(_) => self.success_count += 1,
+            Err(e) => {
+                self.failure_count += 1;
+                self.last_error = Some(e.clone());
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockProvider;
+
+    #[test]
+    fn test_counts_and_rate() {
+        let provider = MockProvider::sequence(vec![
+            Ok(CpuTemperature::from_celsius(40.0)),
+            Err("busy".into()),
+            Ok(CpuTemperature::from_celsius(41.0)),
+            Ok(CpuTemperature::from_celsius(42.0)),
+        ]);
+        let mut reader = ReliabilityReader::with_provider(provider);
+        assert_eq!(reader.success_rate(), None);
+
+        for _ in 0..4 {
+            let _ = reader.read();
+        }
+
+        assert_eq!(reader.success_count(), 3);
+        assert_eq!(reader.failure_count(), 1);
+        assert_eq!(reader.last_error(), Some(&"busy".into()));
+        assert_eq!(reader.success_rate(), Some(0.75));
+    }
+}
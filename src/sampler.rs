@@ -0,0 +1,315 @@
+//! A background sampler that fans readings out to many subscribers.
+
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::{CpuTemperature, Smoother, Smoothing, TemperatureError, TemperatureProvider, TimedReading, WmiProvider};
+
+/// The result of one sample, as delivered to subscribers.
+type Sample = Result<CpuTemperature, TemperatureError>;
+
+/// The result of one sample with the moment it was taken, as delivered to
+/// [`SamplerHandle::subscribe_timed`] subscribers.
+type TimedSample = Result<TimedReading, TemperatureError>;
+
+/// A callback registered with [`SamplerHandle::subscribe_with`].
+type Callback = Box<dyn FnMut(&Sample) + Send>;
+
+/// Samples the CPU temperature at a fixed interval on a background thread.
+///
+/// Configure the sampler, [`spawn`](TemperatureSampler::spawn) it once, and
+/// then receive readings through any number of channels or callbacks
+/// registered on the returned [`SamplerHandle`].
+///
+/// Unlike [`CpuTemperature::monitor_to_channel`], one sampler serves every
+/// consumer, so adding a consumer does not add WMI queries.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use tunjukin_suhu_cpu_windows::TemperatureSampler;
+///
+/// let sampler = TemperatureSampler::new(Duration::from_secs(1)).spawn();
+///
+/// sampler.subscribe_with(|sample| {
+///     if let Err(e) = sample {
+///         eprintln!("read failed: {}", e);
+///     }
+/// });
+///
+/// for temp in sampler.subscribe().iter().flatten().take(10) {
+///     println!("{:.1}°C", temp.celsius());
+/// }
+///
+/// sampler.stop();
+/// ```
+#[derive(Debug, Clone)]
+pub struct TemperatureSampler<P = WmiProvider> {
+    provider: P,
+    interval: Duration,
+    smoothing: Option<Smoothing>,
+}
+
+impl TemperatureSampler {
+    /// Creates a sampler over the default WMI provider that reads every
+    /// `interval`.
+    pub fn new(interval: Duration) -> Self {
+        Self::with_provider(WmiProvider, interval)
+    }
+}
+
+impl<P> TemperatureSampler<P> {
+    /// Creates a sampler over `provider` that reads every `interval`.
+    pub fn with_provider(provider: P, interval: Duration) -> Self {
+        TemperatureSampler { provider, interval, smoothing: None }
+    }
+
+    /// Smooths every successful sample with `smoothing` before it is
+    /// delivered, or delivers raw readings with `None`, the default.
+    ///
+    /// Failed reads are delivered as they are and do not reset the filter.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tunjukin_suhu_cpu_windows::{Smoothing, TemperatureSampler};
+    ///
+    /// let sampler = TemperatureSampler::new(Duration::from_secs(1))
+    ///     .smoothing(Some(Smoothing::Exponential { alpha: 0.3 }))
+    ///     .spawn();
+    /// ```
+    pub fn smoothing(mut self, smoothing: Option<Smoothing>) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Returns the time between samples.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+}
+
+impl<P: TemperatureProvider + Send + 'static> TemperatureSampler<P> {
+    /// Starts sampling on a background thread.
+    ///
+    /// A sample is taken immediately and then every `interval`. Failed reads
+    /// are delivered as `Err` values and sampling continues. Samples taken
+    /// before a subscriber registers are not replayed to it.
+    pub fn spawn(self) -> SamplerHandle {
+        let subscribers = Arc::new(Subscribers::default());
+        let (stop, stopped) = mpsc::channel();
+
+        let thread = {
+            let subscribers = Arc::clone(&subscribers);
+            let mut smoother = self.smoothing.map(Smoother::new);
+            thread::spawn(move || loop {
+                let sample = match smoother.as_mut() {
+                    Some(smoother) => self.provider.read_timed().map(|timed| TimedReading { reading: smoother.push(&timed.reading), ..timed }),
+                    None => self.provider.read_timed(),
+                };
+                subscribers.deliver(sample);
+                match stopped.recv_timeout(self.interval) {
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+                }
+            })
+        };
+
+        SamplerHandle {
+            subscribers,
+            stop,
+            thread,
+        }
+    }
+}
+
+/// A running [`TemperatureSampler`].
+///
+/// Dropping the handle stops the sampler after its current sample; use
+/// [`SamplerHandle::stop`] to also wait for the thread to exit.
+#[derive(Debug)]
+pub struct SamplerHandle {
+    subscribers: Arc<Subscribers>,
+    stop: Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+impl SamplerHandle {
+    /// Returns a channel that receives every future sample.
+    ///
+    /// Dropping the receiver unsubscribes it.
+    pub fn subscribe(&self) -> Receiver<Sample> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.lock().channels.push(sender);
+        receiver
+    }
+
+    /// Like [`subscribe`](SamplerHandle::subscribe), but every sample carries
+    /// the moment it was taken on the sampler thread, so consumers that
+    /// fall behind still log and chart it at the right time.
+    pub fn subscribe_timed(&self) -> Receiver<TimedSample> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.lock().timed_channels.push(sender);
+        receiver
+    }
+
+    /// Calls `callback` with every future sample on the sampler thread.
+    ///
+    /// Callbacks run one after another before the next sample is taken, so
+    /// a slow callback delays every subscriber. Hand expensive work off to
+    /// another thread.
+    pub fn subscribe_with(&self, callback: impl FnMut(&Sample) + Send + 'static) {
+        self.subscribers.lock().callbacks.push(Box::new(callback));
+    }
+
+    /// Returns the number of live subscribers.
+    ///
+    /// Channel subscribers whose receiver was dropped are counted until the
+    /// next sample finds them gone.
+    pub fn subscriber_count(&self) -> usize {
+        let subscribers = self.subscribers.lock();
+        subscribers.channels.len() + subscribers.timed_channels.len() + subscribers.callbacks.len()
+    }
+
+    /// Stops the sampler and waits for its thread to exit.
+    ///
+    /// If a sample is being taken, this waits for that query to finish.
+    pub fn stop(self) {
+        let _ = self.stop.send(());
+        let _ = self.thread.join();
+    }
+}
+
+/// Everyone who receives samples.
+#[derive(Default)]
+struct Subscribers {
+    inner: Mutex<SubscriberList>,
+}
+
+#[derive(Default)]
+struct SubscriberList {
+    channels: Vec<Sender<Sample>>,
+    timed_channels: Vec<Sender<TimedSample>>,
+    callbacks: Vec<Callback>,
+}
+
+impl Subscribers {
+    /// Sends `timed` to every subscriber, dropping closed channels.
+    fn deliver(&self, timed: TimedSample) {
+        let mut subscribers = self.lock();
+        subscribers.timed_channels.retain(|channel| channel.send(timed.clone()).is_ok());
+        let sample = timed.map(|timed| timed.reading);
+        subscribers.channels.retain(|channel| channel.send(sample.clone()).is_ok());
+        for callback in &mut subscribers.callbacks {
+            callback(&sample);
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SubscriberList> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl std::fmt::Debug for Subscribers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let subscribers = self.lock();
+        f.debug_struct("Subscribers")
+            .field("channels", &subscribers.channels.len())
+            .field("timed_channels", &subscribers.timed_channels.len())
+            .field("callbacks", &subscribers.callbacks.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockProvider;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_fans_out_to_channels_and_callbacks() {
+        let provider = MockProvider::fixed(CpuTemperature::from_celsius(45.0));
+        let sampler = TemperatureSampler::with_provider(provider, Duration::from_millis(1)).spawn();
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        sampler.subscribe_with(move |sample| {
+            assert!(sample.is_ok());
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        let first = sampler.subscribe();
+        let second = sampler.subscribe();
+        assert_eq!(sampler.subscriber_count(), 3);
+
+        for receiver in [&first, &second] {
+            for _ in 0..3 {
+                assert_eq!(receiver.recv().unwrap(), Ok(CpuTemperature::from_celsius(45.0)));
+            }
+        }
+
+        sampler.stop();
+        assert!(calls.load(Ordering::SeqCst) >= 3);
+    }
+
+    #[test]
+    fn test_timed_samples() {
+        let provider = MockProvider::fixed(CpuTemperature::from_celsius(45.0));
+        let sampler = TemperatureSampler::with_provider(provider, Duration::from_millis(1)).spawn();
+
+        let timed = sampler.subscribe_timed();
+        let first = timed.recv().unwrap().unwrap();
+        let second = timed.recv().unwrap().unwrap();
+        assert_eq!(first.reading, CpuTemperature::from_celsius(45.0));
+        assert!(second.at > first.at);
+        assert!(second.time >= first.time);
+
+        sampler.stop();
+    }
+
+    #[test]
+    fn test_dropped_receivers_are_removed() {
+        let sampler = TemperatureSampler::with_provider(MockProvider::failing("busy"), Duration::from_millis(1)).spawn();
+
+        let kept = sampler.subscribe();
+        drop(sampler.subscribe());
+        assert_eq!(kept.recv().unwrap(), Err("busy".into()));
+        assert_eq!(kept.recv().unwrap(), Err("busy".into()));
+
+        assert_eq!(sampler.subscriber_count(), 1);
+        sampler.stop();
+    }
+
+    #[test]
+    fn test_smoothing() {
+        let readings = [40.0, 48.0, 48.0, 48.0, 48.0, 48.0];
+        let provider = MockProvider::sequence(readings.map(|c| Ok(CpuTemperature::from_celsius(c))));
+        let sampler = TemperatureSampler::with_provider(provider, Duration::from_millis(10))
+            .smoothing(Some(Smoothing::Exponential { alpha: 0.5 }))
+            .spawn();
+
+        // Samples taken before subscribing are missed, but every one received
+        // is still on its way up from 40°C; the exhausted mock ends the run.
+        let smoothed: Vec<f64> = sampler.subscribe().iter().map_while(Result::ok).map(|t| t.celsius()).collect();
+        assert!(!smoothed.is_empty());
+        assert!(smoothed.iter().all(|&c| c < 48.0));
+        assert!(smoothed.windows(2).all(|pair| pair[0] < pair[1]));
+        sampler.stop();
+    }
+
+    #[test]
+    fn test_stop_interrupts_the_interval() {
+        let provider = Arc::new(MockProvider::fixed(CpuTemperature::from_celsius(45.0)));
+        let sampler = TemperatureSampler::with_provider(Arc::clone(&provider), Duration::from_secs(3600)).spawn();
+
+        let started = std::time::Instant::now();
+        sampler.stop();
+
+        assert!(started.elapsed() < Duration::from_secs(60));
+        assert_eq!(provider.call_count(), 1);
+    }
+}
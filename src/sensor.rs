@@ -0,0 +1,152 @@
+//! Readings of sensors other than the CPU's thermal zones, such as GPU
+//! temperatures, fan speeds and voltages.
+//!
+//! [`ThermalZoneReading`](crate::ThermalZoneReading) says nothing about what
+//! it measured, which is fine while every reading is a CPU temperature. A
+//! [`SensorReading`] also carries a [`SensorKind`], so readings from several
+//! kinds of hardware can be collected into one list.
+
+use std::fmt;
+use std::time::{Instant, SystemTime};
+
+use crate::{CpuTemperature, TemperatureError, TemperatureProvider, ThermalZoneReading};
+
+/// What a [`SensorReading`] measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum SensorKind {
+    /// A CPU temperature, in degrees Celsius.
+    Cpu,
+    /// A GPU temperature, in degrees Celsius.
+    Gpu,
+    /// A drive temperature, in degrees Celsius.
+    Disk,
+    /// A battery temperature, in degrees Celsius.
+    Battery,
+    /// A fan speed, in revolutions per minute.
+    Fan,
+    /// A voltage, in volts.
+    Voltage,
+}
+
+impl SensorKind {
+    /// Returns the lowercase name of the kind, such as `"gpu"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SensorKind::Cpu => "cpu",
+            SensorKind::Gpu => "gpu",
+            SensorKind::Disk => "disk",
+            SensorKind::Battery => "battery",
+            SensorKind::Fan => "fan",
+            SensorKind::Voltage => "voltage",
+        }
+    }
+
+    /// Returns the symbol of the unit readings of this kind are in, such as
+    /// `"°C"`.
+    pub fn unit(self) -> &'static str {
+        match self {
+            SensorKind::Cpu | SensorKind::Gpu | SensorKind::Disk | SensorKind::Battery => "°C",
+            SensorKind::Fan => "RPM",
+            SensorKind::Voltage => "V",
+        }
+    }
+}
+
+impl fmt::Display for SensorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One reading of one sensor.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SensorReading {
+    /// What the sensor measures.
+    pub kind: SensorKind,
+    /// The sensor's name as its backend reports it, such as
+    /// `GPU 0: NVIDIA GeForce RTX 3080`, or `None` if it has none.
+    pub name: Option<String>,
+    /// The value, in the [unit](SensorKind::unit) of the kind.
+    pub value: f64,
+    /// When the sensor was read. Not serialized; deserialized readings are
+    /// stamped with the moment they were deserialized.
+    #[cfg_attr(feature = "serde", serde(skip, default = "Instant::now"))]
+    pub at: Instant,
+    /// When the sensor was read, on the system clock.
+    pub time: SystemTime,
+}
+
+impl SensorReading {
+    /// Creates a reading of a sensor taken now.
+    pub fn new(kind: SensorKind, name: Option<String>, value: f64) -> Self {
+        SensorReading { kind, name, value, at: Instant::now(), time: SystemTime::now() }
+    }
+
+
+    /// Tags a thermal zone reading with `kind`, keeping when it was taken.
+    pub fn from_zone(kind: SensorKind, zone: ThermalZoneReading) -> Self {
+        SensorReading { kind, name: zone.instance_name, value: zone.temperature.celsius(), at: zone.at, time: zone.time }
+    }
+
+    /// Returns the value as a temperature, or `None` if the sensor does not
+    /// measure one.
+    pub fn temperature(&self) -> Option<CpuTemperature> {
+        match self.kind {
+            SensorKind::Cpu | SensorKind::Gpu | SensorKind::Disk | SensorKind::Battery => Some(CpuTemperature::from_celsius(self.value)),
+            SensorKind::Fan | SensorKind::Voltage => None,
+        }
+    }
+}
+
+/// A backend whose readings carry the kind of sensor they came from.
+///
+/// Providers of temperatures other than the CPU's, such as
+/// [`GpuTemperature`](crate::GpuTemperature) or
+/// [`BatteryTemperature`](crate::BatteryTemperature), implement this next to
+/// [`TemperatureProvider`]. Both return one reading per sensor, and this one
+/// tags every reading with the provider's [`SensorKind`], so readings from
+/// several of them can be merged without losing what each one measured.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::{AmdGpuProvider, GpuTemperature, SensorProvider};
+///
+/// let providers: [&dyn SensorProvider; 2] = [&GpuTemperature::new(), &AmdGpuProvider];
+/// for reading in providers.iter().filter_map(|provider| provider.read_sensors().ok()).flatten() {
+///     println!("{} {}: {:.0} {}", reading.kind, reading.name.unwrap_or_default(), reading.value, reading.kind.unit());
+/// }
+/// ```
+pub trait SensorProvider {
+    /// Reads every sensor the provider knows about.
+    ///
+    /// Implementations should return an error rather than an empty vector
+    /// when nothing could be read.
+    fn read_sensors(&self) -> Result<Vec<SensorReading>, TemperatureError>;
+}
+
+/// Reads `provider` and tags every reading with `kind`, for providers that
+/// implement [`SensorProvider`] on top of [`TemperatureProvider`].
+pub(crate) fn read_tagged(provider: &impl TemperatureProvider, kind: SensorKind) -> Result<Vec<SensorReading>, TemperatureError> {
+    Ok(provider.read()?.into_iter().map(|zone| SensorReading::from_zone(kind, zone)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_zone() {
+        let zone = ThermalZoneReading::new(Some("GPU Core".to_string()), CpuTemperature::from_celsius(61.0));
+
+        let reading = SensorReading::from_zone(SensorKind::Gpu, zone);
+        assert_eq!(reading.kind, SensorKind::Gpu);
+        assert_eq!(reading.name.as_deref(), Some("GPU Core"));
+        assert_eq!(reading.value, 61.0);
+        assert_eq!(reading.temperature(), Some(CpuTemperature::from_celsius(61.0)));
+        assert_eq!(reading.kind.to_string(), "gpu");
+    }
+}
@@ -0,0 +1,280 @@
+//! Enumerating every sensor the backends of this crate can find.
+
+use std::thread;
+
+use crate::sensor::read_tagged;
+use crate::{
+    AmdGpuProvider, BatteryTemperature, DiskTemperature, FanSpeed, GpuTemperature, NvmeTemperature, ProviderKind, SensorKind, SensorProvider,
+    SensorReading, TemperatureError, VoltageProvider,
+};
+
+/// A backend listed by [`Sensors::list`], named like its sensors' provider.
+struct Source {
+    provider: &'static str,
+    read: fn() -> Result<Vec<SensorReading>, TemperatureError>,
+}
+
+/// Every backend, CPU backends first, so the first listed sensor is the one
+/// [`CpuTemperature::get`](crate::CpuTemperature::get) would read. Native WMI
+/// and the performance counters are left out, since they read the same zones
+/// as `acpi`, and so is `msr`, since reading it loads a kernel driver.
+const SOURCES: &[Source] = &[
+    Source { provider: "acpi", read: || cpu(ProviderKind::Acpi) },
+    Source { provider: "probe", read: || cpu(ProviderKind::Probe) },
+    Source { provider: "libre-hardware-monitor", read: || cpu(ProviderKind::LibreHardwareMonitor) },
+    Source { provider: "open-hardware-monitor", read: || cpu(ProviderKind::OpenHardwareMonitor) },
+    Source { provider: "core-temp", read: || cpu(ProviderKind::CoreTemp) },
+    #[cfg(all(windows, feature = "shared-memory"))]
+    Source { provider: "hwinfo", read: || crate::HwInfoProvider.read_sensors() },
+    #[cfg(all(windows, feature = "shared-memory"))]
+    Source { provider: "aida64", read: || crate::Aida64Provider.read_sensors() },
+    Source { provider: "nvidia-smi", read: || GpuTemperature::new().read_sensors() },
+    Source { provider: "amd-gpu", read: || AmdGpuProvider.read_sensors() },
+    Source { provider: "smart", read: || DiskTemperature.read_sensors() },
+    Source { provider: "nvme", read: || NvmeTemperature.read_sensors() },
+    Source { provider: "battery", read: || BatteryTemperature.read_sensors() },
+    Source { provider: "fan", read: || Ok(FanSpeed::get_all()?.into_iter().map(SensorReading::from).collect()) },
+    Source { provider: "voltage", read: || VoltageProvider.read_sensors() },
+];
+
+/// Reads the CPU backend `kind`, tagging its readings [`SensorKind::Cpu`].
+fn cpu(kind: ProviderKind) -> Result<Vec<SensorReading>, TemperatureError> {
+    read_tagged(&kind.provider()?, SensorKind::Cpu)
+}
+
+/// What [`Sensors::list`] knows about one sensor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SensorInfo {
+    /// An identifier that stays the same between calls: the provider and
+    /// the name the backend reports, such as `acpi/ACPI\ThermalZone\TZ01_0`,
+    /// or the provider and the sensor's position if it has no name.
+    pub id: String,
+    /// A name to show users, such as `TZ01` or `CPU Package`.
+    pub name: String,
+    /// What the sensor measures.
+    pub kind: SensorKind,
+    /// The backend that found the sensor, such as `acpi`, `hwinfo` or
+    /// `nvidia-smi`. CPU backends are named as parsed by [`ProviderKind`].
+    pub provider: &'static str,
+    /// The symbol of the unit of the sensor's values, such as `"°C"`.
+    pub unit: &'static str,
+}
+
+impl SensorInfo {
+    /// Describes `reading`, the `index`th sensor found by `provider`.
+    fn new(provider: &'static str, index: usize, reading: &SensorReading) -> Self {
+        let (id, name) = match &reading.name {
+            Some(name) => (format!("{}/{}", provider, name), friendly_name(name).to_string()),
+            None => (format!("{}/{}", provider, index), format!("{} {}", reading.kind, index + 1)),
+        };
+        SensorInfo { id, name, kind: reading.kind, provider, unit: reading.kind.unit() }
+    }
+}
+
+/// Shortens an ACPI thermal zone name such as `ACPI\ThermalZone\TZ01_0` to
+/// `TZ01`, and returns other names as they are.
+fn friendly_name(name: &str) -> &str {
+    let Some(zone) = name.strip_prefix(r"ACPI\ThermalZone\") else {
+        return name;
+    };
+    match zone.rsplit_once('_') {
+        Some((short, instance)) if !short.is_empty() && !instance.is_empty() && instance.bytes().all(|b| b.is_ascii_digit()) => short,
+        _ => zone,
+    }
+}
+
+/// Every sensor on the machine, across all backends.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::Sensors;
+///
+/// for sensor in Sensors::list()? {
+///     println!("{:<40} {:<24} {} ({})", sensor.id, sensor.name, sensor.kind, sensor.unit);
+/// }
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sensors;
+
+impl Sensors {
+    /// Lists every sensor any backend finds, such as for a UI letting users
+    /// pick which sensor to chart.
+    ///
+    /// Every backend is read once, in parallel, and those that fail or are
+    /// missing from this build are left out. A sensor found by two
+    /// backends, such as a CPU package reported by both HWiNFO and
+    /// LibreHardwareMonitor, is listed once per backend.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the error of the first backend if no backend finds a
+    /// sensor.
+    pub fn list() -> Result<Vec<SensorInfo>, TemperatureError> {
+        list_from(SOURCES)
+    }
+
+    /// Reads one sensor, named by its [`id`](SensorInfo::id), by the name
+    /// its backend reports, such as `ACPI\ThermalZone\TZ01_0`, or by its
+    /// friendly [`name`](SensorInfo::name), ignoring case.
+    ///
+    /// This pins a particular zone where [`CpuTemperature::get`] takes the
+    /// first one, which on some desktops is a chipset zone rather than the
+    /// CPU. An id only reads the backend it names; any other name reads
+    /// every backend and returns the first match, in the order of
+    /// [`list`](Sensors::list).
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`TemperatureError::InvalidArgument`] if no sensor has the
+    /// name, or with the backend's error if every backend read fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tunjukin_suhu_cpu_windows::Sensors;
+    ///
+    /// let zone = Sensors::read(r"ACPI\ThermalZone\TZ01_0")?;
+    /// println!("{:.1}{}", zone.value, zone.kind.unit());
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    ///
+    /// [`CpuTemperature::get`]: crate::CpuTemperature::get
+    pub fn read(sensor: &str) -> Result<SensorReading, TemperatureError> {
+        read_from(SOURCES, sensor)
+    }
+}
+
+/// Reads every source of `sources` in parallel, returning the results in
+/// order.
+fn read_all(sources: &[&Source]) -> Vec<Result<Vec<SensorReading>, TemperatureError>> {
+    thread::scope(|scope| {
+        let reads: Vec<_> = sources.iter().map(|source| scope.spawn(source.read)).collect();
+        reads.into_iter().map(|read| read.join().unwrap_or(Err(TemperatureError::Other("reading a sensor backend panicked".to_string())))).collect()
+    })
+}
+
+/// Lists the sensors of `sources`, in order.
+fn list_from(sources: &[Source]) -> Result<Vec<SensorInfo>, TemperatureError> {
+    let sources: Vec<&Source> = sources.iter().collect();
+    let mut sensors = Vec::new();
+    let mut first_error = None;
+    for (source, result) in sources.iter().zip(read_all(&sources)) {
+        match result {
+            Ok(readings) => sensors.extend(readings.iter().enumerate().map(|(index, reading)| SensorInfo::new(source.provider, index, reading))),
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    if sensors.is_empty() {
+        return Err(first_error.unwrap_or(TemperatureError::NoSensors));
+    }
+
+    Ok(sensors)
+}
+
+/// Reads the first sensor of `sources` that `sensor` names. An id only
+/// reads the source of its provider.
+fn read_from(sources: &[Source], sensor: &str) -> Result<SensorReading, TemperatureError> {
+    let pinned = sources.iter().find(|source| sensor.strip_prefix(source.provider).is_some_and(|rest| rest.starts_with('/')));
+    let sources: Vec<&Source> = match pinned {
+        Some(source) => vec![source],
+        None => sources.iter().collect(),
+    };
+
+    let mut first_error = None;
+    let mut found_any = false;
+    for (source, result) in sources.iter().zip(read_all(&sources)) {
+        let readings = match result {
+            Ok(readings) => readings,
+            Err(e) => {
+                first_error.get_or_insert(e);
+                continue;
+            }
+        };
+        found_any = true;
+        for (index, reading) in readings.into_iter().enumerate() {
+            let info = SensorInfo::new(source.provider, index, &reading);
+            if info.id == sensor || reading.name.as_deref() == Some(sensor) || info.name.eq_ignore_ascii_case(sensor) {
+                return Ok(reading);
+            }
+        }
+    }
+
+    match first_error {
+        Some(e) if !found_any => Err(e),
+        _ => Err(TemperatureError::InvalidArgument(format!("no sensor named '{}'", sensor))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_describes_sensors() {
+        let sources = [
+            Source { provider: "acpi", read: || Err(TemperatureError::AccessDenied { details: "Access denied".to_string() }) },
+            Source {
+                provider: "hwinfo",
+                read: || {
+                    Ok(vec![
+                        SensorReading::new(SensorKind::Cpu, Some(r"ACPI\ThermalZone\TZ01_0".to_string()), 48.0),
+                        SensorReading::new(SensorKind::Fan, None, 1200.0),
+                    ])
+                },
+            },
+        ];
+
+        let sensors = list_from(&sources).unwrap();
+        assert_eq!(
+            sensors,
+            [
+                SensorInfo {
+                    id: r"hwinfo/ACPI\ThermalZone\TZ01_0".to_string(),
+                    name: "TZ01".to_string(),
+                    kind: SensorKind::Cpu,
+                    provider: "hwinfo",
+                    unit: "°C",
+                },
+                SensorInfo { id: "hwinfo/1".to_string(), name: "fan 2".to_string(), kind: SensorKind::Fan, provider: "hwinfo", unit: "RPM" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_by_id_or_name() {
+        let sources = [
+            Source { provider: "acpi", read: || Ok(vec![SensorReading::new(SensorKind::Cpu, Some(r"ACPI\ThermalZone\TZ00_0".to_string()), 28.0)]) },
+            Source {
+                provider: "hwinfo",
+                read: || {
+                    Ok(vec![
+                        SensorReading::new(SensorKind::Cpu, Some(r"ACPI\ThermalZone\TZ00_0".to_string()), 29.0),
+                        SensorReading::new(SensorKind::Cpu, Some("CPU Package".to_string()), 64.0),
+                    ])
+                },
+            },
+        ];
+
+        assert_eq!(read_from(&sources, r"ACPI\ThermalZone\TZ00_0").unwrap().value, 28.0);
+        assert_eq!(read_from(&sources, r"hwinfo/ACPI\ThermalZone\TZ00_0").unwrap().value, 29.0);
+        assert_eq!(read_from(&sources, "cpu package").unwrap().value, 64.0);
+        assert!(matches!(read_from(&sources, "TZ01"), Err(TemperatureError::InvalidArgument(message)) if message == "no sensor named 'TZ01'"));
+    }
+
+    #[test]
+    fn test_list_fails_with_first_error() {
+        let sources = [
+            Source { provider: "acpi", read: || Err(TemperatureError::Timeout) },
+            Source { provider: "smart", read: || Err(TemperatureError::NoSensors) },
+        ];
+
+        assert_eq!(list_from(&sources), Err(TemperatureError::Timeout));
+        assert_eq!(read_from(&sources, "TZ01"), Err(TemperatureError::Timeout));
+        assert_eq!(friendly_name("CPU Package"), "CPU Package");
+    }
+}
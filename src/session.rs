@@ -0,0 +1,251 @@
+//! A long-lived PowerShell process for repeated reads.
+//!
+//! Starting PowerShell costs far more than the query it runs, so polling
+//! once a second with [`CpuTemperature::get`] spends most of its time
+//! booting shells. A [`TemperatureSession`] starts one PowerShell reading
+//! commands from standard input and sends every query to it. Each query is
+//! followed by an end marker on standard output, which tells the session
+//! where one response stops.
+
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Stdio};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::{limit, shell_command, spawn_error, with_powershell, CpuTemperature, TemperatureError, TemperatureProvider, ThermalZoneReading, ACPI_QUERY};
+
+/// Printed after every response.
+const END_MARKER: &str = "<<tunjukin-suhu:end>>";
+
+/// Printed before the message of a failed query.
+const ERROR_MARKER: &str = "<<tunjukin-suhu:error>>";
+
+/// Reads the ACPI thermal zones through one persistent PowerShell process.
+///
+/// The process is started by [`TemperatureSession::new`] and kept running
+/// until the session is dropped. If it dies, for example because it was
+/// killed from outside, the failing read returns an error and the next read
+/// starts a new process.
+///
+/// A session reads only the ACPI thermal zones through PowerShell: unlike
+/// [`CpuTemperature::get`], it neither uses the `native` backend nor falls
+/// back to other sensor classes. Reads through a shared session are
+/// serialized.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::thread;
+/// use std::time::Duration;
+/// use tunjukin_suhu_cpu_windows::TemperatureSession;
+///
+/// let session = TemperatureSession::new()?;
+///
+/// for _ in 0..60 {
+///     println!("{:.1}°C", session.get()?.celsius());
+///     thread::sleep(Duration::from_secs(1));
+/// }
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug)]
+pub struct TemperatureSession {
+    shell: Mutex<Option<Shell>>,
+}
+
+impl TemperatureSession {
+    /// Starts the PowerShell process.
+    ///
+    /// # Errors
+    ///
+    /// Fails if neither Windows PowerShell nor PowerShell 7 can be started.
+    pub fn new() -> Result<Self, TemperatureError> {
+        Ok(TemperatureSession { shell: Mutex::new(Some(Shell::spawn()?)) })
+    }
+
+    /// Reads the first valid thermal zone, like [`CpuTemperature::get`].
+    pub fn get(&self) -> Result<CpuTemperature, TemperatureError> {
+        CpuTemperature::read_with(|| self.query(ACPI_QUERY))
+    }
+
+    /// Reads every valid thermal zone, like [`CpuTemperature::get_all`].
+    pub fn get_all(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        let output = self.query(ACPI_QUERY)?;
+        let zones = CpuTemperature::parse_zones(&output);
+        if zones.is_empty() {
+            return Err(CpuTemperature::no_readings_error(&output));
+        }
+
+        Ok(zones
+            .into_iter()
+            .map(|(instance_name, temperature)| ThermalZoneReading::new(instance_name, temperature))
+            .collect())
+    }
+
+    /// Runs `cmd` in the session's PowerShell and returns its output,
+    /// restarting the process first if an earlier query lost it.
+    fn query(&self, cmd: &str) -> Result<String, TemperatureError> {
+        let _permit = limit::acquire();
+        let mut shell = self.lock();
+
+        let result = match shell.as_mut() {
+            Some(running) => running.query(cmd),
+            None => shell.insert(Shell::spawn()?).query(cmd),
+        };
+        if matches!(result, Err(TemperatureError::Other(_))) {
+            *shell = None;
+        }
+
+        result
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<Shell>> {
+        self.shell.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl TemperatureProvider for TemperatureSession {
+    fn read(&self) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        self.get_all()
+    }
+
+    fn read_one(&self) -> Result<CpuTemperature, TemperatureError> {
+        self.get()
+    }
+}
+
+/// A running PowerShell that executes one line of standard input at a time.
+#[derive(Debug)]
+struct Shell {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Shell {
+    fn spawn() -> Result<Self, TemperatureError> {
+        with_powershell(|shell| {
+            let mut child = shell_command(shell, "-")
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .map_err(spawn_error)?;
+
+            let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(TemperatureError::Other("PowerShell session pipes are unavailable".to_string()));
+            };
+
+            Ok(Shell { child, stdin, stdout: BufReader::new(stdout) })
+        })
+    }
+
+    /// Sends `cmd` and waits for its response.
+    ///
+    /// A broken pipe is reported as [`TemperatureError::Other`], which tells
+    /// the session to restart the process.
+    fn query(&mut self, cmd: &str) -> Result<String, TemperatureError> {
+        let lost = |e: std::io::Error| TemperatureError::Other(format!("PowerShell session lost: {}", e));
+
+        writeln!(self.stdin, "{}", wrap_command(cmd)).map_err(lost)?;
+        self.stdin.flush().map_err(lost)?;
+
+        read_response(&mut self.stdout)
+    }
+}
+
+impl Drop for Shell {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Wraps `cmd` so that its output, or its error, is followed by the end
+/// marker on a single line of input.
+fn wrap_command(cmd: &str) -> String {
+    format!(
+        "try {{ $ErrorActionPreference = 'Stop'; {} | Out-String -Width 4096 }} catch {{ '{}' + $_ }}; '{}'",
+        cmd, ERROR_MARKER, END_MARKER
+    )
+}
+
+/// Reads one response written by a command from [`wrap_command`].
+fn read_response(stdout: &mut impl BufRead) -> Result<String, TemperatureError> {
+    let mut output = String::new();
+    let mut error: Option<String> = None;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        let read = stdout
+            .read_line(&mut line)
+            .map_err(|e| TemperatureError::Other(format!("PowerShell session lost: {}", e)))?;
+        if read == 0 {
+            return Err(TemperatureError::Other("PowerShell session exited unexpectedly".to_string()));
+        }
+
+        let text = line.trim_end_matches(['\r', '\n']);
+        if text == END_MARKER {
+            break;
+        }
+
+        match (&mut error, text.strip_prefix(ERROR_MARKER)) {
+            (None, Some(message)) => error = Some(message.to_string()),
+            (Some(message), _) => {
+                message.push('\n');
+                message.push_str(text);
+            }
+            (None, None) => {
+                output.push_str(text);
+                output.push('\n');
+            }
+        }
+    }
+
+    match error {
+        Some(message) => Err(TemperatureError::query_failed(None, &message)),
+        None => Ok(output),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_response_stops_at_marker() {
+        let mut stdout = Cursor::new(format!("\r\nCurrentTemperature : 3132\r\n{}\r\nnext response\r\n", END_MARKER));
+
+        assert_eq!(read_response(&mut stdout).unwrap(), "\nCurrentTemperature : 3132\n");
+
+        let mut rest = String::new();
+        stdout.read_line(&mut rest).unwrap();
+        assert_eq!(rest, "next response\r\n");
+    }
+
+    #[test]
+    fn test_read_response_reports_query_errors() {
+        let mut stdout = Cursor::new(format!("{}Access denied\nat line 1\n{}\n", ERROR_MARKER, END_MARKER));
+
+        assert_eq!(
+            read_response(&mut stdout),
+            Err(TemperatureError::AccessDenied { details: "Access denied\nat line 1".to_string() })
+        );
+    }
+
+    #[test]
+    fn test_read_response_detects_exited_shell() {
+        let mut stdout = Cursor::new("CurrentTemperature : 3132\n");
+        assert!(matches!(read_response(&mut stdout), Err(TemperatureError::Other(_))));
+    }
+
+    #[test]
+    fn test_wrap_command_is_one_line() {
+        let wrapped = wrap_command(ACPI_QUERY);
+        assert!(!wrapped.contains('\n'));
+        assert!(wrapped.contains(ACPI_QUERY));
+        assert!(wrapped.ends_with(&format!("'{}'", END_MARKER)));
+    }
+}
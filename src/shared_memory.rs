@@ -0,0 +1,65 @@
+//! Reading the named shared memory blocks that sensor tools publish.
+//!
+//! Core Temp, HWiNFO and AIDA64 each publish their readings in a named file
+//! mapping while they run. This copies a whole mapping out so the providers
+//! can parse it as plain bytes.
+
+use std::mem;
+use std::slice;
+
+use windows::core::HSTRING;
+use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::System::Memory::{MapViewOfFile, OpenFileMappingW, UnmapViewOfFile, VirtualQuery, FILE_MAP_READ, MEMORY_BASIC_INFORMATION};
+
+use crate::TemperatureError;
+
+/// Copies the contents of the file mapping called `name`.
+///
+/// Fails with [`TemperatureError::NoSensors`] if no such mapping exists,
+/// which means the tool that publishes it is not running.
+pub(crate) fn read(name: &str) -> Result<Vec<u8>, TemperatureError> {
+    // SAFETY: the view is only read within the region VirtualQuery reports,
+    // and is unmapped and closed exactly once.
+    unsafe {
+        let mapping = OpenFileMappingW(FILE_MAP_READ.0, false, &HSTRING::from(name)).map_err(|_| TemperatureError::NoSensors)?;
+        let view = MapViewOfFile(mapping, FILE_MAP_READ, 0, 0, 0);
+        if view.Value.is_null() {
+            let _ = CloseHandle(mapping);
+            return Err(TemperatureError::Other(format!("Failed to map the {} shared memory", name)));
+        }
+
+        let mut region = MEMORY_BASIC_INFORMATION::default();
+        let written = VirtualQuery(Some(view.Value), &mut region, mem::size_of::<MEMORY_BASIC_INFORMATION>());
+        let bytes = match written {
+            0 => Vec::new(),
+            _ => slice::from_raw_parts(view.Value as *const u8, region.RegionSize).to_vec(),
+        };
+
+        let _ = UnmapViewOfFile(view);
+        let _ = CloseHandle(mapping);
+        Ok(bytes)
+    }
+}
+
+/// Reads a little-endian `u32` at `offset`, or `None` past the end.
+pub(crate) fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+/// Reads a little-endian `f32` at `offset`, or `None` past the end.
+pub(crate) fn f32_at(bytes: &[u8], offset: usize) -> Option<f32> {
+    u32_at(bytes, offset).map(f32::from_bits)
+}
+
+/// Reads a little-endian `f64` at `offset`, or `None` past the end.
+pub(crate) fn f64_at(bytes: &[u8], offset: usize) -> Option<f64> {
+    Some(f64::from_le_bytes(bytes.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+/// Reads a NUL-terminated string from the `len` bytes at `offset`, or `None`
+/// past the end. Bytes that are not UTF-8 are replaced.
+pub(crate) fn str_at(bytes: &[u8], offset: usize, len: usize) -> Option<String> {
+    let field = bytes.get(offset..offset + len)?;
+    let end = field.iter().position(|&byte| byte == 0).unwrap_or(len);
+    Some(String::from_utf8_lossy(&field[..end]).trim().to_string())
+}
@@ -0,0 +1,251 @@
+//! Smoothing filters for noisy thermal zone readings.
+
+use std::collections::VecDeque;
+
+use crate::{CpuTemperature, TemperatureError, TemperatureProvider, WmiProvider};
+
+/// A simple moving-average filter over the last `window` readings.
+///
+/// Each [`WindowSmoother::update`] takes a new reading and returns the mean
+/// of the most recent `window` readings. Readings older than the window have
+/// no influence at all, which makes the filter easy to reason about. Until
+/// the window has filled up the mean covers fewer readings; check
+/// [`WindowSmoother::count`] or [`WindowSmoother::is_full`] to tell.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::WindowSmoother;
+///
+/// let mut smoother = WindowSmoother::new(5);
+/// let smoothed = smoother.update()?;
+/// println!("{:.1}°C averaged over {} readings", smoothed.celsius(), smoother.count());
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct WindowSmoother<P = WmiProvider> {
+    provider: P,
+    window: usize,
+    readings: VecDeque<f64>,
+}
+
+impl WindowSmoother {
+    /// Creates a smoother over the default WMI provider.
+    ///
+    /// A `window` of `0` is treated as `1`.
+    pub fn new(window: usize) -> Self {
+        Self::with_provider(WmiProvider, window)
+    }
+}
+
+impl<P> WindowSmoother<P> {
+    /// Creates a smoother over `provider`.
+    ///
+    /// A `window` of `0` is treated as `1`.
+    pub fn with_provider(provider: P, window: usize) -> Self {
+        let window = window.max(1);
+        WindowSmoother {
+            provider,
+            window,
+            readings: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// Adds a reading obtained elsewhere and returns the new mean.
+    pub fn push(&mut self, reading: &CpuTemperature) -> CpuTemperature {
+        if self.readings.len() == self.window {
+            self.readings.pop_front();
+        }
+        self.readings.push_back(reading.celsius());
+
+        let mean = self.readings.iter().sum::<f64>() / self.readings.len() as f64;
+        CpuTemperature::from_celsius(mean)
+    }
+
+    /// Returns the number of readings currently averaged.
+    pub fn count(&self) -> usize {
+        self.readings.len()
+    }
+
+    /// Returns the configured window size.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Returns `true` once `window` readings have been collected.
+    pub fn is_full(&self) -> bool {
+        self.readings.len() == self.window
+    }
+}
+
+impl<P: TemperatureProvider> WindowSmoother<P> {
+    /// Takes a new reading and returns the mean of the current window.
+    ///
+    /// If the read fails the error is returned and the window is left
+    /// unchanged.
+    pub fn update(&mut self) -> Result<CpuTemperature, TemperatureError> {
+        let reading = self.provider.read_one()?;
+        Ok(self.push(&reading))
+    }
+}
+
+/// A smoothing filter that [`TemperatureSampler`](crate::TemperatureSampler)
+/// can apply to its samples, or a [`Smoother`] to readings pushed to it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Smoothing {
+    /// An exponential moving average: each output moves `alpha` of the way
+    /// from the previous output towards the new reading. Smaller values
+    /// smooth more and lag more; `0.2` to `0.3` calms ±3 °C jitter at 1 Hz
+    /// while still following a load change within seconds.
+    ///
+    /// An `alpha` outside `(0, 1]` is treated as `1`, which disables
+    /// smoothing.
+    Exponential { alpha: f64 },
+    /// The median of the last `window` readings. Unlike a mean, a median
+    /// ignores isolated spikes entirely.
+    ///
+    /// A `window` of `0` is treated as `1`.
+    Median { window: usize },
+}
+
+/// Applies a [`Smoothing`] filter to a stream of readings.
+///
+/// # Example
+///
+/// ```
+/// use tunjukin_suhu_cpu_windows::{CpuTemperature, Smoother, Smoothing};
+///
+/// let mut smoother = Smoother::new(Smoothing::Exponential { alpha: 0.5 });
+///
+/// assert_eq!(smoother.push(&CpuTemperature::from_celsius(40.0)).celsius(), 40.0);
+/// assert_eq!(smoother.push(&CpuTemperature::from_celsius(50.0)).celsius(), 45.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Smoother {
+    smoothing: Smoothing,
+    state: SmootherState,
+}
+
+#[derive(Debug, Clone)]
+enum SmootherState {
+    Exponential(Option<f64>),
+    Median(VecDeque<f64>),
+}
+
+impl Smoother {
+    /// Creates a smoother that has seen no readings yet.
+    pub fn new(smoothing: Smoothing) -> Self {
+        let state = match smoothing {
+            Smoothing::Exponential { .. } => SmootherState::Exponential(None),
+            Smoothing::Median { window } => SmootherState::Median(VecDeque::with_capacity(window.max(1))),
+        };
+        Smoother { smoothing, state }
+    }
+
+    /// Returns the filter this smoother applies.
+    pub fn smoothing(&self) -> Smoothing {
+        self.smoothing
+    }
+
+    /// Adds `reading` and returns the smoothed value.
+    ///
+    /// The first reading is returned unchanged.
+    pub fn push(&mut self, reading: &CpuTemperature) -> CpuTemperature {
+        let celsius = match (&mut self.state, self.smoothing) {
+            (SmootherState::Exponential(previous), Smoothing::Exponential { alpha }) => {
+                let alpha = if alpha > 0.0 && alpha <= 1.0 { alpha } else { 1.0 };
+                let smoothed = match *previous {
+                    Some(previous) => previous + alpha * (reading.celsius() - previous),
+                    None => reading.celsius(),
+                };
+                *previous = Some(smoothed);
+                smoothed
+            }
+            (SmootherState::Median(readings), Smoothing::Median { window }) => {
+                if readings.len() == window.max(1) {
+                    readings.pop_front();
+                }
+                readings.push_back(reading.celsius());
+                median(readings.iter().copied())
+            }
+            _ => unreachable!("smoother state always matches its filter"),
+        };
+
+        CpuTemperature::from_celsius(celsius)
+    }
+}
+
+/// Returns the median of non-empty `values`; the mean of the two middle
+/// values for an even number.
+pub(crate) fn median(values: impl IntoIterator<Item = f64>) -> f64 {
+    let mut sorted: Vec<f64> = values.into_iter().collect();
+    sorted.sort_by(f64::total_cmp);
+
+    let middle = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[middle - 1] + sorted[middle]) / 2.0
+    } else {
+        sorted[middle]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockProvider;
+
+    #[test]
+    fn test_window_mean_and_eviction() {
+        let provider = MockProvider::sequence([40.0, 42.0, 44.0, 52.0].map(|c| Ok(CpuTemperature::from_celsius(c))));
+        let mut smoother = WindowSmoother::with_provider(provider, 3);
+
+        assert_eq!(smoother.update().unwrap().celsius(), 40.0);
+        assert_eq!(smoother.update().unwrap().celsius(), 41.0);
+        assert!(!smoother.is_full());
+        assert_eq!(smoother.update().unwrap().celsius(), 42.0);
+        assert!(smoother.is_full());
+
+        // 40.0 falls out of the window.
+        assert_eq!(smoother.update().unwrap().celsius(), 46.0);
+        assert_eq!(smoother.count(), 3);
+    }
+
+    #[test]
+    fn test_exponential_smoothing() {
+        let mut smoother = Smoother::new(Smoothing::Exponential { alpha: 0.25 });
+
+        let outputs: Vec<f64> = [40.0, 44.0, 36.0, 40.0]
+            .iter()
+            .map(|&c| smoother.push(&CpuTemperature::from_celsius(c)).celsius())
+            .collect();
+        assert_eq!(outputs, [40.0, 41.0, 39.75, 39.8125]);
+
+        let mut passthrough = Smoother::new(Smoothing::Exponential { alpha: f64::NAN });
+        passthrough.push(&CpuTemperature::from_celsius(40.0));
+        assert_eq!(passthrough.push(&CpuTemperature::from_celsius(50.0)).celsius(), 50.0);
+    }
+
+    #[test]
+    fn test_median_smoothing_ignores_spikes() {
+        let mut smoother = Smoother::new(Smoothing::Median { window: 3 });
+
+        let outputs: Vec<f64> = [40.0, 90.0, 41.0, 42.0, 43.0]
+            .iter()
+            .map(|&c| smoother.push(&CpuTemperature::from_celsius(c)).celsius())
+            .collect();
+        assert_eq!(outputs, [40.0, 65.0, 41.0, 42.0, 42.0]);
+    }
+
+    #[test]
+    fn test_failed_update_leaves_window_unchanged() {
+        let provider = MockProvider::sequence(vec![
+            Ok(CpuTemperature::from_celsius(40.0)),
+            Err("busy".into()),
+        ]);
+        let mut smoother = WindowSmoother::with_provider(provider, 3);
+
+        smoother.update().unwrap();
+        assert!(smoother.update().is_err());
+        assert_eq!(smoother.count(), 1);
+    }
+}
@@ -0,0 +1,117 @@
+//! Temperature and CPU load read together.
+//!
+//! Dashboards nearly always show the temperature next to the CPU load, and
+//! every query costs a PowerShell start. A [`SystemThermalSnapshot`] reads
+//! the ACPI thermal zones and the `Win32_PerfFormattedData_PerfOS_Processor`
+//! counters in one script.
+
+use crate::{fallback, parse_format_list, record_value, CpuTemperature, TemperatureError, ACPI_QUERY};
+
+/// The query listing the load of every logical processor and of `_Total`.
+const LOAD_QUERY: &str = "Get-CimInstance -ClassName Win32_PerfFormattedData_PerfOS_Processor | Format-List Name,PercentProcessorTime";
+
+/// The temperature and load of the CPU at one moment.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SystemThermalSnapshot {
+    /// The CPU temperature.
+    pub temperature: CpuTemperature,
+    /// The load of all logical processors together, in percent.
+    pub total_load: f64,
+    /// The load of each logical processor, in percent, in processor order.
+    pub core_loads: Vec<f64>,
+}
+
+impl SystemThermalSnapshot {
+    /// Reads the temperature, the total load and the load of each logical
+    /// processor with a single PowerShell call.
+    ///
+    /// The temperature comes from the ACPI thermal zones. When they yield
+    /// nothing, the usual fallbacks of [`CpuTemperature::get`] are read
+    /// afterwards, at the cost of another call.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the query fails, no temperature can be read or the load
+    /// counters are missing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tunjukin_suhu_cpu_windows::SystemThermalSnapshot;
+    ///
+    /// let snapshot = SystemThermalSnapshot::get()?;
+    /// println!("{:.1}°C at {:.0}% load", snapshot.temperature.celsius(), snapshot.total_load);
+    /// for (core, load) in snapshot.core_loads.iter().enumerate() {
+    ///     println!("  CPU {}: {:.0}%", core, load);
+    /// }
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    pub fn get() -> Result<Self, TemperatureError> {
+        let output = CpuTemperature::run_powershell(&format!("{}; {}", ACPI_QUERY, LOAD_QUERY))?;
+        let (total_load, core_loads) = parse_loads(&output)?;
+        let temperature = fallback::recover(CpuTemperature::parse_temperature(&output).map(CpuTemperature::from_celsius), |provider| {
+            provider.read_one()
+        })?;
+
+        Ok(SystemThermalSnapshot { temperature, total_load, core_loads })
+    }
+}
+
+/// Parses the load records out of the combined query output, returning the
+/// total load and the per-processor loads in processor order.
+fn parse_loads(output: &str) -> Result<(f64, Vec<f64>), TemperatureError> {
+    let mut total = None;
+    let mut cores: Vec<(u32, f64)> = Vec::new();
+
+    for record in parse_format_list(output) {
+        let (Some(name), Some(load)) = (
+            record_value(&record, "Name"),
+            record_value(&record, "PercentProcessorTime").and_then(|load| load.parse::<f64>().ok()),
+        ) else {
+            continue;
+        };
+        match name {
+            "_Total" => total = Some(load),
+            _ => {
+                if let Ok(index) = name.parse() {
+                    cores.push((index, load));
+                }
+            }
+        }
+    }
+
+    let total = total.ok_or_else(|| TemperatureError::ParseError("no _Total processor load in the output".to_string()))?;
+    cores.sort_by_key(|&(index, _)| index);
+    Ok((total, cores.into_iter().map(|(_, load)| load).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_OUTPUT: &str = "\r\n\
+        InstanceName       : ACPI\\ThermalZone\\TZ00_0\r\n\
+        CurrentTemperature : 3132\r\n\
+        \r\n\
+        Name                 : 1\r\n\
+        PercentProcessorTime : 40\r\n\
+        \r\n\
+        Name                 : 0\r\n\
+        PercentProcessorTime : 12\r\n\
+        \r\n\
+        Name                 : _Total\r\n\
+        PercentProcessorTime : 26\r\n\
+        \r\n";
+
+    #[test]
+    fn test_parse_loads() {
+        assert_eq!(parse_loads(SAMPLE_OUTPUT), Ok((26.0, vec![12.0, 40.0])));
+        assert!((CpuTemperature::parse_temperature(SAMPLE_OUTPUT).unwrap() - 40.05).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_parse_loads_without_total() {
+        assert!(matches!(parse_loads("Name : 0\nPercentProcessorTime : 12\n"), Err(TemperatureError::ParseError(_))));
+    }
+}
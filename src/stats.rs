@@ -0,0 +1,340 @@
+//! Statistical helpers for batches of temperature readings.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::{CpuTemperature, SamplerHandle, TimedReading};
+
+/// The most bins [`histogram`] returns; samples spanning more give an empty
+/// histogram instead.
+pub const MAX_HISTOGRAM_BINS: usize = 10_000;
+
+/// Rolling minimum, maximum, mean and standard deviation over the readings
+/// of the last `window`.
+///
+/// Readings older than the window are evicted as new ones are pushed and are
+/// ignored by every statistic, so a sensor that stops reporting does not
+/// leave stale values in "the last five minutes". Every statistic returns
+/// `None` while the window holds no readings.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use tunjukin_suhu_cpu_windows::{CpuTemperature, TemperatureStats};
+///
+/// let mut stats = TemperatureStats::new(Duration::from_secs(300));
+/// stats.push(&CpuTemperature::from_celsius(40.0));
+/// stats.push(&CpuTemperature::from_celsius(50.0));
+///
+/// assert_eq!(stats.mean().unwrap().celsius(), 45.0);
+/// assert_eq!(stats.std_dev(), Some(5.0));
+/// ```
+///
+/// Fed by a sampler, for an "average over the last 5 minutes" display:
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use tunjukin_suhu_cpu_windows::{TemperatureSampler, TemperatureStats};
+///
+/// let sampler = TemperatureSampler::new(Duration::from_secs(1)).spawn();
+/// let stats = TemperatureStats::attach(Duration::from_secs(300), &sampler);
+///
+/// let mean = stats.lock().unwrap().mean();
+/// if let Some(mean) = mean {
+///     println!("avg over last 5 minutes: {:.1}°C", mean.celsius());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TemperatureStats {
+    window: Duration,
+    readings: VecDeque<TimedReading>,
+}
+
+impl TemperatureStats {
+    /// Creates empty statistics over a window of `window`.
+    pub fn new(window: Duration) -> Self {
+        TemperatureStats { window, readings: VecDeque::new() }
+    }
+
+    /// Creates statistics over `window` that every successful sample from
+    /// `sampler` is pushed to, on the sampler's thread.
+    pub fn attach(window: Duration, sampler: &SamplerHandle) -> Arc<Mutex<Self>> {
+        let stats = Arc::new(Mutex::new(Self::new(window)));
+        {
+            let stats = Arc::clone(&stats);
+            sampler.subscribe_with(move |sample| {
+                if let Ok(reading) = sample {
+                    stats.lock().unwrap_or_else(|e| e.into_inner()).push(reading);
+                }
+            });
+        }
+        stats
+    }
+
+    /// Returns the length of the window.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Records `reading`, timestamped with the current instant.
+    pub fn push(&mut self, reading: &CpuTemperature) {
+        self.push_timed(TimedReading::now(reading.clone()));
+    }
+
+    /// Records a reading that already carries its own timestamp, evicting
+    /// readings more than a window older than it.
+    pub fn push_timed(&mut self, reading: TimedReading) {
+        let cutoff = reading.at.checked_sub(self.window);
+        self.readings.push_back(reading);
+        if let Some(cutoff) = cutoff {
+            while self.readings.front().is_some_and(|oldest| oldest.at < cutoff) {
+                self.readings.pop_front();
+            }
+        }
+    }
+
+    /// Returns the number of readings within the window.
+    pub fn count(&self) -> usize {
+        self.current().count()
+    }
+
+    /// Returns the coolest reading within the window.
+    pub fn min(&self) -> Option<CpuTemperature> {
+        self.current().min_by(|a, b| a.celsius().total_cmp(&b.celsius())).cloned()
+    }
+
+    /// Returns the hottest reading within the window.
+    pub fn max(&self) -> Option<CpuTemperature> {
+        self.current().max_by(|a, b| a.celsius().total_cmp(&b.celsius())).cloned()
+    }
+
+    /// Returns the mean of the readings within the window.
+    pub fn mean(&self) -> Option<CpuTemperature> {
+        self.mean_celsius().map(CpuTemperature::from_celsius)
+    }
+
+    /// Returns the population standard deviation, in degrees Celsius, of the
+    /// readings within the window.
+    pub fn std_dev(&self) -> Option<f64> {
+        let mean = self.mean_celsius()?;
+        let variance = self.current().map(|reading| (reading.celsius() - mean).powi(2)).sum::<f64>() / self.count() as f64;
+        Some(variance.sqrt())
+    }
+
+    fn mean_celsius(&self) -> Option<f64> {
+        let count = self.count();
+        (count > 0).then(|| self.current().map(|reading| reading.celsius()).sum::<f64>() / count as f64)
+    }
+
+    /// Iterates over the readings taken within the last window.
+    fn current(&self) -> impl Iterator<Item = &CpuTemperature> {
+        let cutoff = Instant::now().checked_sub(self.window);
+        self.readings
+            .iter()
+            .filter(move |timed| cutoff.is_none_or(|cutoff| timed.at >= cutoff))
+            .map(|timed| &timed.reading)
+    }
+}
+
+/// Buckets a batch of readings into a histogram of Celsius values.
+///
+/// Bins are `bin_width` degrees wide and aligned to multiples of `bin_width`,
+/// so a width of `2.0` produces bins starting at `38.0`, `40.0`, `42.0`, ...
+/// The returned `(bin_start, count)` pairs cover the whole observed range in
+/// ascending order, including empty bins between the coldest and hottest
+/// reading.
+///
+/// Samples that are not finite, such as a NaN from a broken sensor, are
+/// left out. Returns an empty vector when no finite sample remains, when
+/// `bin_width` is not a positive, finite number, or when the samples would
+/// span more than [`MAX_HISTOGRAM_BINS`] bins, as a width far too small for
+/// their range would.
+///
+/// # Example
+///
+/// ```
+/// use tunjukin_suhu_cpu_windows::{histogram, CpuTemperature};
+///
+/// let samples: Vec<CpuTemperature> = [40.5, 41.0, 43.2]
+///     .iter()
+///     .map(|&c| CpuTemperature::from_celsius(c))
+///     .collect();
+///
+/// assert_eq!(histogram(&samples, 2.0), vec![(40.0, 2), (42.0, 1)]);
+/// ```
+pub fn histogram(samples: &[CpuTemperature], bin_width: f64) -> Vec<(f64, usize)> {
+    if !bin_width.is_finite() || bin_width <= 0.0 {
+        return Vec::new();
+    }
+
+    // Bin indices beyond 2^53 cannot be told apart as floats, and such
+    // samples would span far more than MAX_HISTOGRAM_BINS bins anyway.
+    const EXACT_INTEGER_LIMIT: f64 = 9_007_199_254_740_992.0;
+    let bins: Option<Vec<i64>> = samples
+        .iter()
+        .map(|s| s.celsius())
+        .filter(|c| c.is_finite())
+        .map(|c| {
+            let bin = (c / bin_width).floor();
+            (bin.abs() <= EXACT_INTEGER_LIMIT).then_some(bin as i64)
+        })
+        .collect();
+    let Some(bins) = bins else {
+        return Vec::new();
+    };
+    let (Some(&first), Some(&last)) = (bins.iter().min(), bins.iter().max()) else {
+        return Vec::new();
+    };
+    let len = match usize::try_from(last - first).ok().and_then(|span| span.checked_add(1)) {
+        Some(len) if len <= MAX_HISTOGRAM_BINS => len,
+        _ => return Vec::new(),
+    };
+
+    let mut counts = vec![0usize; len];
+    for bin in bins {
+        counts[(bin - first) as usize] += 1;
+    }
+
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| ((first + i as i64) as f64 * bin_width, count))
+        .collect()
+}
+
+/// Computes the Pearson correlation between aligned temperature and load
+/// samples.
+///
+/// `temps[i]` and `loads[i]` must describe the same moment. The result ranges
+/// from `-1.0` to `1.0`; a live sensor on a CPU under varying load should
+/// show a clearly positive correlation, while a value near zero suggests the
+/// sensor does not track the workload (or is frozen).
+///
+/// Returns `None` if the slices differ in length, hold fewer than two
+/// samples, or either series is constant.
+///
+/// # Example
+///
+/// ```
+/// use tunjukin_suhu_cpu_windows::correlation;
+///
+/// let temps = [45.0, 52.0, 61.0, 70.0];
+/// let loads = [10.0, 35.0, 60.0, 95.0];
+/// assert!(correlation(&temps, &loads).unwrap() > 0.95);
+/// ```
+pub fn correlation(temps: &[f64], loads: &[f64]) -> Option<f64> {
+    if temps.len() != loads.len() || temps.len() < 2 {
+        return None;
+    }
+
+    let n = temps.len() as f64;
+    let mean_t = temps.iter().sum::<f64>() / n;
+    let mean_l = loads.iter().sum::<f64>() / n;
+
+    let covariance: f64 = temps.iter().zip(loads).map(|(t, l)| (t - mean_t) * (l - mean_l)).sum();
+    let variance_t: f64 = temps.iter().map(|t| (t - mean_t).powi(2)).sum();
+    let variance_l: f64 = loads.iter().map(|l| (l - mean_l).powi(2)).sum();
+
+    if variance_t == 0.0 || variance_l == 0.0 {
+        return None;
+    }
+
+    Some(covariance / (variance_t * variance_l).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(celsius: f64) -> CpuTemperature {
+        CpuTemperature::from_celsius(celsius)
+    }
+
+    #[test]
+    fn test_rolling_stats() {
+        let mut stats = TemperatureStats::new(Duration::from_secs(60));
+        assert_eq!(stats.mean(), None);
+        assert_eq!(stats.std_dev(), None);
+
+        for celsius in [40.0, 44.0, 42.0, 46.0] {
+            stats.push(&reading(celsius));
+        }
+
+        assert_eq!(stats.count(), 4);
+        assert_eq!(stats.min().unwrap().celsius(), 40.0);
+        assert_eq!(stats.max().unwrap().celsius(), 46.0);
+        assert_eq!(stats.mean().unwrap().celsius(), 43.0);
+        assert!((stats.std_dev().unwrap() - 5.0_f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_rolling_stats_window() {
+        let now = Instant::now();
+        let Some(old) = now.checked_sub(Duration::from_secs(120)) else {
+            return;
+        };
+        let mut stats = TemperatureStats::new(Duration::from_secs(60));
+
+        stats.push_timed(TimedReading::new(reading(90.0), old));
+        stats.push_timed(TimedReading::new(reading(40.0), now));
+
+        assert_eq!(stats.count(), 1);
+        assert_eq!(stats.max().unwrap().celsius(), 40.0);
+        assert_eq!(stats.readings.len(), 1);
+    }
+
+    #[test]
+    fn test_histogram_counts_and_gaps() {
+        let samples: Vec<_> = [40.1, 40.9, 45.0, 41.5].iter().map(|&c| reading(c)).collect();
+        let bins = histogram(&samples, 2.0);
+
+        assert_eq!(bins, vec![(40.0, 3), (42.0, 0), (44.0, 1)]);
+    }
+
+    #[test]
+    fn test_histogram_single_bin() {
+        let samples = vec![reading(38.85), reading(38.85)];
+        assert_eq!(histogram(&samples, 1.0), vec![(38.0, 2)]);
+    }
+
+    #[test]
+    fn test_histogram_empty_and_invalid_width() {
+        assert!(histogram(&[], 1.0).is_empty());
+
+        let samples = vec![reading(40.0)];
+        assert!(histogram(&samples, 0.0).is_empty());
+        assert!(histogram(&samples, -1.0).is_empty());
+        assert!(histogram(&samples, f64::NAN).is_empty());
+    }
+
+    #[test]
+    fn test_histogram_tiny_width_and_extremes() {
+        let samples = vec![reading(40.0), reading(60.0)];
+        assert!(histogram(&samples, 1e-300).is_empty());
+        assert!(histogram(&[reading(-1e300), reading(1e300)], 1.0).is_empty());
+        assert_eq!(histogram(&samples, 20.0), vec![(40.0, 1), (60.0, 1)]);
+    }
+
+    #[test]
+    fn test_histogram_skips_nan() {
+        let samples = vec![reading(f64::NAN), reading(41.0), reading(f64::INFINITY)];
+        assert_eq!(histogram(&samples, 2.0), vec![(40.0, 1)]);
+        assert!(histogram(&[reading(f64::NAN)], 2.0).is_empty());
+    }
+
+    #[test]
+    fn test_correlation_perfect_and_inverse() {
+        let temps = [40.0, 50.0, 60.0];
+        assert!((correlation(&temps, &[1.0, 2.0, 3.0]).unwrap() - 1.0).abs() < 1e-12);
+        assert!((correlation(&temps, &[3.0, 2.0, 1.0]).unwrap() + 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_correlation_rejects_bad_input() {
+        assert_eq!(correlation(&[40.0, 50.0], &[1.0]), None);
+        assert_eq!(correlation(&[40.0], &[1.0]), None);
+        assert_eq!(correlation(&[40.0, 40.0, 40.0], &[1.0, 2.0, 3.0]), None);
+    }
+}
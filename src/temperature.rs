@@ -0,0 +1,304 @@
+//! A temperature value that cannot disagree with itself.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::str::FromStr;
+
+#[cfg(feature = "uom")]
+use uom::si::f64::ThermodynamicTemperature;
+#[cfg(feature = "uom")]
+use uom::si::thermodynamic_temperature::degree_celsius;
+
+use crate::{CpuTemperature, TemperatureError};
+
+/// A temperature, stored once in degrees Celsius and converted on demand.
+///
+/// A `Temperature` has a single value, so every unit always describes the
+/// same temperature. Every [`CpuTemperature`] stores one; the two convert
+/// into each other, and [`CpuTemperature::temperature`] returns the
+/// `Temperature` of a reading.
+///
+/// With the `uom` feature, both also convert to and from
+/// [`uom`](https://docs.rs/uom)'s `ThermodynamicTemperature`.
+///
+/// Adding or subtracting an `f64` shifts the temperature by that many
+/// degrees Celsius (which equal Kelvin); subtracting two temperatures gives
+/// their difference in degrees.
+///
+/// # Example
+///
+/// ```
+/// use tunjukin_suhu_cpu_windows::Temperature;
+///
+/// let ambient = Temperature::from_celsius(25.0);
+/// let cpu = ambient + 40.0;
+/// assert_eq!(cpu.as_fahrenheit(), 149.0);
+/// assert_eq!(cpu - ambient, 40.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+pub struct Temperature {
+    celsius: f64,
+}
+
+impl Temperature {
+    /// Creates a temperature from degrees Celsius.
+    pub fn from_celsius(celsius: f64) -> Self {
+        Temperature { celsius }
+    }
+
+    /// Creates a temperature from degrees Fahrenheit.
+    pub fn from_fahrenheit(fahrenheit: f64) -> Self {
+        Self::from_celsius((fahrenheit - 32.0) * 5.0 / 9.0)
+    }
+
+    /// Creates a temperature from Kelvin.
+    pub fn from_kelvin(kelvin: f64) -> Self {
+        Self::from_celsius(kelvin - 273.15)
+    }
+
+    /// Returns the temperature in degrees Celsius.
+    pub fn as_celsius(self) -> f64 {
+        self.celsius
+    }
+
+    /// Returns the temperature in degrees Fahrenheit.
+    pub fn as_fahrenheit(self) -> f64 {
+        self.celsius * 9.0 / 5.0 + 32.0
+    }
+
+    /// Returns the temperature in Kelvin.
+    pub fn as_kelvin(self) -> f64 {
+        self.celsius + 273.15
+    }
+
+    /// Returns the temperature in `unit`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tunjukin_suhu_cpu_windows::{Temperature, TemperatureUnit};
+    ///
+    /// assert_eq!(Temperature::from_celsius(100.0).as_unit(TemperatureUnit::Fahrenheit), 212.0);
+    /// ```
+    pub fn as_unit(self, unit: TemperatureUnit) -> f64 {
+        match unit {
+            TemperatureUnit::Celsius => self.as_celsius(),
+            TemperatureUnit::Fahrenheit => self.as_fahrenheit(),
+            TemperatureUnit::Kelvin => self.as_kelvin(),
+        }
+    }
+}
+
+/// A unit temperatures can be expressed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum TemperatureUnit {
+    /// Degrees Celsius.
+    #[default]
+    Celsius,
+    /// Degrees Fahrenheit.
+    Fahrenheit,
+    /// Kelvin.
+    Kelvin,
+}
+
+impl TemperatureUnit {
+    /// Returns the unit's symbol, such as `"°C"`.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+            TemperatureUnit::Kelvin => "K",
+        }
+    }
+}
+
+impl FromStr for TemperatureUnit {
+    type Err = TemperatureError;
+
+    /// Parses `celsius`, `fahrenheit` or `kelvin`, or their symbols, ignoring
+    /// case.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "celsius" | "c" | "°c" => Ok(TemperatureUnit::Celsius),
+            "fahrenheit" | "f" | "°f" => Ok(TemperatureUnit::Fahrenheit),
+            "kelvin" | "k" => Ok(TemperatureUnit::Kelvin),
+            _ => Err(TemperatureError::InvalidArgument(format!(
+                "unknown temperature unit '{}' (expected celsius, fahrenheit or kelvin)",
+                text.trim()
+            ))),
+        }
+    }
+}
+
+/// Formats the temperature in degrees Celsius, such as `38.9 °C`, with one
+/// decimal unless the format string gives a precision.
+impl fmt::Display for Temperature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.*} °C", f.precision().unwrap_or(1), self.celsius)
+    }
+}
+
+impl Add<f64> for Temperature {
+    type Output = Temperature;
+
+    fn add(self, degrees: f64) -> Temperature {
+        Temperature::from_celsius(self.celsius + degrees)
+    }
+}
+
+impl Sub<f64> for Temperature {
+    type Output = Temperature;
+
+    fn sub(self, degrees: f64) -> Temperature {
+        Temperature::from_celsius(self.celsius - degrees)
+    }
+}
+
+impl Sub for Temperature {
+    type Output = f64;
+
+    fn sub(self, other: Temperature) -> f64 {
+        self.celsius - other.celsius
+    }
+}
+
+impl AddAssign<f64> for Temperature {
+    fn add_assign(&mut self, degrees: f64) {
+        self.celsius += degrees;
+    }
+}
+
+impl SubAssign<f64> for Temperature {
+    fn sub_assign(&mut self, degrees: f64) {
+        self.celsius -= degrees;
+    }
+}
+
+impl From<Temperature> for CpuTemperature {
+    fn from(temperature: Temperature) -> Self {
+        CpuTemperature::from_celsius(temperature.celsius)
+    }
+}
+
+impl From<CpuTemperature> for Temperature {
+    fn from(reading: CpuTemperature) -> Self {
+        reading.temperature()
+    }
+}
+
+/// The serialized form of a [`CpuTemperature`], which keeps both units for
+/// consumers of the JSON. Only `celsius` is read back, so a stale
+/// `fahrenheit` cannot make a reading disagree with itself.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct ReadingFields {
+    celsius: f64,
+    #[serde(default, skip_deserializing)]
+    fahrenheit: f64,
+}
+
+#[cfg(feature = "serde")]
+impl From<CpuTemperature> for ReadingFields {
+    fn from(reading: CpuTemperature) -> Self {
+        ReadingFields { celsius: reading.celsius(), fahrenheit: reading.fahrenheit() }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<ReadingFields> for CpuTemperature {
+    fn from(fields: ReadingFields) -> Self {
+        CpuTemperature::from_celsius(fields.celsius)
+    }
+}
+
+impl CpuTemperature {
+    /// Returns the reading as a [`Temperature`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// let temp = CpuTemperature::from_celsius(100.0).temperature();
+    /// assert_eq!(temp.as_fahrenheit(), 212.0);
+    /// ```
+    pub fn temperature(&self) -> Temperature {
+        self.temperature
+    }
+}
+
+#[cfg(feature = "uom")]
+impl From<Temperature> for ThermodynamicTemperature {
+    fn from(temperature: Temperature) -> Self {
+        ThermodynamicTemperature::new::<degree_celsius>(temperature.celsius)
+    }
+}
+
+#[cfg(feature = "uom")]
+impl From<ThermodynamicTemperature> for Temperature {
+    fn from(temperature: ThermodynamicTemperature) -> Self {
+        Temperature::from_celsius(temperature.get::<degree_celsius>())
+    }
+}
+
+#[cfg(feature = "uom")]
+impl From<CpuTemperature> for ThermodynamicTemperature {
+    fn from(reading: CpuTemperature) -> Self {
+        ThermodynamicTemperature::new::<degree_celsius>(reading.celsius())
+    }
+}
+
+#[cfg(feature = "uom")]
+impl From<ThermodynamicTemperature> for CpuTemperature {
+    fn from(temperature: ThermodynamicTemperature) -> Self {
+        CpuTemperature::from_celsius(temperature.get::<degree_celsius>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        let temp = Temperature::from_fahrenheit(212.0);
+        assert_eq!(temp.as_celsius(), 100.0);
+        assert!((Temperature::from_kelvin(temp.as_kelvin()).as_celsius() - 100.0).abs() < 1e-9);
+
+        let reading = CpuTemperature::from(temp);
+        assert_eq!(reading.fahrenheit(), 212.0);
+        assert_eq!(Temperature::from(reading), temp);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let mut temp = Temperature::from_celsius(50.0);
+        temp += 8.0;
+        assert_eq!(temp, Temperature::from_celsius(58.0));
+        temp -= 3.0;
+        assert_eq!(temp - 5.0, Temperature::from_celsius(50.0));
+        assert_eq!(temp - Temperature::from_celsius(40.0), 15.0);
+        assert_eq!(format!("{} / {:.0}", temp, temp), "55.0 °C / 55 °C");
+    }
+
+    #[test]
+    fn test_parse_unit() {
+        assert_eq!("Fahrenheit".parse(), Ok(TemperatureUnit::Fahrenheit));
+        assert_eq!(" °C ".parse(), Ok(TemperatureUnit::Celsius));
+        assert_eq!("k".parse(), Ok(TemperatureUnit::Kelvin));
+        assert!(matches!("rankine".parse::<TemperatureUnit>(), Err(TemperatureError::InvalidArgument(_))));
+    }
+
+    #[cfg(feature = "uom")]
+    #[test]
+    fn test_uom() {
+        use uom::si::thermodynamic_temperature::kelvin;
+
+        let thermodynamic = ThermodynamicTemperature::from(CpuTemperature::from_celsius(26.85));
+        assert!((thermodynamic.get::<kelvin>() - 300.0).abs() < 1e-9);
+        assert!((Temperature::from(thermodynamic).as_celsius() - 26.85).abs() < 1e-9);
+        assert!((CpuTemperature::from(ThermodynamicTemperature::new::<kelvin>(373.15)).celsius() - 100.0).abs() < 1e-9);
+    }
+}
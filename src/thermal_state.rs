@@ -0,0 +1,273 @@
+//! Coarse thermal states with hysteresis between them.
+
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::{CpuTemperature, SamplerHandle};
+
+/// Lowest Celsius value of [`ThermalState::Warm`] by default.
+const WARM_CELSIUS: f64 = 70.0;
+/// Lowest Celsius value of [`ThermalState::Hot`] by default.
+const HOT_CELSIUS: f64 = 85.0;
+/// Lowest Celsius value of [`ThermalState::Critical`] by default.
+const CRITICAL_CELSIUS: f64 = 95.0;
+/// How far below a threshold the temperature must fall to leave its state
+/// by default.
+const HYSTERESIS_CELSIUS: f64 = 3.0;
+
+/// How hot the CPU is, from coolest to hottest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThermalState {
+    /// Below 70 °C by default.
+    Normal,
+    /// From 70 °C by default.
+    Warm,
+    /// From 85 °C by default.
+    Hot,
+    /// From 95 °C by default.
+    Critical,
+}
+
+impl ThermalState {
+    /// Returns the state of `celsius` under the default thresholds, without
+    /// hysteresis.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tunjukin_suhu_cpu_windows::ThermalState;
+    ///
+    /// assert_eq!(ThermalState::from_celsius(88.0), ThermalState::Hot);
+    /// ```
+    pub fn from_celsius(celsius: f64) -> Self {
+        classify(celsius, [WARM_CELSIUS, HOT_CELSIUS, CRITICAL_CELSIUS])
+    }
+
+    /// Returns the lowercase name of the state, such as `"hot"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ThermalState::Normal => "normal",
+            ThermalState::Warm => "warm",
+            ThermalState::Hot => "hot",
+            ThermalState::Critical => "critical",
+        }
+    }
+}
+
+impl fmt::Display for ThermalState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A change of [`ThermalState`] reported by a [`ThermalStateMachine`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateTransition {
+    /// The state before the reading.
+    pub from: ThermalState,
+    /// The state after the reading.
+    pub to: ThermalState,
+    /// The reading that caused the change.
+    pub temperature: CpuTemperature,
+}
+
+/// A callback registered with [`ThermalStateMachine::on_transition`].
+type Callback = Box<dyn FnMut(&StateTransition) + Send>;
+
+/// Tracks the [`ThermalState`] of a stream of readings and reports every
+/// change of state.
+///
+/// A state is entered as soon as a reading reaches its threshold, but only
+/// left once a reading falls `hysteresis` degrees below it. With the default
+/// thresholds of 70, 85 and 95 °C and 3 °C of hysteresis, a CPU that becomes
+/// hot at 85 °C stays hot until it drops below 82 °C, so a temperature
+/// oscillating around 85 °C reports one transition instead of one per
+/// reading. A single reading can skip states, for example from normal
+/// straight to critical.
+///
+/// The machine starts in [`ThermalState::Normal`].
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use tunjukin_suhu_cpu_windows::{TemperatureSampler, ThermalState, ThermalStateMachine};
+///
+/// let sampler = TemperatureSampler::new(Duration::from_secs(1)).spawn();
+///
+/// let mut machine = ThermalStateMachine::new().hysteresis(5.0);
+/// let transitions = machine.subscribe();
+/// machine.attach(&sampler);
+///
+/// for transition in transitions {
+///     println!("{} -> {}", transition.from, transition.to);
+///     if transition.to == ThermalState::Critical {
+///         // Throttle the workload.
+///     }
+/// }
+/// ```
+pub struct ThermalStateMachine {
+    thresholds: [f64; 3],
+    hysteresis: f64,
+    state: ThermalState,
+    callbacks: Vec<Callback>,
+    channels: Vec<Sender<StateTransition>>,
+}
+
+impl ThermalStateMachine {
+    /// Creates a machine with the default thresholds and hysteresis.
+    pub fn new() -> Self {
+        ThermalStateMachine {
+            thresholds: [WARM_CELSIUS, HOT_CELSIUS, CRITICAL_CELSIUS],
+            hysteresis: HYSTERESIS_CELSIUS,
+            state: ThermalState::Normal,
+            callbacks: Vec::new(),
+            channels: Vec::new(),
+        }
+    }
+
+    /// Sets the lowest Celsius values of the warm, hot and critical states.
+    ///
+    /// The thresholds are sorted, so they may be given in any order.
+    pub fn thresholds(mut self, warm: f64, hot: f64, critical: f64) -> Self {
+        self.thresholds = [warm, hot, critical];
+        self.thresholds.sort_by(f64::total_cmp);
+        self
+    }
+
+    /// Sets how many degrees below a threshold the temperature must fall to
+    /// leave its state. Negative values are treated as zero.
+    pub fn hysteresis(mut self, celsius: f64) -> Self {
+        self.hysteresis = celsius.max(0.0);
+        self
+    }
+
+    /// Calls `callback` with every transition, on the thread that feeds the
+    /// readings.
+    pub fn on_transition(mut self, callback: impl FnMut(&StateTransition) + Send + 'static) -> Self {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
+    /// Returns a channel that receives every future transition.
+    ///
+    /// Dropping the receiver unsubscribes it.
+    pub fn subscribe(&mut self) -> Receiver<StateTransition> {
+        let (sender, receiver) = mpsc::channel();
+        self.channels.push(sender);
+        receiver
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> ThermalState {
+        self.state
+    }
+
+    /// Moves to the state of `reading`, delivers the transition if the state
+    /// changed and returns it.
+    pub fn update(&mut self, reading: &CpuTemperature) -> Option<StateTransition> {
+        let rising = classify(reading.celsius(), self.thresholds);
+        let falling = classify(reading.celsius() + self.hysteresis, self.thresholds);
+
+        let next = if rising > self.state {
+            rising
+        } else if falling < self.state {
+            falling
+        } else {
+            return None;
+        };
+
+        let transition = StateTransition { from: self.state, to: next, temperature: reading.clone() };
+        self.state = next;
+
+        self.channels.retain(|channel| channel.send(transition.clone()).is_ok());
+        for callback in &mut self.callbacks {
+            callback(&transition);
+        }
+        Some(transition)
+    }
+
+    /// Feeds every successful sample from `sampler` to the machine on the
+    /// sampler's thread.
+    ///
+    /// Failed reads leave the state as it was.
+    pub fn attach(mut self, sampler: &SamplerHandle) {
+        sampler.subscribe_with(move |sample| {
+            if let Ok(reading) = sample {
+                self.update(reading);
+            }
+        });
+    }
+}
+
+impl Default for ThermalStateMachine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for ThermalStateMachine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThermalStateMachine")
+            .field("thresholds", &self.thresholds)
+            .field("hysteresis", &self.hysteresis)
+            .field("state", &self.state)
+            .field("callbacks", &self.callbacks.len())
+            .field("channels", &self.channels.len())
+            .finish()
+    }
+}
+
+/// Returns the state of `celsius` given the sorted warm, hot and critical
+/// thresholds.
+fn classify(celsius: f64, [warm, hot, critical]: [f64; 3]) -> ThermalState {
+    if celsius >= critical {
+        ThermalState::Critical
+    } else if celsius >= hot {
+        ThermalState::Hot
+    } else if celsius >= warm {
+        ThermalState::Warm
+    } else {
+        ThermalState::Normal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn states(machine: &mut ThermalStateMachine, readings: &[f64]) -> Vec<ThermalState> {
+        readings
+            .iter()
+            .map(|&celsius| {
+                machine.update(&CpuTemperature::from_celsius(celsius));
+                machine.state()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_hysteresis_prevents_flapping() {
+        use ThermalState::*;
+        let mut machine = ThermalStateMachine::new();
+
+        assert_eq!(states(&mut machine, &[84.0, 85.0, 84.0, 85.5, 82.5, 81.9]), [Warm, Hot, Hot, Hot, Hot, Warm]);
+        assert_eq!(states(&mut machine, &[96.0, 60.0]), [Critical, Normal]);
+    }
+
+    #[test]
+    fn test_transitions_are_delivered() {
+        let mut machine = ThermalStateMachine::new().thresholds(90.0, 60.0, 80.0).hysteresis(0.0);
+        let transitions = machine.subscribe();
+
+        assert!(machine.update(&CpuTemperature::from_celsius(50.0)).is_none());
+        let transition = machine.update(&CpuTemperature::from_celsius(85.0)).unwrap();
+        assert_eq!((transition.from, transition.to), (ThermalState::Normal, ThermalState::Hot));
+        machine.update(&CpuTemperature::from_celsius(79.9));
+
+        assert_eq!(transitions.recv().unwrap().to, ThermalState::Hot);
+        assert_eq!(transitions.recv().unwrap().to, ThermalState::Warm);
+        assert!(transitions.try_recv().is_err());
+    }
+}
@@ -0,0 +1,149 @@
+//! Throttling detection from the processor performance limit counter.
+//!
+//! The "Processor Information" performance counters include
+//! `% Performance Limit`, the share of the CPU's rated performance it is
+//! currently allowed to reach. It stays at 100 until firmware or Windows
+//! limits the CPU, typically because it is too hot or drawing too much
+//! power, and falls by how much performance is being withheld.
+
+use crate::{parse_format_list, record_value, CpuTemperature, TemperatureError, ACPI_QUERY};
+
+/// The query printing the performance limit of all processors together.
+const LIMIT_QUERY: &str = "Get-CimInstance -ClassName Win32_PerfFormattedData_Counters_ProcessorInformation -Filter \"Name='_Total'\" | \
+    Format-List PercentPerformanceLimit";
+
+/// How far below 100 the performance limit may fall by default before the
+/// CPU counts as throttled, to ignore rounding in the counter.
+const DEFAULT_TOLERANCE_PERCENT: f64 = 1.0;
+
+/// Whether the CPU was throttled, as reported by [`ThrottleDetector::check`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThrottleStatus {
+    /// `true` if the CPU was limited by more than the tolerance and, when
+    /// the detector has a minimum temperature, was at least that hot.
+    pub throttled: bool,
+    /// How much of its rated performance the CPU was denied, from 0 to 100
+    /// percent.
+    pub severity: f64,
+    /// The temperature at the time, if the detector has a minimum
+    /// temperature.
+    pub temperature: Option<CpuTemperature>,
+}
+
+/// Detects whether the CPU is throttled from its performance limit.
+///
+/// The performance limit falls for power limits as well as thermal ones.
+/// Set a [minimum temperature](ThrottleDetector::min_celsius) to count only
+/// limits reached while the CPU is hot, which reads the temperature in the
+/// same query.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::ThrottleDetector;
+///
+/// let status = ThrottleDetector::new().min_celsius(80.0).check()?;
+/// if status.throttled {
+///     println!("Thermally throttled by {:.0}%", status.severity);
+/// }
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThrottleDetector {
+    tolerance: f64,
+    min_celsius: Option<f64>,
+}
+
+impl ThrottleDetector {
+    /// Creates a detector counting any limit of more than 1% as throttling,
+    /// whatever the temperature.
+    pub fn new() -> Self {
+        ThrottleDetector { tolerance: DEFAULT_TOLERANCE_PERCENT, min_celsius: None }
+    }
+
+    /// Sets how many percent of performance may be withheld before the CPU
+    /// counts as throttled. Negative values are treated as 0.
+    pub fn tolerance(mut self, percent: f64) -> Self {
+        self.tolerance = percent.max(0.0);
+        self
+    }
+
+    /// Counts a limit as throttling only while the CPU is at least `celsius`
+    /// hot.
+    pub fn min_celsius(mut self, celsius: f64) -> Self {
+        self.min_celsius = Some(celsius);
+        self
+    }
+
+    /// Reads the performance limit, and the temperature if a minimum is set,
+    /// and reports whether the CPU is throttled.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the query fails, the counter is missing or, with a minimum
+    /// temperature, the ACPI thermal zones yield no reading.
+    pub fn check(&self) -> Result<ThrottleStatus, TemperatureError> {
+        let output = match self.min_celsius {
+            Some(_) => CpuTemperature::run_powershell(&format!("{}; {}", ACPI_QUERY, LIMIT_QUERY))?,
+            None => CpuTemperature::run_powershell(LIMIT_QUERY)?,
+        };
+        let temperature = match self.min_celsius {
+            Some(_) => Some(CpuTemperature::from_celsius(CpuTemperature::parse_temperature(&output)?)),
+            None => None,
+        };
+
+        Ok(self.status(parse_limit(&output)?, temperature))
+    }
+
+    /// Judges a performance limit of `limit` percent at `temperature`.
+    fn status(&self, limit: f64, temperature: Option<CpuTemperature>) -> ThrottleStatus {
+        let severity = (100.0 - limit).clamp(0.0, 100.0);
+        let hot = match (self.min_celsius, &temperature) {
+            (Some(min_celsius), Some(reading)) => reading.celsius() >= min_celsius,
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+
+        ThrottleStatus { throttled: hot && severity > self.tolerance, severity, temperature }
+    }
+}
+
+impl Default for ThrottleDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parses the performance limit out of the query output.
+fn parse_limit(output: &str) -> Result<f64, TemperatureError> {
+    parse_format_list(output)
+        .iter()
+        .find_map(|record| record_value(record, "PercentPerformanceLimit")?.parse().ok())
+        .ok_or_else(|| TemperatureError::ParseError("no PercentPerformanceLimit in the output".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status() {
+        let detector = ThrottleDetector::new();
+        assert_eq!(detector.status(100.0, None), ThrottleStatus { throttled: false, severity: 0.0, temperature: None });
+        assert!(!detector.status(99.5, None).throttled);
+        assert_eq!(detector.status(72.0, None), ThrottleStatus { throttled: true, severity: 28.0, temperature: None });
+
+        let thermal = ThrottleDetector::new().min_celsius(80.0);
+        assert!(!thermal.status(72.0, Some(CpuTemperature::from_celsius(60.0))).throttled);
+        assert!(thermal.status(72.0, Some(CpuTemperature::from_celsius(95.0))).throttled);
+    }
+
+    #[test]
+    fn test_parse_limit() {
+        let output = "\r\nInstanceName : ACPI\\ThermalZone\\TZ00_0\r\nCurrentTemperature : 3682\r\n\r\nPercentPerformanceLimit : 64\r\n\r\n";
+
+        assert_eq!(parse_limit(output), Ok(64.0));
+        assert!(matches!(parse_limit(""), Err(TemperatureError::ParseError(_))));
+    }
+}
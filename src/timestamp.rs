@@ -0,0 +1,103 @@
+//! Calendar timestamps without a date-time dependency.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Formats `time` as an RFC 3339 UTC timestamp with millisecond precision,
+/// such as `2025-10-14T08:30:00.250Z`.
+///
+/// This is the format of the timestamps in [`CsvLogger`](crate::CsvLogger)
+/// rows. Times before 1970 are formatted as the Unix epoch.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use tunjukin_suhu_cpu_windows::format_rfc3339;
+///
+/// let time = UNIX_EPOCH + Duration::from_millis(1_760_430_600_250);
+/// assert_eq!(format_rfc3339(time), "2025-10-14T08:30:00.250Z");
+/// ```
+pub fn format_rfc3339(time: SystemTime) -> String {
+    let parts = Parts::of(time);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        parts.year, parts.month, parts.day, parts.hour, parts.minute, parts.second, parts.millis
+    )
+}
+
+/// Formats `time` for use in a file name, such as `20251014T083000Z`.
+pub(crate) fn file_stamp(time: SystemTime) -> String {
+    let parts = Parts::of(time);
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        parts.year, parts.month, parts.day, parts.hour, parts.minute, parts.second
+    )
+}
+
+/// A UTC time split into calendar fields.
+struct Parts {
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u64,
+    minute: u64,
+    second: u64,
+    millis: u32,
+}
+
+impl Parts {
+    fn of(time: SystemTime) -> Self {
+        let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let seconds = since_epoch.as_secs();
+        let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+        let of_day = seconds % 86_400;
+
+        Parts {
+            year,
+            month,
+            day,
+            hour: of_day / 3600,
+            minute: of_day / 60 % 60,
+            second: of_day % 60,
+            millis: since_epoch.subsec_millis(),
+        }
+    }
+}
+
+/// Converts days since 1970-01-01 to a proleptic Gregorian date.
+///
+/// This is Howard Hinnant's `civil_from_days` algorithm.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_rfc3339() {
+        assert_eq!(format_rfc3339(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            format_rfc3339(UNIX_EPOCH + Duration::from_millis(1_760_430_600_250)),
+            "2025-10-14T08:30:00.250Z"
+        );
+        assert_eq!(format_rfc3339(UNIX_EPOCH + Duration::from_secs(951_782_400)), "2000-02-29T00:00:00.000Z");
+    }
+
+    #[test]
+    fn test_file_stamp() {
+        assert_eq!(file_stamp(UNIX_EPOCH + Duration::from_millis(1_760_430_600_250)), "20251014T083000Z");
+    }
+}
@@ -0,0 +1,163 @@
+//! Windows toast notifications for threshold alerts.
+//!
+//! Toasts are raised through the WinRT notification API from Windows
+//! PowerShell, so no extra crates are needed. PowerShell 7 cannot load WinRT
+//! types, so this requires Windows PowerShell, which ships with Windows 10
+//! and later.
+
+use std::thread;
+
+use crate::{powershell_quote, Alert, AlertMonitor, CpuTemperature, Crossing, TemperatureError};
+
+/// The application ID of Windows PowerShell, which is registered with the
+/// Start menu on every Windows installation and so may raise toasts without
+/// any setup.
+const POWERSHELL_APP_ID: &str = r"{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe";
+
+/// Raises Windows toast notifications such as "CPU at 96 °C".
+///
+/// By default toasts are attributed to Windows PowerShell in the Action
+/// Center. Applications with their own Start menu shortcut can pass its
+/// application user model ID to [`app_id`](ToastNotifier::app_id) to show
+/// their name and icon instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use tunjukin_suhu_cpu_windows::{AlertMonitor, TemperatureSampler, ToastNotifier};
+///
+/// let sampler = TemperatureSampler::new(Duration::from_secs(1)).spawn();
+///
+/// AlertMonitor::new()
+///     .threshold("critical", 95.0)
+///     .toast(ToastNotifier::new().title("Render farm"))
+///     .attach(&sampler);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToastNotifier {
+    app_id: String,
+    title: String,
+}
+
+impl ToastNotifier {
+    /// Creates a notifier titled "CPU temperature", attributed to Windows
+    /// PowerShell.
+    pub fn new() -> Self {
+        ToastNotifier { app_id: POWERSHELL_APP_ID.to_string(), title: "CPU temperature".to_string() }
+    }
+
+    /// Attributes toasts to the application with the given user model ID.
+    ///
+    /// Windows silently drops toasts from IDs that no Start menu shortcut
+    /// is registered for.
+    pub fn app_id(mut self, app_id: impl Into<String>) -> Self {
+        self.app_id = app_id.into();
+        self
+    }
+
+    /// Sets the first line of every toast.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Raises a toast with `message` under the title, waiting until Windows
+    /// has accepted it.
+    ///
+    /// # Errors
+    ///
+    /// Fails if Windows PowerShell cannot be started or cannot reach the
+    /// notification API, for example on Windows Server Core.
+    pub fn show(&self, message: &str) -> Result<(), TemperatureError> {
+        CpuTemperature::run_powershell(&self.script(message)).map(|_| ())
+    }
+
+    /// Raises a toast describing `alert`, such as "CPU at 96 °C (critical)".
+    pub fn notify(&self, alert: &Alert) -> Result<(), TemperatureError> {
+        self.show(&message(alert))
+    }
+
+    /// Builds the PowerShell script that shows a toast with `message`.
+    fn script(&self, message: &str) -> String {
+        let xml = format!(
+            r#"<toast><visual><binding template="ToastGeneric"><text>{}</text><text>{}</text></binding></visual></toast>"#,
+            xml_text(&self.title),
+            xml_text(message)
+        );
+
+        format!(
+            "$null = [Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime]; \
+             $null = [Windows.Data.Xml.Dom.XmlDocument, Windows.Data.Xml.Dom.XmlDocument, ContentType = WindowsRuntime]; \
+             $xml = New-Object Windows.Data.Xml.Dom.XmlDocument; \
+             $xml.LoadXml({}); \
+             [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier({}).Show([Windows.UI.Notifications.ToastNotification]::new($xml))",
+            powershell_quote(&xml),
+            powershell_quote(&self.app_id)
+        )
+    }
+}
+
+impl Default for ToastNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AlertMonitor {
+    /// Raises a toast with `notifier` whenever a threshold is raised.
+    ///
+    /// Cleared thresholds do not raise toasts. Each toast is shown on its own
+    /// thread, so a slow PowerShell start does not delay the readings, and a
+    /// toast that cannot be shown is dropped.
+    pub fn toast(self, notifier: ToastNotifier) -> Self {
+        self.on_alert(move |alert| {
+            if alert.crossing == Crossing::Raised {
+                let notifier = notifier.clone();
+                let message = message(alert);
+                thread::spawn(move || notifier.show(&message));
+            }
+        })
+    }
+}
+
+/// Describes `alert` in one line.
+fn message(alert: &Alert) -> String {
+    match alert.crossing {
+        Crossing::Raised => format!("CPU at {:.0} °C ({})", alert.temperature.celsius(), alert.name),
+        Crossing::Cleared => format!("CPU back to {:.0} °C ({} cleared)", alert.temperature.celsius(), alert.name),
+    }
+}
+
+/// Escapes `value` for use as XML element text.
+fn xml_text(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert(crossing: Crossing, celsius: f64) -> Alert {
+        Alert {
+            name: "critical".to_string(),
+            threshold: 95.0,
+            crossing,
+            temperature: CpuTemperature::from_celsius(celsius),
+        }
+    }
+
+    #[test]
+    fn test_message() {
+        assert_eq!(message(&alert(Crossing::Raised, 96.2)), "CPU at 96 °C (critical)");
+        assert_eq!(message(&alert(Crossing::Cleared, 91.6)), "CPU back to 92 °C (critical cleared)");
+    }
+
+    #[test]
+    fn test_script_escapes_text() {
+        let script = ToastNotifier::new().title("Tom's <rig> & co").script("CPU at 96 °C");
+
+        assert!(script.contains("<text>Tom''s &lt;rig&gt; &amp; co</text><text>CPU at 96 °C</text>"));
+        assert!(script.contains(r"CreateToastNotifier('{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe')"));
+    }
+}
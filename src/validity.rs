@@ -0,0 +1,159 @@
+//! The range of temperatures accepted as valid readings.
+//!
+//! Every provider drops readings outside this range, which by default
+//! filters out the `0` and `2732` placeholders some firmware reports for
+//! absent sensors. When every reading of a query falls outside it, the
+//! query fails with [`TemperatureError::OutOfRange`] listing them.
+//!
+//! The range is process-wide unless a
+//! [`TemperatureReader`](crate::TemperatureReader) sets its own, which then
+//! applies to the threads reading for it while they read.
+
+use std::cell::Cell;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{TemperatureError, ThermalZoneReading};
+
+/// The range of accepted temperatures, in degrees Celsius, until
+/// [`set_valid_range`] is called.
+pub const DEFAULT_VALID_RANGE: (f64, f64) = (-50.0, 150.0);
+
+static MIN_CELSIUS: AtomicU64 = AtomicU64::new(DEFAULT_VALID_RANGE.0.to_bits());
+static MAX_CELSIUS: AtomicU64 = AtomicU64::new(DEFAULT_VALID_RANGE.1.to_bits());
+
+thread_local! {
+    /// The inclusive range of the reader reading on this thread, if any.
+    static READER_RANGE: Cell<Option<(f64, f64)>> = const { Cell::new(None) };
+}
+
+/// Sets the range of temperatures, in degrees Celsius, that this crate
+/// accepts as valid across the whole process.
+///
+/// Readings between `min` and `max`, bounds included, are accepted, like
+/// with a reader's own
+/// [`valid_range`](crate::TemperatureReaderBuilder::valid_range). The default of
+/// [`DEFAULT_VALID_RANGE`] suits CPUs in ordinary rooms; widen it for
+/// hardware that legitimately runs colder or hotter, such as in a climate
+/// chamber.
+///
+/// # Errors
+///
+/// Fails with [`TemperatureError::InvalidArgument`] if `min` is not below
+/// `max` or either is not a number; the range is left unchanged.
+///
+/// # Example
+///
+/// ```
+/// use tunjukin_suhu_cpu_windows::{set_valid_range, valid_range, DEFAULT_VALID_RANGE};
+///
+/// set_valid_range(-80.0, 200.0)?;
+/// assert_eq!(valid_range(), (-80.0, 200.0));
+/// # set_valid_range(DEFAULT_VALID_RANGE.0, DEFAULT_VALID_RANGE.1)?;
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+pub fn set_valid_range(min: f64, max: f64) -> Result<(), TemperatureError> {
+    check_range(min, max)?;
+    MIN_CELSIUS.store(min.to_bits(), Ordering::Relaxed);
+    MAX_CELSIUS.store(max.to_bits(), Ordering::Relaxed);
+    Ok(())
+}
+
+/// Fails with [`TemperatureError::InvalidArgument`] unless `min` is below
+/// `max`, as every valid range must be.
+pub(crate) fn check_range(min: f64, max: f64) -> Result<(), TemperatureError> {
+    if min.is_nan() || max.is_nan() || min >= max {
+        return Err(TemperatureError::InvalidArgument(format!("invalid temperature range {} to {} °C", min, max)));
+    }
+    Ok(())
+}
+
+/// Returns the current process-wide range of valid temperatures, in degrees
+/// Celsius, as `(min, max)`.
+pub fn valid_range() -> (f64, f64) {
+    (f64::from_bits(MIN_CELSIUS.load(Ordering::Relaxed)), f64::from_bits(MAX_CELSIUS.load(Ordering::Relaxed)))
+}
+
+/// Runs `read` with `range`, bounds included, accepted on this thread in
+/// place of the process-wide range, or as it is with `None`.
+pub(crate) fn with_range<T>(range: Option<&RangeInclusive<f64>>, read: impl FnOnce() -> T) -> T {
+    /// Puts back the range that was in effect, even if `read` panics.
+    struct Restore(Option<(f64, f64)>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            READER_RANGE.set(self.0);
+        }
+    }
+
+    let Some(range) = range else {
+        return read();
+    };
+    let _restore = Restore(READER_RANGE.replace(Some((*range.start(), *range.end()))));
+    read()
+}
+
+/// Returns `true` if `celsius` is accepted as a valid reading on this
+/// thread.
+pub(crate) fn accepts(celsius: f64) -> bool {
+    let (min, max) = READER_RANGE.get().unwrap_or_else(valid_range);
+    (min..=max).contains(&celsius)
+}
+
+/// Keeps the zones accepted on this thread, failing with
+/// [`TemperatureError::OutOfRange`] if there were zones but none is.
+pub(crate) fn accepted(zones: Vec<ThermalZoneReading>) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+    let (accepted, rejected): (Vec<_>, Vec<_>) = zones.into_iter().partition(|zone| accepts(zone.temperature.celsius()));
+    if accepted.is_empty() && !rejected.is_empty() {
+        return Err(out_of_range(rejected.iter().map(|zone| (zone.instance_name.as_deref(), zone.temperature.celsius()))));
+    }
+    Ok(accepted)
+}
+
+/// Builds the error for readings that all fell outside the valid range,
+/// given as `(name, celsius)` pairs.
+pub(crate) fn out_of_range<'a>(rejected: impl IntoIterator<Item = (Option<&'a str>, f64)>) -> TemperatureError {
+    let (min, max) = READER_RANGE.get().unwrap_or_else(valid_range);
+    let readings: Vec<String> = rejected
+        .into_iter()
+        .map(|(name, celsius)| format!("{}: {:.2} °C", name.unwrap_or("unnamed zone"), celsius))
+        .collect();
+    TemperatureError::OutOfRange { details: format!("{} (accepted: {} to {} °C)", readings.join(", "), min, max) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_invalid_range() {
+        assert!(matches!(set_valid_range(10.0, 10.0), Err(TemperatureError::InvalidArgument(_))));
+        assert!(matches!(set_valid_range(f64::NAN, 10.0), Err(TemperatureError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let error = out_of_range([(Some("TZ00"), 0.05), (None, 200.0)]);
+        let TemperatureError::OutOfRange { details } = error else {
+            panic!("unexpected error {:?}", error);
+        };
+        assert!(details.starts_with("TZ00: 0.05 °C, unnamed zone: 200.00 °C (accepted: "));
+    }
+
+    #[test]
+    fn test_global_range_includes_bounds() {
+        let (min, max) = DEFAULT_VALID_RANGE;
+        assert!(accepts(min) && accepts(max));
+        assert!(!accepts(max + 0.5));
+    }
+
+    #[test]
+    fn test_reader_range_replaces_global() {
+        assert!(!accepts(180.0));
+        with_range(Some(&(-80.0..=200.0)), || {
+            assert!(accepts(180.0) && accepts(200.0) && !accepts(200.5));
+            assert!(matches!(out_of_range([(None, 250.0)]), TemperatureError::OutOfRange { details } if details.ends_with("(accepted: -80 to 200 °C)")));
+        });
+        assert!(!accepts(180.0));
+    }
+}
@@ -0,0 +1,79 @@
+//! Voltage sensor readings.
+//!
+//! Voltages come from the `Voltage` sensors LibreHardwareMonitor and
+//! OpenHardwareMonitor publish while they run, in volts, such as the CPU
+//! core voltage. Without either tool, `Win32_VoltageProbe` is used, whose
+//! `CurrentReading` is in millivolts but which most firmware leaves empty.
+
+use crate::hardware_monitor::{sensor_query, sensor_value, LIBRE_HARDWARE_MONITOR_NAMESPACE, OPEN_HARDWARE_MONITOR_NAMESPACE};
+use crate::{parse_records, record_value, CpuTemperature, SensorKind, SensorProvider, SensorReading, TemperatureError};
+
+/// The query listing the voltage probes the firmware describes.
+const WIN32_VOLTAGE_PROBE_QUERY: &str = "Get-CimInstance -ClassName Win32_VoltageProbe | Format-List Name,CurrentReading";
+
+/// Reads voltage sensors, for tools such as undervolting utilities that need
+/// voltages next to temperatures.
+///
+/// As a [`SensorProvider`], every reading is tagged [`SensorKind::Voltage`]
+/// and its value is in volts. Readings come from LibreHardwareMonitor,
+/// OpenHardwareMonitor or `Win32_VoltageProbe`, whichever answers first.
+///
+/// # Example
+///
+/// ```no_run
+/// use tunjukin_suhu_cpu_windows::{CpuTemperature, SensorProvider, VoltageProvider};
+///
+/// let cpu = CpuTemperature::get()?;
+/// for sensor in VoltageProvider.read_sensors()? {
+///     println!("{}: {:.3} V at {:.1}°C", sensor.name.unwrap_or_default(), sensor.value, cpu.celsius());
+/// }
+/// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VoltageProvider;
+
+impl SensorProvider for VoltageProvider {
+    fn read_sensors(&self) -> Result<Vec<SensorReading>, TemperatureError> {
+        for namespace in [LIBRE_HARDWARE_MONITOR_NAMESPACE, OPEN_HARDWARE_MONITOR_NAMESPACE] {
+            if let Ok(voltages) = CpuTemperature::run_powershell(&sensor_query(namespace, "Voltage")).and_then(|output| voltages_from(&output, "Value", 1.0)) {
+                return Ok(voltages);
+            }
+        }
+
+        voltages_from(&CpuTemperature::run_powershell(WIN32_VOLTAGE_PROBE_QUERY)?, "CurrentReading", 1000.0)
+    }
+}
+
+/// Parses query output listing each sensor's voltage as `property`, in volts
+/// times `per_volt`, failing if it lists no sensor with a voltage.
+fn voltages_from(output: &str, property: &str, per_volt: f64) -> Result<Vec<SensorReading>, TemperatureError> {
+    parse_records(output, |record| {
+        let value = sensor_value(record, property)?;
+        Some(SensorReading::new(SensorKind::Voltage, record_value(record, "Name").map(str::to_string), value / per_volt))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_voltages() {
+        let output = "Identifier : /intelcpu/0/voltage/0\r\nName : CPU Core\r\nValue : 1.187\r\n\r\n";
+
+        let voltages = voltages_from(output, "Value", 1.0).unwrap();
+        assert_eq!(voltages.len(), 1);
+        assert_eq!((voltages[0].kind, voltages[0].name.as_deref(), voltages[0].value), (SensorKind::Voltage, Some("CPU Core"), 1.187));
+        assert_eq!(voltages[0].kind.unit(), "V");
+        assert_eq!(voltages[0].temperature(), None);
+
+        let probes = voltages_from("Name : Voltage Probe\nCurrentReading : 3300\n", "CurrentReading", 1000.0).unwrap();
+        assert_eq!(probes[0].value, 3.3);
+    }
+
+    #[test]
+    fn test_voltage_errors() {
+        assert_eq!(voltages_from("", "Value", 1.0), Err(TemperatureError::NoSensors));
+        assert_eq!(voltages_from("Name : Voltage Probe\nCurrentReading :\n", "CurrentReading", 1000.0), Err(TemperatureError::NoValidReadings));
+    }
+}
@@ -0,0 +1,112 @@
+//! Readings of individual thermal zones.
+
+use std::time::{Instant, SystemTime};
+
+#[cfg(not(all(windows, feature = "native")))]
+use crate::ACPI_QUERY;
+use crate::{fallback, CpuTemperature, TemperatureError};
+
+/// The reading of one thermal zone.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThermalZoneReading {
+    /// The zone's WMI `InstanceName`, such as `ACPI\ThermalZone\TZ00_0`, or
+    /// `None` if WMI did not report one. Providers that do not read thermal
+    /// zones put their own sensor name here.
+    pub instance_name: Option<String>,
+    /// The zone's temperature.
+    pub temperature: CpuTemperature,
+    /// When the zone was read. Not serialized; deserialized readings are
+    /// stamped with the moment they were deserialized.
+    #[cfg_attr(feature = "serde", serde(skip, default = "Instant::now"))]
+    pub at: Instant,
+    /// When the zone was read, on the system clock.
+    pub time: SystemTime,
+}
+
+impl ThermalZoneReading {
+    /// Creates the reading of a zone taken now.
+    pub fn new(instance_name: Option<String>, temperature: CpuTemperature) -> Self {
+        ThermalZoneReading { instance_name, temperature, at: Instant::now(), time: SystemTime::now() }
+    }
+}
+
+impl CpuTemperature {
+    /// Reads every thermal zone that has a valid reading, in WMI order.
+    ///
+    /// Many machines expose several zones, for example `TZ00`, `TZ01` and a
+    /// skin-temperature zone, while [`CpuTemperature::get`] returns only the
+    /// first. Use the instance names to pick the zone that tracks the CPU.
+    ///
+    /// Zones whose reading is missing or fails the sanity check are skipped;
+    /// use [`CpuTemperature::get_all_optional`] to keep their positions.
+    ///
+    /// With the `native` feature, the zones are read through COM like
+    /// [`CpuTemperature::get`]. Like [`CpuTemperature::get`], this falls back
+    /// to `Win32_TemperatureProbe` and the sensors of a running hardware
+    /// monitoring tool when there are no usable ACPI zones.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the WMI query fails or no zone has a valid reading.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tunjukin_suhu_cpu_windows::CpuTemperature;
+    ///
+    /// for zone in CpuTemperature::get_all()? {
+    ///     println!(
+    ///         "{}: {:.1}°C",
+    ///         zone.instance_name.as_deref().unwrap_or("unnamed zone"),
+    ///         zone.temperature.celsius()
+    ///     );
+    /// }
+    /// # Ok::<(), tunjukin_suhu_cpu_windows::TemperatureError>(())
+    /// ```
+    pub fn get_all() -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        fallback::recover(Self::read_acpi_zones(), |provider| provider.read())
+    }
+
+    /// Reads every plausible ACPI thermal zone, without any fallback.
+    pub(crate) fn read_acpi_zones() -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        #[cfg(all(windows, feature = "native"))]
+        let zones = crate::NativeWmiProvider.read_zones()?;
+
+        #[cfg(not(all(windows, feature = "native")))]
+        let zones = Self::acpi_zones_with(Self::run_wmi_query)?;
+
+        Ok(zone_readings(zones))
+    }
+
+    /// Like [`Self::read_acpi_zones`], but gives up at `deadline`, killing
+    /// PowerShell or abandoning the COM query if it is still running.
+    pub(crate) fn read_acpi_zones_until(deadline: Instant) -> Result<Vec<ThermalZoneReading>, TemperatureError> {
+        #[cfg(all(windows, feature = "native"))]
+        let zones = crate::NativeWmiProvider.read_zones_until(Some(deadline))?;
+
+        #[cfg(not(all(windows, feature = "native")))]
+        let zones = Self::acpi_zones_with(|| Self::run_powershell_until(ACPI_QUERY, deadline))?;
+
+        Ok(zone_readings(zones))
+    }
+
+    /// Runs the ACPI query through `runner` and parses every plausible zone.
+    #[cfg(not(all(windows, feature = "native")))]
+    fn acpi_zones_with(runner: impl FnOnce() -> Result<String, TemperatureError>) -> Result<Vec<(Option<String>, Self)>, TemperatureError> {
+        let output = runner()?;
+        let zones = Self::parse_zones(&output);
+        if zones.is_empty() {
+            return Err(Self::no_readings_error(&output));
+        }
+        Ok(zones)
+    }
+}
+
+/// Pairs parsed zones with their instance names.
+fn zone_readings(zones: Vec<(Option<String>, CpuTemperature)>) -> Vec<ThermalZoneReading> {
+    zones
+        .into_iter()
+        .map(|(instance_name, temperature)| ThermalZoneReading::new(instance_name, temperature))
+        .collect()
+}